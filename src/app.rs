@@ -1,83 +1,1592 @@
-use crate::args::Cli;
-use crate::prints::DisplayType;
+use crate::args::{CargoView, Cli, ColorMode, Commands, FlatFormat};
+use crate::cargo;
+use crate::clipboard;
+use crate::comments::{self, Comments};
+use crate::completions;
+use crate::compression;
+use crate::config;
+use crate::datetime::{self, TimeZone};
+use crate::diff::{self, print_diff, print_side_by_side};
+use crate::embedded;
+use crate::emit;
+use crate::encoding;
+use crate::environment;
+use crate::exit::{ExitCode, ExitError};
+use crate::fetch;
+use crate::flatten;
+use crate::glob;
+use crate::har;
+use crate::hash;
+use crate::highlight::highlight;
+use crate::humanize;
+use crate::humanize::format_bytes;
+use crate::kubernetes;
+use crate::lockfile;
+use crate::manpage;
+use crate::merge;
+use crate::openapi;
+use crate::plugin::PluginProcessor;
+use crate::prints::{
+    Annotations, Heat, Hints, LineLimit, RenderOptions, print_root, print_truncation_marker,
+    print_violations,
+};
 use crate::processors::*;
+use crate::progress::Progress;
+use crate::query;
+use crate::repl;
+use crate::schema::{self, Violation};
+use crate::split;
+use crate::terminal;
+use crate::terraform;
+use crate::theme::{self, Background, Theme};
+use crate::timing;
 use crate::values::VizValue;
+use crate::verbose;
 use anyhow::{Result, anyhow, bail};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use colored::Colorize;
-use std::env::var;
+use encoding_rs::Encoding;
+use std::collections::BTreeMap;
+use std::collections::hash_map::RandomState;
+use std::env::{args_os, var, var_os};
 use std::fs;
-use std::io::{Read, stdin};
-use std::path::Path;
-use std::process::exit;
+use std::hash::BuildHasher;
+use std::io::{self, IsTerminal, Read, Write, stderr, stdin, stdout};
+use std::iter;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use vizo_core::{Format, VizError};
 
 pub fn run() -> Result<()> {
-    let cli = Cli::parse();
+    let cli = get_cli()?;
 
     configure_colors(&cli);
+    verbose::set_level(cli.verbose);
+    if cli.timing {
+        timing::enable();
+    }
+
+    let render_options = get_render_options(&cli)?;
+    let registry = ProcessorRegistry::default();
+    let options = get_parse_options(&cli);
+
+    if let Some(command) = &cli.command {
+        return run_command(&cli, command, &render_options, &registry, &options);
+    }
+    let paths = expand_paths(&cli, &registry)?;
+
+    if cli.watch {
+        let [path] = paths.as_slice() else {
+            bail!("watch mode requires a single file path");
+        };
+        // The document of the previous render, to highlight what changed since.
+        let mut previous: Option<VizValue<'static>> = None;
+        return crate::watch::watch(path, get_read_options(&cli), |contents, extension, out| {
+            if cli.highlight_only {
+                return print_highlighted(out, contents, &extension.name, &render_options);
+            }
+            let mut warnings = Vec::new();
+            let data = get_parsed_data(contents, extension, &registry, &options, &mut warnings)?;
+            let data = select_data(&cli, normalize_data(&cli, data)?)?;
+            let violations = get_violations(&cli, &data, &registry, &options)?;
+            let mut annotations = get_annotations(&cli, &data, &violations, &warnings);
+            if cli.diff {
+                let last = previous.replace(data.clone().into_owned());
+                if let Some(last) = &last {
+                    let changes = diff::diff(last, &data);
+                    if cli.changes_only {
+                        return print_changes(out, &changes, &render_options);
+                    }
+                    annotate_changes(&mut annotations, &changes);
+                }
+            }
+            let data = expand_data(&cli, data, &options);
+            let render_options = RenderOptions {
+                hints: get_hints(&cli, &data, Some(path)),
+                heat: get_heat(&cli, &data)?,
+                ..render_options.clone()
+            };
+            print_parsed_data(out, data, &render_options, annotations)?;
+            print_violations(out, &violations)?;
+            Ok(())
+        });
+    }
 
-    let (contents, extension) = get_content_and_extension(&cli)?;
-    let indent = get_indent(&cli)?;
-    let data = get_parsed_data(&contents, &extension)?;
+    // Listed files are viewed under their paths even if there is only one, so batches look alike.
+    let is_list = cli.files_from.is_some() || cli.null;
+    if paths.len() <= 1 && !is_list {
+        return view(
+            &cli,
+            paths.first().map(String::as_str),
+            &render_options,
+            &registry,
+            &options,
+        );
+    }
 
-    print_parsed_data(data, indent);
+    // Every file is viewed even if some of them fail, like `cat` does.
+    let mut failed: Option<(ExitCode, usize)> = None;
+    for (id, path) in paths.iter().enumerate() {
+        if !(cli.check && cli.quiet) {
+            let mut out = stdout().lock();
+            if id > 0 {
+                writeln!(out, "\n{}", "─".repeat(40).bright_black())?;
+            }
+            writeln!(out, "{}", path.cyan().bold())?;
+        }
+
+        if let Err(e) = view(&cli, Some(path), &render_options, &registry, &options) {
+            writeln!(stdout(), "{}: {}", "error".red().bold(), e)?;
+            failed.get_or_insert((ExitCode::of(&e), 0)).1 += 1;
+        }
+    }
+
+    if let Some((code, count)) = failed {
+        return Err(ExitError::new(
+            code,
+            anyhow!("{count} of {} files could not be viewed", paths.len()),
+        )
+        .into());
+    }
 
     Ok(())
 }
-fn configure_colors(cli: &Cli) {
-    let no_color = var("NO_COLOR");
 
-    if no_color.is_ok() {
-        colored::control::set_override(false);
+/// Width of the side-by-side view of `diff` if the width of the terminal is not known.
+const DEFAULT_SIDE_BY_SIDE_WIDTH: usize = 160;
+
+/// Runs a subcommand instead of viewing files.
+fn run_command(
+    cli: &Cli,
+    command: &Commands,
+    render_options: &RenderOptions,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+) -> Result<()> {
+    match command {
+        Commands::Completions { shell } => {
+            completions::generate(*shell, Cli::command(), &mut stdout().lock())?;
+        }
+        Commands::Man => manpage::generate(&mut Cli::command(), &mut stdout().lock())?,
+        Commands::Diff {
+            old,
+            new,
+            old_language,
+            new_language,
+            side_by_side,
+            patch,
+            merge_patch,
+            exit_code,
+        } => {
+            if old == "-" && new == "-" {
+                bail!("only one of the documents can be read from stdin");
+            }
+            let language = |language: &Option<String>| language.clone().or(cli.language.clone());
+            let (old_data, old_format) =
+                read_document(cli, old, language(old_language), registry, options)?;
+            let (new_data, new_format) =
+                read_document(cli, new, language(new_language), registry, options)?;
+            let changes = diff::diff(&old_data, &new_data);
+
+            let mut out = stdout().lock();
+            if *patch || *merge_patch {
+                let document = if *patch {
+                    diff::to_json_patch(&changes)
+                } else {
+                    let mut warnings = Vec::new();
+                    let document = diff::to_merge_patch(&old_data, &new_data, &mut warnings);
+                    if !cli.quiet {
+                        print_warnings(&warnings);
+                    }
+                    document
+                };
+                out.write_all(emit::to_json(&document)?.as_bytes())?;
+            } else if changes.is_empty() {
+                if !cli.quiet {
+                    writeln!(out, "{}", "no differences".bright_black())?;
+                }
+            } else {
+                // The formats are only worth mentioning when they differ.
+                let header = |path: &str, format: &str| {
+                    let path = if path == "-" { "stdin" } else { path };
+                    if old_format == new_format {
+                        (path.chars().count(), path.bold().to_string())
+                    } else {
+                        let format = format!("({format})");
+                        let width = path.chars().count() + 1 + format.chars().count();
+                        (width, format!("{} {}", path.bold(), format.bright_black()))
+                    }
+                };
+                let (old_width, old_header) = header(old, &old_format);
+                let (_, new_header) = header(new, &new_format);
+                if *side_by_side {
+                    let width = terminal::width().unwrap_or(DEFAULT_SIDE_BY_SIDE_WIDTH);
+                    // Aligned with the lines, which start after a marker and a space.
+                    let padding = (width.saturating_sub(3) / 2).saturating_sub(old_width + 2);
+                    writeln!(
+                        out,
+                        "  {old_header}{}     {new_header}",
+                        " ".repeat(padding)
+                    )?;
+                    print_side_by_side(&mut out, &old_data, &new_data, width, render_options)?;
+                } else {
+                    writeln!(out, "{} {old_header}", "---".red().bold())?;
+                    writeln!(out, "{} {new_header}", "+++".green().bold())?;
+                    print_diff(&mut out, &changes, render_options)?;
+                }
+            }
+
+            if *exit_code && !changes.is_empty() {
+                return Err(
+                    ExitError::new(ExitCode::NoMatch, anyhow!("the documents differ")).into(),
+                );
+            }
+        }
+        Commands::Merge {
+            paths,
+            arrays,
+            to,
+            keep_comments,
+        } => {
+            if paths.iter().filter(|path| *path == "-").count() > 1 {
+                bail!("only one of the documents can be read from stdin");
+            }
+            let mut merged = VizValue::Object(Default::default());
+            // Like values, comments of later documents win.
+            let mut comments = Comments::new();
+            for path in paths {
+                let language = cli.language.clone();
+                let (data, language, contents) =
+                    read_document_with_contents(cli, path, language, registry, options)?;
+                if *keep_comments {
+                    comments.extend(get_comments(&contents, &language));
+                }
+                merge::merge(&mut merged, data, *arrays);
+            }
+            print_result(&merged, *to, &comments, render_options)?;
+        }
+        Commands::Sort {
+            path,
+            by,
+            desc,
+            at,
+            to,
+            keep_comments,
+        } => {
+            let language = cli.language.clone();
+            let (mut data, language, contents) =
+                read_document_with_contents(cli, path, language, registry, options)?;
+            let mut comments = Comments::new();
+            if *keep_comments {
+                // Comments inside of the array would end up at other elements once it is sorted.
+                let inside = format!("{at}/");
+                comments = get_comments(&contents, &language);
+                comments.retain(|path, _| !path.starts_with(&inside));
+            }
+            let Some(VizValue::Array(vec)) = data.pointer_mut(at).map(VizValue::untagged_mut)
+            else {
+                bail!(
+                    "{} is not an array",
+                    if at.is_empty() { "the document" } else { at }
+                );
+            };
+            sort_array(vec, by.as_deref(), *desc);
+            print_result(&data, *to, &comments, render_options)?;
+        }
+        Commands::Flatten { path, to } => {
+            let (data, _) = read_document(cli, path, cli.language.clone(), registry, options)?;
+            let flat = flatten::flatten(&data);
+            if *to == Some(FlatFormat::Properties) {
+                let properties = flatten::to_properties(&flat);
+                stdout().lock().write_all(properties.as_bytes())?;
+                return Ok(());
+            }
+
+            let flat = VizValue::Object(flat.into_iter().map(|(k, v)| (k.into(), v)).collect());
+            match to {
+                Some(FlatFormat::Data(format)) => {
+                    print_result(&flat, Some(*format), &Comments::new(), render_options)?
+                }
+                // The paths are not identifiers, but quoting all of them would only add noise.
+                _ => {
+                    let options = RenderOptions {
+                        bare_keys: true,
+                        ..render_options.clone()
+                    };
+                    print_result(&flat, None, &Comments::new(), &options)?;
+                }
+            }
+        }
+        Commands::Hash {
+            paths,
+            algorithm,
+            canonical,
+        } => {
+            if paths.iter().filter(|path| *path == "-").count() > 1 {
+                bail!("only one of the documents can be read from stdin");
+            }
+            let mut out = stdout().lock();
+            for path in paths {
+                let (data, _) = read_document(cli, path, cli.language.clone(), registry, options)?;
+                if *canonical {
+                    writeln!(out, "{}", hash::canonical(&data))?;
+                } else {
+                    // The layout of `sha256sum`, so the output can be compared the same way.
+                    writeln!(out, "{}  {path}", hash::hash(&data, *algorithm))?;
+                }
+            }
+        }
+        Commands::Sample {
+            path,
+            n,
+            random,
+            seed,
+            to,
+        } => {
+            let (data, _) = read_document(cli, path, cli.language.clone(), registry, options)?;
+            let VizValue::Array(vec) = data.untagged() else {
+                bail!("the document is not an array");
+            };
+            let total = vec.len();
+            let sample = if *random {
+                let seed = seed.unwrap_or_else(|| RandomState::new().hash_one(Instant::now()));
+                sample_random(vec, *n, seed)
+            } else {
+                vec.iter().take(*n).cloned().collect()
+            };
+
+            let count = sample.len();
+            print_result(
+                &VizValue::Array(sample),
+                *to,
+                &Comments::new(),
+                render_options,
+            )?;
+            // A note would break the document in other formats.
+            if to.is_none() && count < total && !cli.quiet {
+                let note = format!("... {count} of {total} elements");
+                writeln!(stdout(), "{}", note.bright_black())?;
+            }
+        }
+        Commands::Cargo { view, args, to } => {
+            let contents = cargo::metadata(args)?;
+            let metadata = get_parsed_data(
+                &contents,
+                &Language::given("json"),
+                registry,
+                options,
+                &mut Vec::new(),
+            )?;
+            match view {
+                CargoView::Metadata => {
+                    print_result(&metadata, *to, &Comments::new(), render_options)?
+                }
+                // Names of packages are not identifiers, and most of them have no dependencies.
+                CargoView::Tree => {
+                    let options = RenderOptions {
+                        bare_keys: true,
+                        compact_empty: true,
+                        ..render_options.clone()
+                    };
+                    print_result(&cargo::tree(&metadata)?, *to, &Comments::new(), &options)?;
+                }
+            }
+        }
+        Commands::Env {
+            grep,
+            redact,
+            split_paths,
+            to,
+        } => {
+            let variables = environment::variables(grep.as_deref(), *redact, *split_paths);
+            print_result(&variables, *to, &Comments::new(), render_options)?;
+        }
+        Commands::Repl { path } => {
+            // Queries are read from stdin, so the document cannot be.
+            if path == "-" {
+                bail!("the document of the REPL cannot be read from stdin");
+            }
+            let (data, _) = read_document(cli, path, cli.language.clone(), registry, options)?;
+            repl::run(&data, render_options)?;
+        }
+        Commands::Validate { schema, paths } => validate(cli, schema, paths, registry, options)?,
+        Commands::Unflatten { path, to } => {
+            // Properties are not a language for viewing, so their extension is not known otherwise.
+            let is_properties = Path::new(path)
+                .extension()
+                .is_some_and(|ext| ext == "properties");
+            let language = match &cli.language {
+                None if is_properties => Some("properties".to_string()),
+                language => language.clone(),
+            };
+            let mut warnings = Vec::new();
+            let (contents, language) = read_document_text(cli, path, language, &mut warnings)?;
+            let flat = if language.name == "properties" {
+                flatten::parse_properties(&contents)?
+            } else {
+                match &mut get_parsed_data(&contents, &language, registry, options, &mut warnings)?
+                {
+                    VizValue::Object(map) => mem::take(map)
+                        .into_iter()
+                        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                        .collect(),
+                    _ => bail!("the document is not an object of flat paths"),
+                }
+            };
+            if !cli.quiet {
+                print_warnings(&warnings);
+            }
+            print_result(
+                &flatten::unflatten(flat)?,
+                *to,
+                &Comments::new(),
+                render_options,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates every document against the schema and prints whether it matches.
+///
+/// Fails with the code of the first document that failed: [`ExitCode::NoMatch`] if it does not
+/// match, or the code of the error if it could not be read.
+fn validate(
+    cli: &Cli,
+    schema_path: &str,
+    paths: &[String],
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+) -> Result<()> {
+    let schema = load_schema(cli, schema_path, registry, options)?;
+    // Directories are walked, since validating all configs of a project is the common case.
+    let paths = expand_paths(
+        &Cli {
+            paths: paths.to_vec(),
+            extra_paths: Vec::new(),
+            recursive: true,
+            ..cli.clone()
+        },
+        registry,
+    )?;
+    if paths.iter().filter(|path| *path == "-").count() > 1 {
+        bail!("only one of the documents can be read from stdin");
+    }
+
+    let mut out = stdout().lock();
+    let mut failed: Option<(ExitCode, usize)> = None;
+    for path in &paths {
+        let name = if path == "-" { "stdin" } else { path };
+        let violations = match read_document(cli, path, cli.language.clone(), registry, options) {
+            Ok((data, _)) => schema::validate(&schema, &data),
+            Err(e) => {
+                writeln!(out, "{} {}: {e}", "error".red().bold(), name.bold())?;
+                failed.get_or_insert((ExitCode::of(&e), 0)).1 += 1;
+                continue;
+            }
+        };
+
+        if violations.is_empty() {
+            if !cli.quiet {
+                writeln!(out, "{} {}", "pass".green().bold(), name.bold())?;
+            }
+            continue;
+        }
+        writeln!(out, "{} {}", "fail".red().bold(), name.bold())?;
+        for violation in &violations {
+            let path = if violation.path.is_empty() {
+                "/"
+            } else {
+                &violation.path
+            };
+            writeln!(out, "  {}: {}", path.blue(), violation.message)?;
+        }
+        failed.get_or_insert((ExitCode::NoMatch, 0)).1 += 1;
+    }
+
+    if let Some((code, count)) = failed {
+        return Err(ExitError::new(
+            code,
+            anyhow!("{count} of {} files are not valid", paths.len()),
+        )
+        .into());
+    }
+    if !cli.quiet {
+        let summary = match paths.len() {
+            1 => "the file is valid".to_string(),
+            count => format!("all {count} files are valid"),
+        };
+        writeln!(out, "{}", summary.bright_black())?;
+    }
+    Ok(())
+}
+
+/// Picks `count` random elements of the array, keeping their order.
+///
+/// Every element is picked with the same chance (Knuth's selection sampling), and the same
+/// seed picks the same elements.
+fn sample_random<'a>(vec: &[VizValue<'a>], count: usize, seed: u64) -> Vec<VizValue<'a>> {
+    // SplitMix64, which is plenty for picking elements to look at.
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    };
+
+    let mut sample = Vec::with_capacity(count.min(vec.len()));
+    for (id, value) in vec.iter().enumerate() {
+        let needed = count - sample.len();
+        if needed == 0 {
+            break;
+        }
+        let left = (vec.len() - id) as u64;
+        if next() % left < needed as u64 {
+            sample.push(value.clone());
+        }
+    }
+    sample
+}
+
+/// Sorts the elements of an array, or objects by their field.
+///
+/// The sort is stable, and objects without the field are kept at the end in both orders.
+fn sort_array(vec: &mut [VizValue], field: Option<&str>, descending: bool) {
+    fn key<'v, 'a>(value: &'v VizValue<'a>, field: Option<&str>) -> Option<&'v VizValue<'a>> {
+        match (field, value.untagged()) {
+            (None, value) => Some(value),
+            (Some(field), VizValue::Object(map)) => map.get(field),
+            (Some(_), _) => None,
+        }
+    }
+
+    vec.sort_by(|a, b| match (key(a, field), key(b, field)) {
+        (Some(a), Some(b)) if descending => b.compare(a),
+        (Some(a), Some(b)) => a.compare(b),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+}
+
+/// Prints the result of a subcommand, serialized in the format if there is one.
+///
+/// The comments are inserted before their keys, if the format has comments.
+fn print_result(
+    data: &VizValue,
+    format: Option<Format>,
+    comments: &Comments,
+    options: &RenderOptions,
+) -> Result<()> {
+    let mut out = stdout().lock();
+    match format {
+        Some(format) => {
+            // The input was fine, it just cannot be written in the format, like nulls in TOML.
+            let text =
+                emit::to_format(data, format).map_err(|e| ExitError::new(ExitCode::Failure, e))?;
+            let text = if comments.is_empty() {
+                text
+            } else {
+                comments::insert(&text, format, comments)
+            };
+            out.write_all(text.as_bytes())?;
+        }
+        None => print_parsed_data(&mut out, data.clone(), options, Annotations::new())?,
+    }
+    Ok(())
+}
+
+/// Reads and parses a whole document for a subcommand, where `-` is stdin.
+///
+/// Returns the document with the language it was parsed as. The language is taken
+/// from `language`, or from the extension or the content of the document.
+fn read_document(
+    cli: &Cli,
+    path: &str,
+    language: Option<String>,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+) -> Result<(VizValue<'static>, String)> {
+    let (data, language, _) = read_document_with_contents(cli, path, language, registry, options)?;
+    Ok((data, language))
+}
+
+/// Reads and parses a whole document like [`read_document`], and also returns its contents.
+fn read_document_with_contents(
+    cli: &Cli,
+    path: &str,
+    language: Option<String>,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+) -> Result<(VizValue<'static>, String, String)> {
+    let mut warnings = Vec::new();
+    let started = Instant::now();
+    let (contents, language) = read_document_text(cli, path, language, &mut warnings)?;
+    timing::record("read", started.elapsed());
+    let started = Instant::now();
+    let data = get_parsed_data(&contents, &language, registry, options, &mut warnings)?;
+    let data = normalize_data(cli, data)?.into_owned();
+    timing::record("parse", started.elapsed());
+    record_size(&contents, &data);
+    if !cli.quiet {
+        print_warnings(&warnings);
+    }
+    Ok((data, language.name, contents))
+}
+
+/// Records the size of a document for `--timing`.
+fn record_size(contents: &str, data: &VizValue) {
+    // Counting the values takes a walk over the whole document, so it is only done on request.
+    if timing::enabled() {
+        let mut nodes = 0;
+        data.walk(|_, _| nodes += 1);
+        timing::record_size(contents.len(), nodes);
+    }
+}
+
+/// Collects the comments of a document to carry them over to the result of a subcommand.
+fn get_comments(contents: &str, language: &str) -> Comments {
+    match Format::from_name(language) {
+        Some(format) => comments::extract(contents, format),
+        None => Comments::new(),
+    }
+}
+
+/// Reads a whole document for a subcommand without parsing it, where `-` is stdin.
+///
+/// Returns the contents of the document with its language, like [`read_document`].
+fn read_document_text(
+    cli: &Cli,
+    path: &str,
+    language: Option<String>,
+    warnings: &mut Vec<Warning>,
+) -> Result<(String, Language)> {
+    let cli = &Cli {
+        language,
+        ..cli.clone()
+    };
+    let path = Some(path).filter(|path| *path != "-");
+    let (contents, mut language) =
+        get_content_and_extension(cli, path, &Progress::hidden(), warnings)?;
+    // Both extensions of YAML are the same language.
+    if language.name == "yml" {
+        language.name = "yaml".to_string();
+    }
+    Ok((contents, language))
+}
+
+/// Parses the arguments on top of the defaults from the config file and `VIZ_*` variables.
+fn get_cli() -> Result<Cli> {
+    let cli = Cli::parse();
+
+    let env_args = config::env_args()?;
+    let no_config = cli.no_config || env_args.iter().any(|arg| arg == "--no-config");
+    let mut defaults = if no_config {
+        Vec::new()
     } else {
-        colored::control::set_override(!cli.no_color);
+        config::load()?
+    };
+    defaults.extend(env_args);
+    if defaults.is_empty() {
+        return Ok(cli);
     }
+
+    let mut args = args_os();
+    let program = args.next().unwrap_or_else(|| "vizo".into());
+    Ok(Cli::parse_from(
+        iter::once(program)
+            .chain(defaults.into_iter().map(Into::into))
+            .chain(args),
+    ))
 }
 
-fn get_content_and_extension(cli: &Cli) -> Result<(String, String)> {
-    let file_path = cli.path.clone().unwrap_or_default();
+/// Expands glob patterns and, with `--recursive`, directories into paths of files.
+fn expand_paths(cli: &Cli, registry: &ProcessorRegistry) -> Result<Vec<String>> {
+    let supported = glob::Supported::new(registry, &cli.mappings);
+    let mut paths = Vec::new();
 
-    if file_path.is_empty() {
-        get_from_stdin(cli)
+    for path in cli.paths() {
+        if fetch::is_url(path) {
+            paths.push(path.to_string());
+            continue;
+        }
+        // Existing files are taken as is, even if their names look like patterns.
+        let files = if glob::is_pattern(path) && !Path::new(path).exists() {
+            glob::expand(path, &supported)?
+        } else if cli.recursive && Path::new(path).is_dir() {
+            let files = glob::walk(Path::new(path), &supported);
+            if files.is_empty() {
+                bail!("no supported files in {path}");
+            }
+            files
+        } else {
+            paths.push(path.to_string());
+            continue;
+        };
+        paths.extend(files.iter().map(|file| file.to_string_lossy().into_owned()));
+    }
+    if cli.files_from.is_some() || cli.null {
+        paths.extend(read_path_list(cli)?);
+    }
+
+    Ok(paths)
+}
+
+/// Reads the paths of `--files-from` and `-0`, which are taken as they are, without patterns.
+///
+/// Reading the paths instead of taking them as arguments avoids the limit on the length of
+/// the command line, so a list can have any number of files.
+fn read_path_list(cli: &Cli) -> Result<Vec<String>> {
+    let source = cli.files_from.as_deref().unwrap_or("-");
+    let bytes = if source == "-" {
+        let mut bytes = Vec::new();
+        stdin()
+            .lock()
+            .read_to_end(&mut bytes)
+            .map_err(|e| anyhow!("failed to read the paths from stdin: {e}"))?;
+        bytes
     } else {
-        get_file_content(&file_path)
+        fs::read(source).map_err(|e| anyhow!("failed to read the paths from {source}: {e}"))?
+    };
+
+    let separator = if cli.null { b'\0' } else { b'\n' };
+    let paths: Vec<String> = bytes
+        .split(|byte| *byte == separator)
+        .map(|path| {
+            path.strip_suffix(b"\r")
+                .filter(|_| !cli.null)
+                .unwrap_or(path)
+        })
+        .filter(|path| !path.is_empty())
+        .map(|path| {
+            String::from_utf8(path.to_vec()).map_err(|_| {
+                anyhow!(
+                    "the path {} is not valid UTF-8",
+                    String::from_utf8_lossy(path)
+                )
+            })
+        })
+        .collect::<Result<_>>()?;
+    if paths.is_empty() {
+        let source = if source == "-" { "stdin" } else { source };
+        bail!("there are no paths in {source}");
+    }
+    Ok(paths)
+}
+
+/// Reads, parses and prints a single file, or stdin if there is no path.
+fn view(
+    cli: &Cli,
+    path: Option<&str>,
+    render_options: &RenderOptions,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+) -> Result<()> {
+    // The progress indicator would be drawn over the logs.
+    let mut progress = if cli.quiet || verbose::enabled(1) {
+        Progress::hidden()
+    } else {
+        Progress::start()
+    };
+    let mut warnings = Vec::new();
+    let started = Instant::now();
+    let (contents, extension) = get_content_and_extension(cli, path, &progress, &mut warnings)?;
+    timing::record("read", started.elapsed());
+    verbose::log(
+        1,
+        format_args!(
+            "read {} from {} in {}",
+            format_bytes(contents.len() as u64),
+            get_source_name(cli, path),
+            verbose::format_duration(started.elapsed())
+        ),
+    );
+    if cli.highlight_only {
+        progress.finish();
+        if !cli.quiet {
+            print_warnings(&warnings);
+        }
+        return print_highlighted(
+            &mut stdout().lock(),
+            &contents,
+            &extension.name,
+            render_options,
+        );
+    }
+
+    if cli.split || cli.k8s || cli.k8s_summary {
+        progress.finish();
+        if !cli.quiet {
+            print_warnings(&warnings);
+        }
+        if cli.k8s_summary {
+            return summarize_objects(&contents, &extension, registry, options);
+        }
+        return view_documents(
+            cli,
+            path,
+            &contents,
+            &extension,
+            render_options,
+            registry,
+            options,
+        );
+    }
+    if cli.summary {
+        progress.finish();
+        return summarize_har(cli, &contents, &extension, registry, options, warnings);
+    }
+    if cli.lock_summary {
+        progress.finish();
+        return summarize_lockfile(cli, &contents, &extension, registry, options, warnings);
+    }
+    view_document(
+        cli,
+        path,
+        &contents,
+        &extension,
+        &mut progress,
+        warnings,
+        render_options,
+        registry,
+        options,
+    )
+}
+
+/// Splits the input into documents, like the ones of `kubectl get -o yaml`, and views each of them.
+///
+/// Every document is viewed even if some of them fail, like the files are.
+fn view_documents(
+    cli: &Cli,
+    path: Option<&str>,
+    contents: &str,
+    extension: &Language,
+    render_options: &RenderOptions,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+) -> Result<()> {
+    let delimiter = cli
+        .delimiter
+        .as_deref()
+        .map(|delimiter| delimiter.replace("\\0", "\0"));
+    let documents = split::split_documents(contents, &extension.name, delimiter.as_deref());
+
+    let mut failed: Option<(ExitCode, usize)> = None;
+    for (id, document) in documents.iter().enumerate() {
+        if !(cli.check && cli.quiet) {
+            let mut out = stdout().lock();
+            if id > 0 {
+                writeln!(out, "\n{}", "─".repeat(40).bright_black())?;
+            }
+            let header = get_document_header(cli, id, document, extension, registry, options);
+            writeln!(out, "{}", header.cyan().bold())?;
+        }
+
+        let progress = &mut Progress::hidden();
+        let result = view_document(
+            cli,
+            path,
+            document,
+            extension,
+            progress,
+            Vec::new(),
+            render_options,
+            registry,
+            options,
+        );
+        if let Err(e) = result {
+            writeln!(stdout(), "{}: {}", "error".red().bold(), e)?;
+            failed.get_or_insert((ExitCode::of(&e), 0)).1 += 1;
+        }
+    }
+
+    if let Some((code, count)) = failed {
+        return Err(ExitError::new(
+            code,
+            anyhow!(
+                "{count} of {} documents could not be viewed",
+                documents.len()
+            ),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Returns the header of a document of `--split`, which is the identity of the object for `--k8s`.
+fn get_document_header(
+    cli: &Cli,
+    id: usize,
+    document: &str,
+    extension: &Language,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+) -> String {
+    // Errors and warnings are reported when the document is viewed.
+    if cli.k8s
+        && let Ok(data) = get_parsed_data(document, extension, registry, options, &mut Vec::new())
+        && let Some(object) = kubernetes::identify(&data)
+    {
+        return object.to_string();
+    }
+    format!("document {}", id + 1)
+}
+
+/// Prints the kind, name, replicas and images of every Kubernetes object in the input as a table.
+fn summarize_objects(
+    contents: &str,
+    extension: &Language,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+) -> Result<()> {
+    let mut rows = Vec::new();
+    let mut failed: Option<(ExitCode, usize)> = None;
+    let documents = split::split_documents(contents, &extension.name, None);
+    for document in &documents {
+        let mut warnings = Vec::new();
+        let data = match get_parsed_data(document, extension, registry, options, &mut warnings) {
+            Ok(data) => data,
+            Err(e) => {
+                writeln!(stdout(), "{}: {}", "error".red().bold(), e)?;
+                failed.get_or_insert((ExitCode::of(&e), 0)).1 += 1;
+                continue;
+            }
+        };
+        for object in kubernetes::objects(&data) {
+            let Some(identity) = kubernetes::identify(object) else {
+                continue;
+            };
+            let name = match identity.namespace {
+                Some(namespace) => format!("{namespace}/{}", identity.name.unwrap_or("")),
+                None => identity.name.unwrap_or("").to_string(),
+            };
+            let replicas = kubernetes::replicas(object).map_or("-".to_string(), |r| r.to_string());
+            let images = kubernetes::images(object);
+            let images = if images.is_empty() {
+                "-".to_string()
+            } else {
+                images.join(", ")
+            };
+            rows.push([identity.kind.to_string(), name, replicas, images]);
+        }
+    }
+    if rows.is_empty() && failed.is_none() {
+        return Err(ExitError::new(
+            ExitCode::NoMatch,
+            anyhow!("there are no Kubernetes objects in the input"),
+        )
+        .into());
+    }
+
+    print_table(
+        &mut stdout().lock(),
+        ["KIND", "NAME", "REPLICAS", "IMAGES"],
+        &rows,
+        |_| false,
+    )?;
+
+    if let Some((code, failed)) = failed {
+        return Err(ExitError::new(
+            code,
+            anyhow!(
+                "{failed} of {} documents could not be parsed",
+                documents.len()
+            ),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Prints the entries of a HAR capture as a table of their methods, statuses, times and URLs.
+///
+/// The numbers of the entries are the ones that `--entry` takes.
+fn summarize_har(
+    cli: &Cli,
+    contents: &str,
+    extension: &Language,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+    mut warnings: Vec<Warning>,
+) -> Result<()> {
+    let data = get_parsed_data(contents, extension, registry, options, &mut warnings)?;
+    if !cli.quiet {
+        print_warnings(&warnings);
+    }
+    let Some(entries) = har::entries(&data) else {
+        bail!("`--summary` needs a HAR capture, which has its requests in `log.entries`");
+    };
+
+    let rows: Vec<[String; 5]> = entries
+        .iter()
+        .enumerate()
+        .map(|(id, entry)| {
+            let entry = har::entry(entry);
+            [
+                (id + 1).to_string(),
+                entry.method.to_string(),
+                entry
+                    .status
+                    .map_or("-".to_string(), |status| status.to_string()),
+                entry.time.map_or("-".to_string(), |time| {
+                    verbose::format_duration(Duration::from_secs_f64(time / 1000.0))
+                }),
+                entry.url.to_string(),
+            ]
+        })
+        .collect();
+    print_table(
+        &mut stdout().lock(),
+        ["#", "METHOD", "STATUS", "TIME", "URL"],
+        &rows,
+        |_| false,
+    )?;
+    Ok(())
+}
+
+/// Prints the packages of a lockfile as a table of their names and versions.
+///
+/// Packages with several versions are highlighted, as they are built more than once.
+fn summarize_lockfile(
+    cli: &Cli,
+    contents: &str,
+    extension: &Language,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+    mut warnings: Vec<Warning>,
+) -> Result<()> {
+    let data = get_parsed_data(contents, extension, registry, options, &mut warnings)?;
+    if !cli.quiet {
+        print_warnings(&warnings);
+    }
+    let Some(packages) = lockfile::packages(&data) else {
+        bail!("`--lock-summary` needs a Cargo.lock, package-lock.json or poetry.lock");
+    };
+
+    let mut versions: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, version) in packages {
+        let versions = versions.entry(name).or_default();
+        if !versions.contains(&version) {
+            versions.push(version);
+        }
+    }
+    let duplicates = versions
+        .values()
+        .filter(|versions| versions.len() > 1)
+        .count();
+    let rows: Vec<[String; 2]> = versions
+        .into_iter()
+        .map(|(name, versions)| [name, versions.join(", ")])
+        .collect();
+
+    let mut out = stdout().lock();
+    print_table(&mut out, ["NAME", "VERSION"], &rows, |[_, versions]| {
+        versions.contains(", ")
+    })?;
+    if !cli.quiet {
+        let note = format!(
+            "{} packages, {duplicates} of them with several versions",
+            rows.len()
+        );
+        writeln!(out, "{}", note.bright_black())?;
+    }
+    Ok(())
+}
+
+/// Replaces the document with the part of it that was asked for, like an entry of a HAR capture.
+fn select_data<'a>(cli: &Cli, data: VizValue<'a>) -> Result<VizValue<'a>> {
+    let data = select_resources(cli, select_entry(cli, data)?)?;
+    if cli.openapi {
+        if !openapi::is_openapi(&data) {
+            bail!(
+                "the document is not an OpenAPI document, which has an `openapi` or `swagger` version"
+            );
+        }
+        return Ok(openapi::overview(&data));
+    }
+    Ok(data)
+}
+
+/// Replaces a HAR capture with one of its entries for `--entry`, counted from 1.
+fn select_entry<'a>(cli: &Cli, data: VizValue<'a>) -> Result<VizValue<'a>> {
+    let Some(number) = cli.entry else {
+        return Ok(data);
+    };
+    let Some(entries) = har::entries(&data) else {
+        bail!("`--entry` needs a HAR capture, which has its requests in `log.entries`");
+    };
+    match number.checked_sub(1).and_then(|id| entries.get(id)) {
+        Some(entry) => Ok(entry.clone()),
+        None => bail!(
+            "there is no entry {number}, the capture has {} entries",
+            entries.len()
+        ),
+    }
+}
+
+/// Replaces a Terraform state with its resources for `--tfstate` and `--get`.
+fn select_resources<'a>(cli: &Cli, data: VizValue<'a>) -> Result<VizValue<'a>> {
+    if !cli.tfstate && cli.get.is_none() {
+        return Ok(data);
+    }
+    let Some(resources) = terraform::resources(&data) else {
+        bail!(
+            "the document is not a Terraform state, which has `terraform_version` and `resources`"
+        );
+    };
+    let Some(address) = &cli.get else {
+        return Ok(terraform::summarize(resources));
+    };
+    let selected = terraform::select(resources, address);
+    if matches!(&selected, VizValue::Object(map) if map.is_empty()) {
+        bail!("there are no resources at `{address}` in the state");
+    }
+    Ok(selected)
+}
+
+/// Prints rows as a table with a bold header, padding every column but the last one.
+///
+/// Rows that `is_highlighted` picks are printed in yellow.
+fn print_table<const N: usize>(
+    out: &mut impl Write,
+    header: [&str; N],
+    rows: &[[String; N]],
+    is_highlighted: impl Fn(&[String; N]) -> bool,
+) -> io::Result<()> {
+    let widths = std::array::from_fn::<usize, N, _>(|column| {
+        rows.iter()
+            .map(|row| row[column].chars().count())
+            .chain([header[column].len()])
+            .max()
+            .unwrap_or(0)
+    });
+    let line = |cells: [&str; N]| {
+        let mut line = String::new();
+        for (column, cell) in cells.iter().enumerate() {
+            if column + 1 == N {
+                line.push_str(cell);
+            } else {
+                line.push_str(&format!("{cell:<width$}  ", width = widths[column]));
+            }
+        }
+        line
+    };
+    writeln!(out, "{}", line(header).bold())?;
+    for row in rows {
+        let line = line(row.each_ref().map(String::as_str));
+        if is_highlighted(row) {
+            writeln!(out, "{}", line.yellow())?;
+        } else {
+            writeln!(out, "{line}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Parses, validates and prints a document that was read.
+#[allow(clippy::too_many_arguments)]
+fn view_document(
+    cli: &Cli,
+    path: Option<&str>,
+    contents: &str,
+    extension: &Language,
+    progress: &mut Progress,
+    mut warnings: Vec<Warning>,
+    render_options: &RenderOptions,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+) -> Result<()> {
+    progress.set_phase("parsing");
+    let started = Instant::now();
+    let data = get_parsed_data(contents, extension, registry, options, &mut warnings)?;
+    let data = select_data(cli, normalize_data(cli, data)?)?;
+    timing::record("parse", started.elapsed());
+    record_size(contents, &data);
+    verbose::log(
+        1,
+        format_args!("parsed in {}", verbose::format_duration(started.elapsed())),
+    );
+    progress.set_phase("validating");
+    let started = Instant::now();
+    let violations = get_violations(cli, &data, registry, options)?;
+    if cli.schema.is_some() {
+        timing::record("validate", started.elapsed());
+        verbose::log(
+            1,
+            format_args!(
+                "validated in {}",
+                verbose::format_duration(started.elapsed())
+            ),
+        );
+    }
+    progress.finish();
+
+    if !cli.quiet {
+        print_warnings(&warnings);
+    }
+
+    let mut out = stdout().lock();
+    if !cli.check {
+        let annotations = get_annotations(cli, &data, &violations, &warnings);
+        let started = Instant::now();
+        let data = expand_data(cli, data, options);
+        let render_options = &RenderOptions {
+            hints: get_hints(cli, &data, path),
+            heat: get_heat(cli, &data)?,
+            ..render_options.clone()
+        };
+        print_parsed_data(&mut out, data, render_options, annotations)?;
+        timing::record("render", started.elapsed());
+        verbose::log(
+            1,
+            format_args!(
+                "rendered in {}",
+                verbose::format_duration(started.elapsed())
+            ),
+        );
+    }
+
+    if !violations.is_empty() {
+        print_violations(&mut out, &violations)?;
+        return Err(ExitError::new(
+            ExitCode::NoMatch,
+            anyhow!("document does not match the schema"),
+        )
+        .into());
+    }
+
+    if cli.check && !cli.quiet {
+        let source = path.unwrap_or("stdin");
+        writeln!(out, "{}: {source} is valid", "ok".green().bold())?;
+    }
+
+    Ok(())
+}
+
+/// Colors are only used when the output is a terminal, unless they are forced or disabled.
+fn configure_colors(cli: &Cli) {
+    let no_color = var("NO_COLOR");
+
+    let enabled = match cli.color_mode() {
+        _ if no_color.is_ok() => false,
+        ColorMode::Auto => stdout().is_terminal(),
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+    };
+    colored::control::set_override(enabled);
+}
+
+fn get_content_and_extension(
+    cli: &Cli,
+    path: Option<&str>,
+    progress: &Progress,
+    warnings: &mut Vec<Warning>,
+) -> Result<(String, Language)> {
+    if cli.clipboard {
+        return get_from_clipboard(cli, progress, warnings);
+    }
+    let Some(file_path) = path.filter(|path| !path.is_empty()) else {
+        return get_from_stdin(cli, progress, warnings);
+    };
+    if fetch::is_url(file_path) {
+        return get_from_url(cli, file_path, progress, warnings);
+    }
+
+    let (contents, ext) = get_file_content(file_path, &get_read_options(cli), progress, warnings)?;
+    Ok((contents, get_language(cli, || ext)))
+}
+
+/// Returns the name of the input for logs.
+fn get_source_name(cli: &Cli, path: Option<&str>) -> String {
+    match path.filter(|path| !path.is_empty()) {
+        _ if cli.clipboard => "the clipboard".to_string(),
+        Some(path) => path.to_string(),
+        None => "stdin".to_string(),
     }
 }
 
-fn get_from_stdin(cli: &Cli) -> Result<(String, String)> {
-    let mut contents = String::new();
-    stdin()
-        .read_to_string(&mut contents)
+fn get_from_stdin(
+    cli: &Cli,
+    progress: &Progress,
+    warnings: &mut Vec<Warning>,
+) -> Result<(String, Language)> {
+    // Reading from the terminal would wait for input that the user does not know to type.
+    if stdin().is_terminal() {
+        bail!(
+            "no input, give a path to a file or pipe data to vizo\n\n{}\n\n\
+             For more information, try '--help'.",
+            Cli::command().render_usage()
+        );
+    }
+
+    let options = get_read_options(cli);
+    let bytes = read_limited(stdin().lock(), &options, progress)
         .map_err(|e| anyhow!("failed to read from stdin: {}", e.to_string()))?;
+    check_read_size("input", bytes.len(), &options)?;
+    let bytes = decompress(bytes, &options)?;
+    let contents = encoding::decode(bytes, options.encoding, warnings)?;
 
-    if let Some(lang) = &cli.language {
-        Ok((contents, lang.clone()))
-    } else {
-        bail!("language is not specified for stdin")
+    // Without a language, the format is recognized by the content, like for files.
+    let ext = get_language(cli, || detect_language(&contents));
+    Ok((contents, ext))
+}
+
+fn get_from_clipboard(
+    cli: &Cli,
+    progress: &Progress,
+    warnings: &mut Vec<Warning>,
+) -> Result<(String, Language)> {
+    let options = get_read_options(cli);
+    progress.set_phase("reading the clipboard");
+    let bytes = clipboard::read()?;
+    check_read_size("clipboard", bytes.len(), &options)?;
+    let contents = encoding::decode(bytes, options.encoding, warnings)?;
+
+    let ext = get_language(cli, || detect_language(&contents));
+    Ok((contents, ext))
+}
+
+/// The language of an input, like `json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Language {
+    /// Name of the language, empty if it could not be recognized.
+    pub name: String,
+
+    /// The language was guessed from the content, so other ones are tried if it does not parse.
+    pub guessed: bool,
+}
+
+impl Language {
+    /// A language that was given or follows from the extension.
+    pub fn given(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            guessed: false,
+        }
+    }
+}
+
+/// Options that control how the input is read.
+#[derive(Debug, Clone)]
+pub struct ReadOptions {
+    /// Maximum size of the input in bytes. `0` disables the check.
+    pub max_size: u64,
+
+    /// Encoding of the input, detected automatically if not set.
+    pub encoding: Option<&'static Encoding>,
+
+    /// Reads special files and inputs over the size limit anyway.
+    pub force: bool,
+
+    /// Languages of additional file extensions, like `lock` to `toml`.
+    pub mappings: Vec<(String, String)>,
+}
+
+fn get_read_options(cli: &Cli) -> ReadOptions {
+    ReadOptions {
+        max_size: cli.max_size,
+        encoding: cli.encoding,
+        force: cli.force,
+        mappings: cli.mappings.clone(),
     }
 }
 
-fn get_file_content(file_path: &str) -> Result<(String, String)> {
+/// Returns the language mapped to the extension, or the extension itself.
+fn map_extension(ext: String, options: &ReadOptions) -> String {
+    // Later mappings take precedence, so the command line overrides the config file.
+    match options.mappings.iter().rev().find(|(from, _)| *from == ext) {
+        Some((_, lang)) => lang.clone(),
+        None => ext,
+    }
+}
+
+fn get_from_url(
+    cli: &Cli,
+    url: &str,
+    progress: &Progress,
+    warnings: &mut Vec<Warning>,
+) -> Result<(String, Language)> {
+    let options = get_read_options(cli);
+    progress.set_phase("downloading");
+    let max_size = if options.force { 0 } else { options.max_size };
+    let response = fetch::fetch(url, &cli.headers, max_size)?;
+    check_read_size("response", response.body.len(), &options)?;
+    let body = decompress(response.body, &options)?;
+    let contents = encoding::decode(body, options.encoding, warnings)?;
+
+    // The format is taken from the Content-Type, then from the extension in the URL.
+    let ext = match (&cli.language, response.format) {
+        (Some(lang), _) => Language::given(lang),
+        (None, Some(format)) => Language::given(format),
+        (None, None) => {
+            let path = url.split(['?', '#']).next().unwrap_or(url);
+            let ext = map_extension(compression::inner_extension(Path::new(path)), &options);
+            if is_known_language(&ext) {
+                Language::given(ext)
+            } else {
+                detect_language(&contents)
+            }
+        }
+    };
+
+    Ok((contents, ext))
+}
+
+pub(crate) fn get_file_content(
+    file_path: &str,
+    options: &ReadOptions,
+    progress: &Progress,
+    warnings: &mut Vec<Warning>,
+) -> Result<(String, Language)> {
     let path = Path::new(file_path);
 
     if !path.exists() {
-        return Err(anyhow!("file not found"));
+        return Err(ExitError::new(ExitCode::FileNotFound, anyhow!("file not found")).into());
     }
 
-    let contents = fs::read_to_string(file_path)
+    if let Ok(metadata) = fs::metadata(path) {
+        check_file_type(&metadata, options)?;
+        check_size("file", metadata.len(), options)?;
+    }
+
+    let bytes = fs::File::open(path)
+        .and_then(|file| read_limited(file, options, progress))
         .map_err(|e| anyhow!("failed to read file: {}", e.to_string()))?;
+    // Special files report no size, so the limit is checked again after reading.
+    check_read_size("file", bytes.len(), options)?;
+    let bytes = decompress(bytes, options)?;
+    let contents = encoding::decode(bytes, options.encoding, warnings)?;
 
-    let ext = path
-        .extension()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .to_string()
-        .to_lowercase();
+    let ext = map_extension(compression::inner_extension(path), options);
+    // Files with an unknown extension are recognized by their content.
+    let ext = if is_known_language(&ext) {
+        verbose::log(
+            2,
+            format_args!("the extension of {} is {ext}", path.display()),
+        );
+        Language::given(ext)
+    } else {
+        detect_language(&contents)
+    };
 
     Ok((contents, ext))
 }
 
+/// Returns the language given with `--language`, or the one that was found for the input.
+fn get_language(cli: &Cli, found: impl FnOnce() -> Language) -> Language {
+    match &cli.language {
+        Some(language) => {
+            verbose::log(
+                2,
+                format_args!("the language is {language} from --language"),
+            );
+            Language::given(language)
+        }
+        None => found(),
+    }
+}
+
+/// Guesses the language by the content, leaving the name empty if it is not clear.
+fn detect_language(contents: &str) -> Language {
+    let language = detect::detect(contents).unwrap_or_default();
+    match language {
+        "" => verbose::log(
+            2,
+            format_args!("the language could not be detected from the content"),
+        ),
+        language => verbose::log(2, format_args!("detected {language} from the content")),
+    }
+    Language {
+        name: language.to_string(),
+        guessed: true,
+    }
+}
+
+/// Returns `true` if the language is built in or has a `viz-<lang>` plugin.
+fn is_known_language(language: &str) -> bool {
+    detect::FORMATS.contains(&language) || PluginProcessor::find(language).is_some()
+}
+
+/// Decompresses the input if it is compressed, keeping the result within the size limit.
+fn decompress(bytes: Vec<u8>, options: &ReadOptions) -> Result<Vec<u8>> {
+    let max_size = if options.force { 0 } else { options.max_size };
+    let bytes = compression::decompress(bytes, max_size)?;
+    check_read_size("decompressed input", bytes.len(), options)?;
+    Ok(bytes)
+}
+
+/// Reads the input, stopping one byte past the size limit,
+/// which is enough to tell that the input is too large.
+fn read_limited(
+    reader: impl Read,
+    options: &ReadOptions,
+    progress: &Progress,
+) -> io::Result<Vec<u8>> {
+    if options.force || options.max_size == 0 {
+        progress.read_to_end(reader)
+    } else {
+        progress.read_to_end(reader.take(options.max_size.saturating_add(1)))
+    }
+}
+
+/// Rejects directories and special files, which cannot be read or may never end.
+fn check_file_type(metadata: &fs::Metadata, options: &ReadOptions) -> Result<()> {
+    let file_type = metadata.file_type();
+    if file_type.is_dir() {
+        bail!("path is a directory (use --recursive to view the files in it)");
+    }
+    if file_type.is_file() || options.force {
+        return Ok(());
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+
+        let kind = if file_type.is_fifo() {
+            "a FIFO"
+        } else if file_type.is_socket() {
+            "a socket"
+        } else if file_type.is_char_device() || file_type.is_block_device() {
+            "a device file"
+        } else {
+            "not a regular file"
+        };
+        bail!("path is {kind} (use --force to read it anyway)");
+    }
+
+    #[cfg(not(unix))]
+    bail!("path is not a regular file (use --force to read it anyway)");
+}
+
+fn check_size(source: &str, size: u64, options: &ReadOptions) -> Result<()> {
+    if !options.force && options.max_size != 0 && size > options.max_size {
+        let message = anyhow!(
+            "{source} is {}, more than the limit of {} (use --force or adjust with --max-size)",
+            format_bytes(size),
+            format_bytes(options.max_size)
+        );
+        return Err(ExitError::new(ExitCode::Limit, message).into());
+    }
+
+    Ok(())
+}
+
+/// Checks the size of the input read by [`read_limited`], which stops past the limit,
+/// so only the limit can be reported.
+fn check_read_size(source: &str, size: usize, options: &ReadOptions) -> Result<()> {
+    if !options.force && options.max_size != 0 && size as u64 > options.max_size {
+        bail!(
+            "{source} is larger than the limit of {} (use --force or adjust with --max-size)",
+            format_bytes(options.max_size)
+        );
+    }
+
+    Ok(())
+}
+
 fn get_indent(cli: &Cli) -> Result<usize> {
     let indent = &cli.indent;
 
@@ -88,30 +1597,579 @@ fn get_indent(cli: &Cli) -> Result<usize> {
     Ok(*indent)
 }
 
-fn get_parsed_data(contents: &str, extension: &str) -> Result<VizValue> {
-    let parsed_data = match extension {
-        "json" => json::JSONProcessor::process_data(contents),
-        "toml" => toml::TOMLProcessor::process_data(contents),
-        "yaml" | "yml" => yaml::YAMLProcessor::process_data(contents),
-        _ => {
-            return Err(anyhow!("unsupported file format."));
+/// Collects the options of rendering from the arguments.
+fn get_render_options(cli: &Cli) -> Result<RenderOptions> {
+    let size_keys = if cli.size_keys.is_empty() {
+        humanize::SIZE_KEYS.map(String::from).to_vec()
+    } else {
+        cli.size_keys.clone()
+    };
+
+    Ok(RenderOptions::builder()
+        .indent(get_indent(cli)?)
+        .limit(cli.limit)
+        .raw_strings(cli.raw_strings)
+        .width(get_wrap_width(cli))
+        .max_string_length(cli.max_string_length)
+        .theme(get_theme(cli)?)
+        .bare_keys(cli.bare_keys)
+        .null_text(&cli.null_text)
+        .compact_empty(cli.compact_empty)
+        .depth(cli.depth)
+        .number_separator(get_number_separator(cli))
+        .human_time(cli.human_time)
+        .now(now())
+        .float_format(cli.float_format)
+        .size_keys(cli.human_bytes.then_some(size_keys))
+        .hyperlinks(!cli.no_hyperlinks && terminal::supports_hyperlinks())
+        .sort_keys(cli.sort_keys)
+        .color_swatches(!cli.no_swatches)
+        .build())
+}
+
+/// Returns the current time in seconds since the epoch.
+fn now() -> Option<i64> {
+    let since = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+    i64::try_from(since.as_secs()).ok()
+}
+
+/// Loads the theme from `--theme-file`, or by its name from the built-in themes
+/// and the `themes` directory in the config directory, and applies the `--color-*` options.
+fn get_theme(cli: &Cli) -> Result<Theme> {
+    let mut theme = if let Some(path) = &cli.theme_file {
+        Theme::load(Path::new(path))?
+    } else if cli.theme == "default" {
+        Theme::for_background(get_background(cli))
+    } else if let Some(theme) = Theme::preset(&cli.theme) {
+        theme
+    } else {
+        let file = format!("{}.toml", cli.theme);
+        match config::dir().map(|dir| dir.join("themes").join(file)) {
+            Some(path) if path.is_file() => Theme::load(&path)?,
+            _ => bail!(
+                "unknown theme: {} (built-in themes are {})",
+                cli.theme,
+                theme::PRESETS.join(", ")
+            ),
         }
-    }?;
+    };
+
+    let overrides = [
+        (&mut theme.key, cli.color_keys),
+        (&mut theme.string, cli.color_strings),
+        (&mut theme.number, cli.color_numbers),
+        (&mut theme.boolean, cli.color_booleans),
+        (&mut theme.null, cli.color_null),
+        (&mut theme.punctuation, cli.color_punctuation),
+    ];
+    for (paint, color) in overrides {
+        if color.is_some() {
+            paint.color = color;
+        }
+    }
+    if cli.dim_null {
+        theme.null = theme.null.dimmed();
+    }
+
+    Ok(theme)
+}
+
+/// Returns the background of `--background`, or the one of the terminal if colors are used.
+///
+/// Asking the terminal takes a moment, so it is only done when the colors would be shown.
+fn get_background(cli: &Cli) -> Background {
+    if let Some(background) = cli.background {
+        return background;
+    }
+    if !colored::control::SHOULD_COLORIZE.should_colorize() {
+        return Background::default();
+    }
+    let background = terminal::background();
+    if let Some(background) = background {
+        verbose::log(
+            2,
+            format_args!("the background of the terminal is {background:?}"),
+        );
+    }
+    background.unwrap_or_default()
+}
+
+/// Returns the separator of groups of digits for `--human-numbers`,
+/// taken from the locale unless it is given with `--number-separator`.
+fn get_number_separator(cli: &Cli) -> Option<String> {
+    if !cli.human_numbers {
+        return None;
+    }
+    if let Some(separator) = &cli.number_separator {
+        return Some(separator.clone());
+    }
+
+    let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .iter()
+        .filter_map(|name| var(name).ok())
+        .find(|locale| !locale.is_empty())
+        .unwrap_or_default();
+    let language = locale
+        .split(['_', '.', '@', '-'])
+        .next()
+        .unwrap_or_default();
+    let separator = match language {
+        "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "sl" | "hr" => ".",
+        "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "uk" | "hu" | "bg" => " ",
+        _ => ",",
+    };
+    Some(separator.to_string())
+}
+
+/// Returns the width to wrap long strings at, if the output is a terminal.
+fn get_wrap_width(cli: &Cli) -> Option<usize> {
+    if cli.no_wrap { None } else { terminal::width() }
+}
+
+fn get_parse_options(cli: &Cli) -> ParseOptions {
+    ParseOptions {
+        max_depth: cli.max_depth,
+        max_nodes: cli.max_nodes,
+        strict: cli.strict,
+        lenient: cli.lenient,
+        yaml_version: cli.yaml_spec,
+    }
+}
+
+fn get_parsed_data<'a>(
+    contents: &'a str,
+    language: &Language,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> Result<VizValue<'a>> {
+    if language.guessed {
+        return parse_guessed(contents, &language.name, registry, options, warnings);
+    }
+
+    let extension = language.name.as_str();
+    if registry.contains(extension) {
+        verbose::log(1, format_args!("parsing with the {extension} processor"));
+    }
+    let parsed_data = match registry.parse(extension, contents, options, warnings) {
+        // Formats that are not built in may be handled by a plugin.
+        Err(VizError::UnsupportedFormat(_)) => match PluginProcessor::find(extension) {
+            Some(plugin) => {
+                verbose::log(
+                    1,
+                    format_args!("parsing with the plugin {}", plugin.program().display()),
+                );
+                plugin.process_data(contents, options, warnings)?
+            }
+            None => return Err(VizError::UnsupportedFormat(extension.to_string()).into()),
+        },
+        result => result?,
+    };
 
     Ok(parsed_data)
 }
 
-fn print_parsed_data(data: VizValue, indent: usize) {
-    if let VizValue::Object(map) = data {
-        let entries: Vec<_> = map.into_iter().collect();
-        for (key, val) in entries.into_iter() {
-            crate::prints::print_prettij(&key, val, 0, indent, DisplayType::Key, true);
+/// Parses data whose language was guessed from the content, or could not be guessed at all.
+///
+/// The guess is tried first, then the other built-in formats, so data that only looks like
+/// another format, like a JSON array like a TOML table, is still read. If none of them can
+/// parse it, the error of the guess is returned with the formats that were tried.
+fn parse_guessed<'a>(
+    contents: &'a str,
+    guess: &str,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> Result<VizValue<'a>> {
+    let formats: Vec<&str> = iter::once(guess)
+        .filter(|guess| !guess.is_empty())
+        .chain(["json", "toml", "yaml"].into_iter().filter(|f| *f != guess))
+        .collect();
+
+    let mut guess_error = None;
+    for format in &formats {
+        let mut format_warnings = Vec::new();
+        let language = Language::given(*format);
+        match get_parsed_data(contents, &language, registry, options, &mut format_warnings) {
+            Ok(data) => {
+                if !guess.is_empty() && *format != guess {
+                    verbose::log(1, format_args!("the content is {format}, not {guess}"));
+                }
+                warnings.extend(format_warnings);
+                return Ok(data);
+            }
+            // Another format would not be any less deep or large.
+            Err(e) if matches!(e.downcast_ref(), Some(VizError::Limit(_))) => return Err(e),
+            Err(e) => {
+                verbose::log(2, format_args!("not {format}: {e}"));
+                guess_error.get_or_insert(e);
+            }
+        }
+    }
+
+    // Listed in the order they were tried, like `TOML, JSON or YAML`.
+    let names: Vec<String> = formats.iter().map(|format| format.to_uppercase()).collect();
+    let list = |conjunction: &str| match names.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, others)) => format!("{} {conjunction} {last}", others.join(", ")),
+        None => String::new(),
+    };
+    match guess_error {
+        Some(e) if !guess.is_empty() => Err(ExitError::new(
+            ExitCode::of(&e),
+            anyhow!(
+                "the data is not {} (specify the language with --language): {e}",
+                list("or")
+            ),
+        )
+        .into()),
+        _ => Err(ExitError::new(
+            ExitCode::UnsupportedFormat,
+            anyhow!(
+                "could not detect the format of the data, tried {} \
+                 (specify it with --language)",
+                list("and")
+            ),
+        )
+        .into()),
+    }
+}
+
+fn get_violations(
+    cli: &Cli,
+    data: &VizValue,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+) -> Result<Vec<Violation>> {
+    let Some(schema_path) = cli.schema.as_deref() else {
+        return Ok(Vec::new());
+    };
+
+    let schema = load_schema(cli, schema_path, registry, options)?;
+    Ok(schema::validate(&schema, data))
+}
+
+/// Reads and parses a JSON Schema file.
+fn load_schema(
+    cli: &Cli,
+    schema_path: &str,
+    registry: &ProcessorRegistry,
+    options: &ParseOptions,
+) -> Result<VizValue<'static>> {
+    let read_options = ReadOptions {
+        encoding: None,
+        ..get_read_options(cli)
+    };
+    let (contents, extension) = get_file_content(
+        schema_path,
+        &read_options,
+        &Progress::hidden(),
+        &mut Vec::new(),
+    )
+    .map_err(|e| anyhow!("failed to load schema: {e}"))?;
+    let schema = get_parsed_data(&contents, &extension, registry, options, &mut Vec::new())
+        .map_err(|e| anyhow!("failed to load schema: {e}"))?;
+
+    // Ignored keywords would let documents pass that the schema is meant to reject.
+    let unsupported = schema::unsupported_keywords(&schema);
+    if cli.strict
+        && let Some(warning) = unsupported.first()
+    {
+        let path = warning.path.as_deref().unwrap_or("/");
+        bail!("{schema_path}: {path}: {} (--strict)", warning.message);
+    }
+    if !cli.quiet {
+        print_warnings(&unsupported);
+    }
+
+    Ok(schema.into_owned())
+}
+
+/// Sorts arrays of scalars and converts times to one time zone, if it was asked for.
+///
+/// This happens before the validation, so the paths of violations point to the sorted elements.
+fn normalize_data<'a>(cli: &Cli, mut data: VizValue<'a>) -> Result<VizValue<'a>> {
+    if cli.sort_arrays {
+        data.sort_arrays();
+    }
+    if let Some(zone) = get_time_zone(cli)? {
+        datetime::normalize(&mut data, &zone);
+    }
+    Ok(data)
+}
+
+/// Returns the time zone of `--utc` or `--local`, `None` if times are kept as they are.
+///
+/// The local time zone is read from `TZ`, like `Europe/Berlin` or a POSIX rule such as
+/// `CET-1CEST,M3.5.0,M10.5.0/3`, or from `/etc/localtime`.
+fn get_time_zone(cli: &Cli) -> Result<Option<TimeZone>> {
+    if cli.utc {
+        return Ok(Some(TimeZone::utc()));
+    }
+    if !cli.local {
+        return Ok(None);
+    }
+
+    let read = |path: &Path| -> Result<TimeZone> { Ok(TimeZone::from_tzif(&fs::read(path)?)?) };
+    let tz = var("TZ").ok().filter(|tz| !tz.is_empty());
+    let zone = match tz.as_deref().map(|tz| tz.strip_prefix(':').unwrap_or(tz)) {
+        None => read(Path::new("/etc/localtime")),
+        Some(tz) if tz.starts_with('/') => read(Path::new(tz)),
+        Some(tz) => read(&Path::new("/usr/share/zoneinfo").join(tz))
+            .or_else(|_| Ok(TimeZone::from_posix(tz)?)),
+    };
+    zone.map(Some).map_err(|e| {
+        anyhow!("the local time zone is not known, set TZ, like TZ=Europe/Berlin ({e})")
+    })
+}
+
+/// Expands data that is embedded in strings, if it was asked for.
+///
+/// This happens after the validation, so the schema sees the document as it is.
+fn expand_data<'a>(cli: &Cli, mut data: VizValue<'a>, options: &ParseOptions) -> VizValue<'a> {
+    // Decoded base64 may be JSON, so it is decoded first.
+    if cli.decode_base64 {
+        embedded::expand_base64(&mut data);
+    }
+    if cli.parse_nested {
+        embedded::expand_json(&mut data, options);
+    }
+    data
+}
+
+/// Returns the hints to print after values, like whether the paths in the document exist.
+fn get_hints(cli: &Cli, data: &VizValue, path: Option<&str>) -> Hints {
+    let mut hints = Hints::new();
+    if !cli.check_paths {
+        return hints;
+    }
+
+    // Paths in a document are usually relative to the document, and to the working directory
+    // if it does not come from a file.
+    let base = path
+        .filter(|path| !fetch::is_url(path))
+        .and_then(|path| Path::new(path).parent())
+        .unwrap_or(Path::new(""));
+    data.walk(|value_path, value| {
+        if let VizValue::String(text) = value
+            && looks_like_path(text)
+        {
+            let file = match text.strip_prefix("~/") {
+                Some(rest) => var_os("HOME").map(|home| PathBuf::from(home).join(rest)),
+                None => Some(base.join(text.as_ref())),
+            };
+            let exists = file.is_some_and(|file| file.exists());
+            hints.insert(
+                value_path.to_string(),
+                if exists { "✓" } else { "✗" }.to_string(),
+            );
         }
+    });
+    hints
+}
+
+/// Returns the positions of the numbers of `--heat` between the lowest and the highest of them.
+///
+/// Every path is colored on its own scale, so columns of different units can be compared at once.
+fn get_heat(cli: &Cli, data: &VizValue) -> Result<Heat> {
+    let mut heat = Heat::new();
+    for expression in &cli.heat {
+        let matches = query::query(data, expression)?;
+        let mut numbers: Vec<(String, f64)> = match matches.as_slice() {
+            [(path, value)] if let VizValue::Array(vec) = value.untagged() => vec
+                .iter()
+                .enumerate()
+                .filter_map(|(id, value)| Some((format!("{path}/{id}"), value.as_f64()?)))
+                .collect(),
+            matches => matches
+                .iter()
+                .filter_map(|(path, value)| Some((path.to_string(), value.as_f64()?)))
+                .collect(),
+        };
+        // NaN and infinities would stretch the scale to nothing.
+        numbers.retain(|(_, n)| n.is_finite());
+        if numbers.is_empty() {
+            bail!("there are no numbers at `{expression}`");
+        }
+
+        let min = numbers
+            .iter()
+            .map(|(_, n)| *n)
+            .fold(f64::INFINITY, f64::min);
+        let max = numbers
+            .iter()
+            .map(|(_, n)| *n)
+            .fold(f64::NEG_INFINITY, f64::max);
+        for (path, number) in numbers {
+            let position = if max > min {
+                (number - min) / (max - min)
+            } else {
+                0.5
+            };
+            heat.insert(path, position);
+        }
+    }
+    Ok(heat)
+}
+
+/// Returns `true` if the text looks like a path to a file, like `./build` or `src/main.rs`.
+///
+/// URLs and MIME types, like `application/json`, are not paths.
+fn looks_like_path(text: &str) -> bool {
+    const MIME_TYPES: [&str; 9] = [
+        "application",
+        "audio",
+        "font",
+        "image",
+        "message",
+        "model",
+        "multipart",
+        "text",
+        "video",
+    ];
+    if text.len() < 2 || text.contains("://") || text.contains(['\n', '\t']) {
+        return false;
+    }
+    if ["./", "../", "/", "~/"]
+        .iter()
+        .any(|prefix| text.starts_with(prefix))
+    {
+        return true;
+    }
+    // Without a prefix, a path needs a directory and a file with an extension, like `src/main.rs`.
+    let Some((first, _)) = text.split_once('/') else {
+        return false;
+    };
+    let has_extension = text
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .is_some_and(|(stem, ext)| !stem.is_empty() && !ext.is_empty());
+    has_extension && !text.contains(' ') && !MIME_TYPES.contains(&first)
+}
+
+fn get_annotations(
+    cli: &Cli,
+    data: &VizValue,
+    violations: &[Violation],
+    warnings: &[Warning],
+) -> Annotations {
+    let mut annotations = Annotations::new();
+    if cli.find_dupes {
+        for (duplicate, original) in diff::duplicates(data) {
+            let message = format!("duplicate of {original}");
+            annotations.insert(duplicate.to_string(), vec![message]);
+        }
+    }
+
+    let violations = violations.iter().map(|v| (&v.path, &v.message));
+    let warnings = warnings
+        .iter()
+        .filter_map(|w| w.path.as_ref().map(|path| (path, &w.message)));
+    for (path, message) in violations.chain(warnings) {
+        annotations
+            .entry(path.clone())
+            .or_default()
+            .push(message.clone());
+    }
+
+    annotations
+}
+
+/// Adds what changed since the previous render of `--watch` to the messages of the values.
+///
+/// Removed values are not in the document anymore, so only `--changes-only` shows them.
+fn annotate_changes(annotations: &mut Annotations, changes: &[diff::Change]) {
+    for change in changes {
+        let message = match change {
+            diff::Change::Added { .. } => "added".to_string(),
+            diff::Change::Changed { old, .. } => match old.untagged() {
+                VizValue::String(s) => format!("was \"{s}\""),
+                VizValue::Number(n) => format!("was {n}"),
+                VizValue::Float(f) => format!("was {f}"),
+                VizValue::RawNumber(s) | VizValue::DateTime(s) => format!("was {s}"),
+                VizValue::Bool(b) => format!("was {b}"),
+                VizValue::Null => "was null".to_string(),
+                _ => "changed".to_string(),
+            },
+            diff::Change::Removed { .. } => continue,
+        };
+        annotations
+            .entry(change.path().to_string())
+            .or_default()
+            .push(message);
+    }
+}
+
+/// Prints only the changes since the previous render of `--watch`, like `vizo diff` does.
+fn print_changes(
+    out: &mut impl Write,
+    changes: &[diff::Change],
+    render_options: &RenderOptions,
+) -> Result<()> {
+    if changes.is_empty() {
+        writeln!(
+            out,
+            "{}",
+            "no changes since the previous render".bright_black()
+        )?;
     } else {
-        println!(
-            "{}: parsed data is not a valid object.",
-            "error".red().bold()
-        );
-        exit(1);
+        print_diff(out, changes, render_options)?;
+    }
+    Ok(())
+}
+
+fn print_warnings(warnings: &[Warning]) {
+    let mut err = stderr().lock();
+    for warning in warnings {
+        let label = "warning".yellow().bold();
+        // Unlike `eprintln!`, a closed stderr does not make the warnings panic.
+        let _ = match &warning.path {
+            Some(path) => writeln!(err, "{label}: {path}: {}", warning.message),
+            None => writeln!(err, "{label}: {}", warning.message),
+        };
+    }
+}
+
+fn print_parsed_data(
+    out: &mut impl Write,
+    data: VizValue<'_>,
+    options: &RenderOptions,
+    annotations: Annotations,
+) -> Result<()> {
+    let options = &RenderOptions {
+        annotations,
+        ..options.clone()
+    };
+    let mut line_limit = LineLimit::new(options.limit);
+    print_root(out, data, &mut line_limit, options)?;
+
+    if let Some(limit) = options.limit.filter(|_| line_limit.is_truncated()) {
+        print_truncation_marker(out, limit, options)?;
     }
+
+    Ok(())
+}
+
+/// Prints the document as it is with colored tokens, for `--highlight-only`.
+fn print_highlighted(
+    out: &mut impl Write,
+    contents: &str,
+    extension: &str,
+    options: &RenderOptions,
+) -> Result<()> {
+    let mut line_limit = LineLimit::new(options.limit);
+    highlight(
+        out,
+        contents,
+        extension,
+        options.active_theme(),
+        &mut line_limit,
+    )?;
+
+    if let Some(limit) = options.limit.filter(|_| line_limit.is_truncated()) {
+        print_truncation_marker(out, limit, options)?;
+    }
+
+    Ok(())
 }
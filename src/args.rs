@@ -1,23 +1,775 @@
 use std::env;
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum, ValueHint};
+use colored::Color;
+use encoding_rs::Encoding;
+
+use crate::completions::Shell;
+use crate::hash::Algorithm;
+use crate::merge::ArrayMerge;
+use crate::plugin::PluginProcessor;
+use crate::prints::FloatFormat;
+use crate::processors::detect::FORMATS;
+use crate::processors::yaml::YamlVersion;
+use crate::theme::{self, Background};
+use vizo_core::Format;
 
 /// A CLI builder for Vizo app.
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(name = "vizo", about = env!("CARGO_PKG_DESCRIPTION"), version = env!("CARGO_PKG_VERSION"))]
+// Options from the config file go first, so the ones given later must replace them.
+#[command(args_override_self = true, disable_help_subcommand = true)]
 pub struct Cli {
-    /// Path to the file to view
-    pub path: Option<String>,
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    /// Paths to the files to view. Reads from stdin if none are given.
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub paths: Vec<String>,
+
+    /// Path to a file to view, can be repeated.
+    #[arg(long = "path", value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub extra_paths: Vec<String>,
+
+    /// Read the paths of the files to view from a file, `-` for stdin, one per line, and view
+    /// each of them under its path, like the output of `find`.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath, conflicts_with_all = ["clipboard", "watch"])]
+    pub files_from: Option<String>,
+
+    /// Separate the paths of `--files-from` by NUL characters instead of lines, like the output
+    /// of `find -print0`. Reads them from stdin if `--files-from` is not given.
+    #[arg(short = '0', long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["clipboard", "watch"])]
+    pub null: bool,
+
+    /// View all supported files in the directories, including subdirectories.
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    pub recursive: bool,
+
+    /// Read the input from the system clipboard.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["paths", "extra_paths", "watch"])]
+    pub clipboard: bool,
+
+    /// Header to send when the path is an HTTP(S) URL, e.g. "Authorization: Bearer token".
+    #[arg(short = 'H', long = "header", value_name = "HEADER")]
+    pub headers: Vec<String>,
 
     /// Language of the file to view (e.g., json, toml, yaml).
     #[arg(short, long)]
     pub language: Option<String>,
 
-    /// Disable colored output.
-    #[arg(short = 'n', long = "no-color", action = clap::ArgAction::SetTrue)]
+    /// Map a file extension to a language, e.g. `lock=toml` or `webmanifest=json`. Can be repeated.
+    #[arg(long = "map", value_name = "EXT=LANG", value_parser = parse_mapping)]
+    pub mappings: Vec<(String, String)>,
+
+    /// When to color the output: auto (only if it is a terminal), always or never.
+    #[arg(long, value_name = "WHEN", default_value = "auto", overrides_with_all = ["no_color", "force_color"])]
+    pub color: ColorMode,
+
+    /// Disable colored output, same as --color never.
+    #[arg(short = 'n', long = "no-color", action = clap::ArgAction::SetTrue, overrides_with_all = ["color", "force_color"])]
     pub no_color: bool,
 
+    /// Color the output even if it is not a terminal, same as --color always.
+    #[arg(long, action = clap::ArgAction::SetTrue, overrides_with_all = ["color", "no_color"])]
+    pub force_color: bool,
+
+    /// Encoding of the input (e.g., utf-8, utf-16le, latin1). Detected automatically by default.
+    #[arg(short, long, value_parser = parse_encoding)]
+    pub encoding: Option<&'static Encoding>,
+
     /// Indentation level for output.
     #[arg(short, long, default_value_t = 2)]
     pub indent: usize,
+
+    /// Maximum number of lines to render before truncating output.
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Print control characters and escape sequences in strings as is instead of escaping them.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub raw_strings: bool,
+
+    /// Truncate strings longer than this number of characters.
+    #[arg(long, value_name = "N")]
+    pub max_string_length: Option<usize>,
+
+    /// Color theme of the output: default, monokai, solarized-light, dracula, plain,
+    /// or the name of a theme in ~/.config/viz/themes.
+    #[arg(long, default_value = "default")]
+    pub theme: String,
+
+    /// Background of the terminal that the default theme picks its colors for: light or dark.
+    ///
+    /// It is detected from `COLORFGBG` or by asking the terminal if it is not given.
+    #[arg(long, value_name = "BACKGROUND", value_parser = parse_background)]
+    pub background: Option<Background>,
+
+    /// Load the color theme from a TOML file.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub theme_file: Option<String>,
+
+    /// Color of object keys, e.g. cyan, 208 or #98c379.
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    pub color_keys: Option<Color>,
+
+    /// Color of strings.
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    pub color_strings: Option<Color>,
+
+    /// Color of numbers.
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    pub color_numbers: Option<Color>,
+
+    /// Color of booleans.
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    pub color_booleans: Option<Color>,
+
+    /// Color of null values.
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    pub color_null: Option<Color>,
+
+    /// Color of brackets, quotes, commas and `=`.
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    pub color_punctuation: Option<Color>,
+
+    /// Do not wrap long strings at the width of the terminal.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub no_wrap: bool,
+
+    /// Print nulls dimmed, so they stand out less than other values.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub dim_null: bool,
+
+    /// Text to print for nulls instead of `null`, like `~` or `∅`.
+    #[arg(long, value_name = "TEXT", default_value = "null")]
+    pub null_text: String,
+
+    /// Print empty arrays and objects as `[]` and `{}` on the line of their key.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub compact_empty: bool,
+
+    /// Fold arrays and objects at this level of nesting into the number of their elements,
+    /// where the keys of the document are at level 1.
+    #[arg(long, value_name = "LEVEL")]
+    pub depth: Option<usize>,
+
+    /// Follow large integers with their digits grouped by thousands, like `1234567 (1,234,567)`.
+    ///
+    /// The separator is taken from the locale, unless it is given with `--number-separator`.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub human_numbers: bool,
+
+    /// Separator of groups of digits for `--human-numbers`, like `_`.
+    #[arg(long, value_name = "SEP")]
+    pub number_separator: Option<String>,
+
+    /// Follow timestamps with their time in UTC and how long ago it was.
+    ///
+    /// Integers are taken as seconds or milliseconds since the epoch if they fall between
+    /// 2001 and 2286, and strings are recognized as ISO 8601 dates and date-times.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub human_time: bool,
+
+    /// How to print floats: `auto`, `fixed:N` for N decimal places, or `scientific`, like `1.5e-7`.
+    #[arg(long, value_name = "FORMAT", default_value = "auto", value_parser = parse_float_format)]
+    pub float_format: FloatFormat,
+
+    /// Convert date-times with an offset to UTC, like `2024-11-02T14:03:00Z`.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["local", "highlight_only"])]
+    pub utc: bool,
+
+    /// Convert date-times with an offset to the local time zone, which is taken from `TZ`.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "highlight_only")]
+    pub local: bool,
+
+    /// Follow sizes in bytes with their size in binary units, like `1503238553 (1.4 GiB)`.
+    ///
+    /// Sizes are the integers under keys that look like sizes, such as `size`, `*_bytes`
+    /// or `content_length`, unless other patterns are given with `--size-keys`.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub human_bytes: bool,
+
+    /// A glob pattern of keys whose values are sizes for `--human-bytes`, like `*_len`.
+    #[arg(long = "size-keys", value_name = "PATTERN")]
+    pub size_keys: Vec<String>,
+
+    /// Render strings that hold JSON objects or arrays as nested values, tagged with `!json`.
+    ///
+    /// Strings inside of them are expanded too, so JSON that was encoded several times is unwrapped.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "highlight_only")]
+    pub parse_nested: bool,
+
+    /// Render strings that look like base64, like the data of Kubernetes Secrets, decoded and tagged with `!base64`.
+    ///
+    /// Text is shown as it is, and binary data as a hex dump.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "highlight_only")]
+    pub decode_base64: bool,
+
+    /// Follow strings that look like file paths with ✓ if they exist and ✗ if they do not.
+    ///
+    /// Relative paths are resolved against the directory of the file.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "highlight_only")]
+    pub check_paths: bool,
+
+    /// Color the numbers at a JSON Pointer or JSONPath, like `$.requests[*].latency`, from green
+    /// for the lowest to red for the highest. An array colors its elements.
+    #[arg(long, value_name = "PATH", conflicts_with = "highlight_only")]
+    pub heat: Vec<String>,
+
+    /// Highlight elements of arrays that repeat an earlier element, like copy-pasted entries.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "highlight_only")]
+    pub find_dupes: bool,
+
+    /// Do not follow hex colors, like `#ff8800`, with a block of the color.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub no_swatches: bool,
+
+    /// Do not make URLs clickable, even if the terminal supports hyperlinks.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub no_hyperlinks: bool,
+
+    /// Print all keys without quotes, even the ones that are not identifiers.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub bare_keys: bool,
+
+    /// Print the document as it is, keeping its layout and comments, and only color its tokens.
+    ///
+    /// The document is not parsed, so it is shown even if it is invalid.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["check", "schema"])]
+    pub highlight_only: bool,
+
+    /// Split the input into several documents and view each of them, like the output of
+    /// `kubectl get -o yaml` or concatenated JSON.
+    ///
+    /// Documents are separated by NUL characters if there are any, by `---` in YAML,
+    /// by nothing in JSON and by blank lines otherwise, unless `--delimiter` is given.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["watch", "highlight_only"])]
+    pub split: bool,
+
+    /// Line that separates the documents of `--split`, like `%%`, or `\0` for NUL characters.
+    ///
+    /// An empty delimiter splits at blank lines.
+    #[arg(long, value_name = "TEXT", requires = "split")]
+    pub delimiter: Option<String>,
+
+    /// View a stream of Kubernetes objects like `--split`, with the kind, namespace and name
+    /// of each object above it.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["watch", "highlight_only"])]
+    pub k8s: bool,
+
+    /// Print only the kind, name, images and replicas of each Kubernetes object of the stream.
+    ///
+    /// The items of lists, like the output of `kubectl get -o yaml`, are printed one by one.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["watch", "highlight_only", "k8s"])]
+    pub k8s_summary: bool,
+
+    /// Print only a table of the requests of a HAR capture, with their methods, statuses,
+    /// times and URLs.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["watch", "highlight_only", "split", "k8s", "k8s_summary"])]
+    pub summary: bool,
+
+    /// View only one entry of a HAR capture, by its number in the table of `--summary`.
+    #[arg(long, value_name = "NUMBER", conflicts_with_all = ["summary", "highlight_only", "split", "k8s", "k8s_summary"])]
+    pub entry: Option<usize>,
+
+    /// View the resources of a Terraform state grouped by their modules and types, with the
+    /// number of instances of each, instead of the whole state.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["summary", "entry", "highlight_only", "split", "k8s", "k8s_summary"])]
+    pub tfstate: bool,
+
+    /// View only the resources of a Terraform state at an address, like `module.vpc` or
+    /// `aws_s3_bucket.logs`, with the attributes of their instances.
+    #[arg(long, value_name = "ADDRESS", conflicts_with_all = ["tfstate", "summary", "entry", "highlight_only", "split", "k8s", "k8s_summary"])]
+    pub get: Option<String>,
+
+    /// View an overview of an OpenAPI or Swagger document instead of the whole document: its
+    /// paths, their operations with summaries and status codes, and the names of its schemas.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["tfstate", "get", "summary", "entry", "highlight_only", "split", "k8s", "k8s_summary"])]
+    pub openapi: bool,
+
+    /// Print only a table of the packages of a lockfile, like `Cargo.lock`, `package-lock.json`
+    /// or `poetry.lock`, with their versions, highlighting the ones with several versions.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["openapi", "tfstate", "get", "summary", "entry", "watch", "highlight_only", "split", "k8s", "k8s_summary"])]
+    pub lock_summary: bool,
+
+    /// Sort object keys alphabetically instead of keeping the source order.
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    pub sort_keys: bool,
+
+    /// Sort arrays of scalars, like lists of hosts or tags, so the order of elements does not matter.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "highlight_only")]
+    pub sort_arrays: bool,
+
+    /// Watch the file for changes and re-render it.
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    pub watch: bool,
+
+    /// Highlight the values that changed since the previous render of `--watch`.
+    #[arg(long, action = clap::ArgAction::SetTrue, requires = "watch", conflicts_with = "highlight_only")]
+    pub diff: bool,
+
+    /// Show only the changes since the previous render of `--diff`, like `vizo diff` does.
+    #[arg(long, action = clap::ArgAction::SetTrue, requires = "diff")]
+    pub changes_only: bool,
+
+    /// Treat duplicate keys and unsupported keywords of schemas as errors instead of warnings.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub strict: bool,
+
+    /// Tolerate common mistakes like comments and trailing commas in JSON or tabs in YAML.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub lenient: bool,
+
+    /// Version of the YAML specification to interpret plain scalars with (1.1 or 1.2).
+    ///
+    /// YAML 1.1 treats values like `yes`, `off` and `1:30` as booleans and numbers.
+    #[arg(long, default_value = "1.2", value_parser = parse_yaml_version)]
+    pub yaml_spec: YamlVersion,
+
+    /// Validate the document against a JSON Schema file. Unsupported keywords, like `pattern`,
+    /// are ignored.
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    pub schema: Option<String>,
+
+    /// Only check that the input is valid, without printing it.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "watch")]
+    pub check: bool,
+
+    /// Suppress warnings, the progress indicator and other non-essential output.
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    pub quiet: bool,
+
+    /// Print how long reading, parsing, validating and rendering took, and the size of the largest
+    /// input, to stderr after the run.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub timing: bool,
+
+    /// Log the chosen processor, sizes and timings to stderr. Repeat for more details.
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Maximum nesting depth of the document (0 to disable).
+    #[arg(long, default_value_t = 512)]
+    pub max_depth: usize,
+
+    /// Maximum size of the input, e.g. 512K, 100M or 2G (0 to disable).
+    #[arg(long, default_value = "2G", value_parser = parse_size)]
+    pub max_size: u64,
+
+    /// Read special files like FIFOs and inputs larger than --max-size anyway.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub force: bool,
+
+    /// Maximum number of values in the document (0 to disable).
+    #[arg(long, default_value_t = 10_000_000)]
+    pub max_nodes: usize,
+
+    /// Ignore the config file at ~/.config/viz/config.toml.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub no_config: bool,
+}
+
+/// Subcommands of Vizo app.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Commands {
+    /// Print the completion script for a shell.
+    Completions {
+        /// Shell to print the completion script for.
+        shell: Shell,
+    },
+
+    /// Print the man page in roff.
+    Man,
+
+    /// Compare two documents and print the keys that were added, removed or changed.
+    ///
+    /// The order of keys and the formatting of the files do not matter, and the documents
+    /// may be in different formats, like `values.yaml` and `values.json`.
+    /// Options like `--color` and `--indent` go before the subcommand.
+    Diff {
+        /// The old document, `-` for stdin.
+        #[arg(value_hint = ValueHint::FilePath)]
+        old: String,
+
+        /// The new document, `-` for stdin.
+        #[arg(value_hint = ValueHint::FilePath)]
+        new: String,
+
+        /// Language of the old document, if it cannot be told from its extension.
+        #[arg(long, value_name = "LANG")]
+        old_language: Option<String>,
+
+        /// Language of the new document, if it cannot be told from its extension.
+        #[arg(long, value_name = "LANG")]
+        new_language: Option<String>,
+
+        /// Print both documents in aligned columns, with the lines that differ highlighted.
+        #[arg(short = 'y', long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["patch", "merge_patch"])]
+        side_by_side: bool,
+
+        /// Print the changes as a JSON Patch (RFC 6902) instead of a tree.
+        #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "merge_patch")]
+        patch: bool,
+
+        /// Print the changes as a JSON Merge Patch (RFC 7396) instead of a tree.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        merge_patch: bool,
+
+        /// Exit with code 5 if the documents differ.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        exit_code: bool,
+    },
+
+    /// Deep-merge documents, where values of later documents win, and print the result.
+    Merge {
+        /// Documents to merge, from the base to the last override, `-` for stdin.
+        #[arg(required = true, value_hint = ValueHint::FilePath)]
+        paths: Vec<String>,
+
+        /// How to merge arrays: replace them or append to them.
+        #[arg(long, value_name = "MODE", default_value = "replace", value_parser = parse_array_merge)]
+        arrays: ArrayMerge,
+
+        /// Print the result as JSON, YAML or TOML instead of rendering it.
+        #[arg(long, value_name = "FORMAT", value_parser = parse_format)]
+        to: Option<Format>,
+
+        /// Carry comments of TOML and YAML documents over to a TOML or YAML result, on a best-effort basis.
+        #[arg(long, action = clap::ArgAction::SetTrue, requires = "to")]
+        keep_comments: bool,
+    },
+
+    /// Sort an array, like the records of an export, and print the document.
+    Sort {
+        /// Document with the array, `-` for stdin.
+        #[arg(value_hint = ValueHint::FilePath)]
+        path: String,
+
+        /// Field of the objects to sort by. Elements are compared as a whole if not set.
+        #[arg(long, value_name = "FIELD")]
+        by: Option<String>,
+
+        /// Sort in descending order.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        desc: bool,
+
+        /// JSON Pointer to the array, like `/items`. The root of the document by default.
+        #[arg(long, value_name = "POINTER", default_value = "")]
+        at: String,
+
+        /// Print the result as JSON, YAML or TOML instead of rendering it.
+        #[arg(long, value_name = "FORMAT", value_parser = parse_format)]
+        to: Option<Format>,
+
+        /// Carry comments of a TOML or YAML document over to a TOML or YAML result, on a best-effort basis.
+        #[arg(long, action = clap::ArgAction::SetTrue, requires = "to")]
+        keep_comments: bool,
+    },
+
+    /// Flatten a document into pairs of paths and values, like `servers[0].host = "localhost"`.
+    ///
+    /// Keys are joined with dots and indices are written in brackets, so the result can be
+    /// used as Spring-style properties or compared by tools that only handle flat keys.
+    Flatten {
+        /// Document to flatten, `-` for stdin.
+        #[arg(value_hint = ValueHint::FilePath)]
+        path: String,
+
+        /// Print the result as JSON, YAML, TOML or a properties file instead of rendering it.
+        #[arg(long, value_name = "FORMAT", value_parser = parse_flat_format)]
+        to: Option<FlatFormat>,
+    },
+
+    /// Rebuild a nested document from flat paths, like the ones of `flatten`.
+    ///
+    /// The input is a properties file or a flat object, and brackets with numbers become arrays,
+    /// so `servers[0].host=localhost` is read back as a list of servers.
+    Unflatten {
+        /// Properties file or flat document, `-` for stdin.
+        #[arg(value_hint = ValueHint::FilePath)]
+        path: String,
+
+        /// Print the result as JSON, YAML or TOML instead of rendering it.
+        #[arg(long, value_name = "FORMAT", value_parser = parse_format)]
+        to: Option<Format>,
+    },
+
+    /// Validate documents against a JSON Schema and print whether each of them matches.
+    ///
+    /// Every file is validated even if some of them fail, and the exit code is 5 if any
+    /// of them does not match, so it can be used as a check in CI.
+    ///
+    /// Keywords that are not supported, like `pattern` and `format`, are ignored with a warning,
+    /// or fail the validation with `--strict`.
+    Validate {
+        /// JSON Schema file to validate against.
+        #[arg(long, required = true, value_hint = ValueHint::FilePath)]
+        schema: String,
+
+        /// Documents to validate, directories and glob patterns included, `-` for stdin.
+        #[arg(required = true, value_hint = ValueHint::AnyPath)]
+        paths: Vec<String>,
+    },
+
+    /// Print a checksum of the data of documents, like `sha256sum` prints one of their bytes.
+    ///
+    /// The checksum is computed over a canonical form of the data, so files with the same data
+    /// have the same checksum, whatever their format, formatting or order of keys is.
+    Hash {
+        /// Documents to hash, `-` for stdin.
+        #[arg(required = true, value_hint = ValueHint::FilePath)]
+        paths: Vec<String>,
+
+        /// Hash function to use: sha256 or sha512.
+        #[arg(long, value_name = "NAME", default_value = "sha256", value_parser = parse_algorithm)]
+        algorithm: Algorithm,
+
+        /// Print the canonical form that is hashed instead of the checksum.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        canonical: bool,
+    },
+
+    /// Print only some elements of the top-level array, to preview a large export.
+    Sample {
+        /// Document with the array, `-` for stdin.
+        #[arg(value_hint = ValueHint::FilePath)]
+        path: String,
+
+        /// Number of elements to print.
+        #[arg(short, long = "n", value_name = "COUNT", default_value_t = 10)]
+        n: usize,
+
+        /// Pick random elements, in the order of the array, instead of the first ones.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        random: bool,
+
+        /// Seed of the random sample, so the same elements can be picked again.
+        #[arg(long, requires = "random")]
+        seed: Option<u64>,
+
+        /// Print the sample as JSON, YAML or TOML instead of rendering it.
+        #[arg(long, value_name = "FORMAT", value_parser = parse_format)]
+        to: Option<Format>,
+    },
+
+    /// Run `cargo metadata` and view the packages of a Rust workspace and their dependencies.
+    ///
+    /// `metadata` views the whole output of `cargo metadata`, and `tree` the dependencies of the
+    /// members of the workspace as nested objects, like `cargo tree` prints them.
+    Cargo {
+        /// What to view: metadata or tree.
+        #[arg(value_enum, default_value = "tree")]
+        view: CargoView,
+
+        /// Arguments for `cargo metadata`, after `--`, like `--manifest-path` or `--no-deps`.
+        #[arg(last = true, value_name = "ARGS")]
+        args: Vec<String>,
+
+        /// Print the result as JSON, YAML or TOML instead of rendering it.
+        #[arg(long, value_name = "FORMAT", value_parser = parse_format)]
+        to: Option<Format>,
+    },
+
+    /// View the environment variables of the process as an object, sorted by their names.
+    Env {
+        /// Only view the variables whose names or values contain the text, ignoring case.
+        #[arg(long, value_name = "TEXT")]
+        grep: Option<String>,
+
+        /// Hide the values of variables that look like they hold secrets, like `GITHUB_TOKEN`.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        redact: bool,
+
+        /// Split lists of paths, like `PATH` or `XDG_DATA_DIRS`, into arrays.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        split_paths: bool,
+
+        /// Print the result as JSON, YAML or TOML instead of rendering it.
+        #[arg(long, value_name = "FORMAT", value_parser = parse_format)]
+        to: Option<Format>,
+    },
+
+    /// Load a document once and query it at a prompt with JSON Pointers or JSONPath expressions.
+    Repl {
+        /// Document to explore.
+        #[arg(value_hint = ValueHint::FilePath)]
+        path: String,
+    },
+}
+
+/// What `cargo` views of the metadata of a workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CargoView {
+    /// The whole output of `cargo metadata`.
+    Metadata,
+    /// The dependencies of the members of the workspace.
+    Tree,
+}
+
+/// A format of the output of `flatten`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlatFormat {
+    /// A Java properties file of `key=value` lines.
+    Properties,
+    /// An object of the flat keys in a structured data format.
+    Data(Format),
+}
+
+/// When to color the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode {
+    /// Only when the output is a terminal.
+    Auto,
+    /// Always, even through pipes.
+    Always,
+    /// Never.
+    Never,
+}
+
+impl Cli {
+    /// Returns when to color the output, taking `--no-color` and `--force-color` into account.
+    pub fn color_mode(&self) -> ColorMode {
+        if self.no_color {
+            ColorMode::Never
+        } else if self.force_color {
+            ColorMode::Always
+        } else {
+            self.color
+        }
+    }
+
+    /// Returns all paths to view, positional ones first.
+    pub fn paths(&self) -> Vec<&str> {
+        self.paths
+            .iter()
+            .chain(&self.extra_paths)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Parses a size in bytes with an optional `K`, `M` or `G` suffix.
+fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.char_indices().last() {
+        Some((id, 'k' | 'K')) => (&value[..id], 1 << 10),
+        Some((id, 'm' | 'M')) => (&value[..id], 1 << 20),
+        Some((id, 'g' | 'G')) => (&value[..id], 1 << 30),
+        _ => (value, 1),
+    };
+
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| format!("invalid size: {value}"))
+}
+
+/// Parses a mapping of a file extension to a language, like `lock=toml`.
+fn parse_mapping(value: &str) -> Result<(String, String), String> {
+    let Some((ext, lang)) = value.split_once('=') else {
+        return Err(format!("invalid mapping: {value} (expected EXT=LANG)"));
+    };
+    let ext = ext.trim().trim_start_matches('.').to_lowercase();
+    let lang = lang.trim().to_lowercase();
+
+    if ext.is_empty() {
+        return Err(format!("invalid mapping: {value} (the extension is empty)"));
+    }
+    if !FORMATS.contains(&lang.as_str()) && PluginProcessor::find(&lang).is_none() {
+        return Err(format!(
+            "invalid mapping: {value} (supported languages are {} and the ones of viz-<lang> plugins)",
+            FORMATS.join(", ")
+        ));
+    }
+
+    Ok((ext, lang))
+}
+
+/// Parses a color name, an index in the 256-color palette or a hex code.
+fn parse_color(value: &str) -> Result<Color, String> {
+    theme::parse_color(value).map_err(|e| e.to_string())
+}
+
+/// Parses the label of an encoding, like `utf-16le` or `latin1`.
+fn parse_encoding(value: &str) -> Result<&'static Encoding, String> {
+    Encoding::for_label(value.trim().as_bytes()).ok_or_else(|| format!("unknown encoding: {value}"))
+}
+
+/// Parses a format of the output, like `json`.
+fn parse_format(value: &str) -> Result<Format, String> {
+    Format::from_name(&value.trim().to_lowercase())
+        .ok_or_else(|| format!("unsupported format: {value} (expected json, yaml or toml)"))
+}
+
+/// Parses a format of the output of `flatten`, which can also be `properties`.
+fn parse_flat_format(value: &str) -> Result<FlatFormat, String> {
+    match value.trim().to_lowercase().as_str() {
+        "properties" => Ok(FlatFormat::Properties),
+        name => Format::from_name(name)
+            .map(FlatFormat::Data)
+            .ok_or_else(|| {
+                format!("unsupported format: {value} (expected json, yaml, toml or properties)")
+            }),
+    }
+}
+
+/// Parses the name of a hash function.
+fn parse_algorithm(value: &str) -> Result<Algorithm, String> {
+    match value.trim().to_lowercase().as_str() {
+        "sha256" => Ok(Algorithm::Sha256),
+        "sha512" => Ok(Algorithm::Sha512),
+        _ => Err(format!(
+            "unsupported algorithm: {value} (expected sha256 or sha512)"
+        )),
+    }
+}
+
+/// Parses the background of the terminal.
+fn parse_background(value: &str) -> Result<Background, String> {
+    match value.trim().to_lowercase().as_str() {
+        "light" => Ok(Background::Light),
+        "dark" => Ok(Background::Dark),
+        _ => Err(format!(
+            "unsupported background: {value} (expected light or dark)"
+        )),
+    }
+}
+
+/// Parses how floats are printed, like `fixed:2`.
+fn parse_float_format(value: &str) -> Result<FloatFormat, String> {
+    let value = value.trim().to_lowercase();
+    match value.split_once(':') {
+        None if value == "auto" => Ok(FloatFormat::Auto),
+        None if value == "scientific" => Ok(FloatFormat::Scientific),
+        Some(("fixed", places)) => places
+            .parse()
+            .map(FloatFormat::Fixed)
+            .map_err(|_| format!("invalid number of decimal places: {places}")),
+        _ => Err(format!(
+            "unsupported float format: {value} (expected auto, fixed:N or scientific)"
+        )),
+    }
+}
+
+/// Parses how arrays are merged.
+fn parse_array_merge(value: &str) -> Result<ArrayMerge, String> {
+    match value.trim() {
+        "replace" => Ok(ArrayMerge::Replace),
+        "append" => Ok(ArrayMerge::Append),
+        _ => Err(format!(
+            "unsupported array mode: {value} (expected replace or append)"
+        )),
+    }
+}
+
+/// Parses a version of the YAML specification.
+fn parse_yaml_version(value: &str) -> Result<YamlVersion, String> {
+    match value.trim() {
+        "1.1" => Ok(YamlVersion::V1_1),
+        "1.2" => Ok(YamlVersion::V1_2),
+        _ => Err(format!(
+            "unsupported YAML version: {value} (expected 1.1 or 1.2)"
+        )),
+    }
 }
@@ -1,8 +1,27 @@
 use colored::Colorize;
+use std::io::{self, Write, stdout};
+use std::process::exit;
 use vizo::app::run;
+use vizo::exit::ExitCode;
+use vizo::timing;
 
 fn main() {
-    if let Err(e) = run() {
-        println!("{}: {}", "error".red().bold(), e);
+    let result = run();
+    timing::report();
+    if let Err(e) = result {
+        // The reader of the output went away, like `head` does, so there is no one to tell.
+        if is_broken_pipe(&e) {
+            exit(0);
+        }
+        let _ = writeln!(stdout(), "{}: {}", "error".red().bold(), e);
+        exit(ExitCode::of(&e) as i32);
     }
 }
+
+fn is_broken_pipe(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<io::Error>()
+            .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+    })
+}
@@ -0,0 +1,130 @@
+use anyhow::{Result, bail};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::env::var_os;
+use std::io::ErrorKind;
+use std::process::Command;
+
+use crate::values::VizValue;
+
+/// Runs `cargo metadata` with the arguments and returns its output, which is JSON.
+///
+/// The `cargo` that runs Vizo, like for `cargo vizo`, is used if there is one.
+pub fn metadata(args: &[String]) -> Result<String> {
+    let cargo = var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = match Command::new(&cargo)
+        .args(["metadata", "--format-version", "1"])
+        .args(args)
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            bail!("viewing the metadata of a workspace requires cargo to be installed")
+        }
+        Err(e) => bail!("failed to run cargo: {e}"),
+    };
+
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        bail!("cargo metadata failed: {}", message.trim());
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Builds the dependencies of the members of a workspace from its metadata, like `cargo tree`.
+///
+/// Every package is a key of its name and version, with its dependencies as an object of the same
+/// kind. Build and development dependencies are grouped under `[build-dependencies]` and
+/// `[dev-dependencies]`, and packages that were already expanded are marked with `(*)`.
+pub fn tree(metadata: &VizValue) -> Result<VizValue<'static>> {
+    let Some(VizValue::Array(nodes)) = metadata.pointer("/resolve/nodes") else {
+        bail!("the metadata has no dependencies, which `--no-deps` leaves out");
+    };
+    let mut names = HashMap::new();
+    if let Some(VizValue::Array(packages)) = metadata.pointer("/packages") {
+        for package in packages {
+            if let (Some(id), Some(name), Some(version)) = (
+                package.pointer_str("/id"),
+                package.pointer_str("/name"),
+                package.pointer_str("/version"),
+            ) {
+                names.insert(id, format!("{name} v{version}"));
+            }
+        }
+    }
+    let nodes: HashMap<&str, &VizValue> = nodes
+        .iter()
+        .filter_map(|node| Some((node.pointer_str("/id")?, node)))
+        .collect();
+
+    let graph = Graph { names, nodes };
+    let mut expanded = HashSet::new();
+    let mut tree = Vec::new();
+    if let Some(VizValue::Array(members)) = metadata.pointer("/workspace_members") {
+        for member in members.iter().filter_map(|member| match member {
+            VizValue::String(id) => Some(id.as_ref()),
+            _ => None,
+        }) {
+            tree.push((
+                Cow::Owned(graph.name(member)),
+                graph.expand(member, &mut expanded),
+            ));
+        }
+    }
+    Ok(VizValue::Object(tree.into_iter().collect()))
+}
+
+/// The packages of the metadata and their dependencies, by their IDs.
+struct Graph<'v> {
+    names: HashMap<&'v str, String>,
+    nodes: HashMap<&'v str, &'v VizValue<'v>>,
+}
+
+impl Graph<'_> {
+    fn name(&self, id: &str) -> String {
+        self.names
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    fn expand(&self, id: &str, expanded: &mut HashSet<String>) -> VizValue<'static> {
+        let deps = match self.nodes.get(id).and_then(|node| node.pointer("/deps")) {
+            Some(VizValue::Array(deps)) => deps.as_slice(),
+            _ => &[],
+        };
+        // Like `cargo tree`, only packages with dependencies are worth marking.
+        if !deps.is_empty() && !expanded.insert(id.to_string()) {
+            return VizValue::String(Cow::Borrowed("(*)"));
+        }
+        let mut normal = Vec::new();
+        let mut groups = [
+            ("[build-dependencies]", Vec::new()),
+            ("[dev-dependencies]", Vec::new()),
+        ];
+        for dep in deps {
+            let Some(pkg) = dep.pointer_str("/pkg") else {
+                continue;
+            };
+            let kinds: Vec<Option<&str>> = match dep.pointer("/dep_kinds") {
+                Some(VizValue::Array(kinds)) => {
+                    kinds.iter().map(|kind| kind.pointer_str("/kind")).collect()
+                }
+                _ => vec![None],
+            };
+            let dependencies = match kinds.iter().find(|kind| kind.is_none()) {
+                Some(_) => &mut normal,
+                None if kinds.contains(&Some("build")) => &mut groups[0].1,
+                None => &mut groups[1].1,
+            };
+            dependencies.push((Cow::Owned(self.name(pkg)), self.expand(pkg, expanded)));
+        }
+        for (group, dependencies) in groups {
+            if !dependencies.is_empty() {
+                let dependencies = VizValue::Object(dependencies.into_iter().collect());
+                normal.push((Cow::Borrowed(group), dependencies));
+            }
+        }
+        VizValue::Object(normal.into_iter().collect())
+    }
+}
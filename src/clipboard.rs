@@ -0,0 +1,58 @@
+use anyhow::{Result, bail};
+use std::env::var_os;
+use std::io::ErrorKind;
+use std::process::Command;
+
+/// Reads the contents of the system clipboard.
+///
+/// The clipboard is read with the tools of the platform: `pbpaste` on macOS, PowerShell
+/// on Windows, and `wl-paste`, `xclip` or `xsel` on Linux and BSDs.
+pub fn read() -> Result<Vec<u8>> {
+    for (tool, args) in tools() {
+        let output = match Command::new(tool).args(*args).output() {
+            Ok(output) => output,
+            // The tool is not installed, so the next one is tried.
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => bail!("failed to run {tool}: {e}"),
+        };
+
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr);
+            bail!(
+                "failed to read the clipboard with {tool}: {}",
+                message.trim()
+            );
+        }
+        return Ok(output.stdout);
+    }
+
+    let names: Vec<&str> = tools().iter().map(|(tool, _)| *tool).collect();
+    bail!(
+        "reading the clipboard requires one of these tools to be installed: {}",
+        names.join(", ")
+    )
+}
+
+/// Returns the tools that can read the clipboard, in the order they are tried.
+fn tools() -> &'static [(&'static str, &'static [&'static str])] {
+    if cfg!(target_os = "macos") {
+        &[("pbpaste", &[])]
+    } else if cfg!(windows) {
+        &[(
+            "powershell",
+            &["-NoProfile", "-Command", "Get-Clipboard -Raw"],
+        )]
+    } else if var_os("WAYLAND_DISPLAY").is_some() {
+        &[
+            ("wl-paste", &["--no-newline"]),
+            ("xclip", &["-selection", "clipboard", "-o"]),
+            ("xsel", &["--clipboard", "--output"]),
+        ]
+    } else {
+        &[
+            ("xclip", &["-selection", "clipboard", "-o"]),
+            ("xsel", &["--clipboard", "--output"]),
+            ("wl-paste", &["--no-newline"]),
+        ]
+    }
+}
@@ -0,0 +1,63 @@
+use clap::builder::PossibleValuesParser;
+use clap::{Command, ValueEnum};
+use std::io::{self, Write};
+
+use crate::processors::detect::FORMATS;
+use crate::theme::PRESETS;
+
+/// A shell to generate the completion script for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    #[value(name = "powershell")]
+    PowerShell,
+}
+
+impl From<Shell> for clap_complete::Shell {
+    fn from(shell: Shell) -> Self {
+        match shell {
+            Shell::Bash => clap_complete::Shell::Bash,
+            Shell::Zsh => clap_complete::Shell::Zsh,
+            Shell::Fish => clap_complete::Shell::Fish,
+            Shell::PowerShell => clap_complete::Shell::PowerShell,
+        }
+    }
+}
+
+/// Writes the completion script for the shell.
+///
+/// The script is generated by `clap_complete` from the definition of the command, so it
+/// completes every option, the known values of options like `--language`, subcommands,
+/// and file paths.
+pub fn generate(shell: Shell, command: Command, out: &mut impl Write) -> io::Result<()> {
+    let mut command = with_known_values(command);
+    let name = command.get_name().to_string();
+    // `clap_complete` panics if writing fails, so the script is written out afterwards.
+    let mut script = Vec::new();
+    clap_complete::generate(
+        clap_complete::Shell::from(shell),
+        &mut command,
+        name,
+        &mut script,
+    );
+    out.write_all(&script)
+}
+
+/// Lists the values of arguments that are parsed by hand, so clap does not know them.
+///
+/// The command is only used to generate the script, so the values do not restrict parsing.
+fn with_known_values(command: Command) -> Command {
+    let values: [(&str, Vec<&'static str>); 4] = [
+        ("language", FORMATS.to_vec()),
+        ("yaml_spec", vec!["1.1", "1.2"]),
+        ("background", vec!["light", "dark"]),
+        ("theme", PRESETS.to_vec()),
+    ];
+    values.into_iter().fold(command, |command, (id, values)| {
+        command.mut_arg(id, |arg| {
+            arg.value_parser(PossibleValuesParser::new(values))
+        })
+    })
+}
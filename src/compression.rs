@@ -0,0 +1,121 @@
+use anyhow::{Result, anyhow, bail};
+use std::io::{ErrorKind, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// A compression format of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+impl Compression {
+    /// Detects the compression by the magic bytes at the start of the data.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(Compression::Gzip)
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Compression::Zstd)
+        } else if bytes.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Some(Compression::Xz)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the compression that is usually denoted by the file extension.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "gz" | "gzip" => Some(Compression::Gzip),
+            "zst" | "zstd" => Some(Compression::Zstd),
+            "xz" => Some(Compression::Xz),
+            _ => None,
+        }
+    }
+
+    /// Name of the tool that decompresses the format.
+    fn tool(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Xz => "xz",
+        }
+    }
+}
+
+/// Returns the extension of the file, looking past a compression extension
+/// like in `events.json.gz`.
+pub fn inner_extension(path: &Path) -> String {
+    let ext = |path: &Path| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_lowercase()
+    };
+
+    let outer = ext(path);
+    match (Compression::from_extension(&outer), path.file_stem()) {
+        (Some(_), Some(stem)) => ext(Path::new(stem)),
+        _ => outer,
+    }
+}
+
+/// Decompresses the data if it starts with the magic bytes of a known format.
+///
+/// Decompression is done by the system tools (`gzip`, `zstd` or `xz`). Reading stops one
+/// byte past `max_size`, so the caller can tell that the data is too large, and a small
+/// file cannot expand into one that exhausts the memory. `0` disables the limit.
+pub fn decompress(bytes: Vec<u8>, max_size: u64) -> Result<Vec<u8>> {
+    let Some(compression) = Compression::detect(&bytes) else {
+        return Ok(bytes);
+    };
+    let tool = compression.tool();
+
+    let mut child = Command::new(tool)
+        .args(["--decompress", "--stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => {
+                anyhow!("decompressing {tool} input requires {tool} to be installed")
+            }
+            _ => anyhow!("failed to run {tool}: {e}"),
+        })?;
+
+    // Feeding the input from another thread keeps the pipes from filling up and blocking.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let writer = thread::spawn(move || stdin.write_all(&bytes));
+
+    let mut decompressed = Vec::new();
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let limit = match max_size {
+        0 => u64::MAX,
+        max_size => max_size.saturating_add(1),
+    };
+    let read = stdout.take(limit).read_to_end(&mut decompressed);
+
+    if max_size != 0 && decompressed.len() as u64 > max_size {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Ok(decompressed);
+    }
+
+    let output = child.wait_with_output()?;
+    read?;
+    // The tool stops reading on corrupted input, so a failed write is reported through its status.
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        let message = message.trim();
+        let message = message.rsplit(": ").next().unwrap_or(message);
+        bail!("failed to decompress {tool} input: {message}");
+    }
+
+    Ok(decompressed)
+}
@@ -0,0 +1,130 @@
+use anyhow::{Result, anyhow, bail};
+use clap::{Command, CommandFactory};
+use std::env::var_os;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use toml::{Table, Value};
+
+use crate::args::Cli;
+
+/// Returns the config directory, `$XDG_CONFIG_HOME/viz` or `~/.config/viz`.
+pub fn dir() -> Option<PathBuf> {
+    let dir = var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(dir.join("viz"))
+}
+
+/// Returns the path to the config file, `config.toml` in the config directory.
+pub fn path() -> Option<PathBuf> {
+    Some(dir()?.join("config.toml"))
+}
+
+/// Loads the config file and turns it into command line arguments.
+///
+/// Every key is the long name of an option, like `indent = 4` or `sort-keys = true`.
+/// The arguments go before the ones given by the user, so the user's ones override them.
+/// Returns no arguments if there is no config file.
+pub fn load() -> Result<Vec<String>> {
+    let Some(path) = path() else {
+        return Ok(Vec::new());
+    };
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => bail!("failed to read config {}: {e}", path.display()),
+    };
+    let table: Table = contents
+        .parse()
+        .map_err(|e: toml::de::Error| anyhow!("failed to parse config {}: {e}", path.display()))?;
+
+    to_args(&table, &Cli::command()).map_err(|e| anyhow!("invalid config {}: {e}", path.display()))
+}
+
+/// Turns `VIZ_*` environment variables into command line arguments.
+///
+/// Every variable is the long name of an option in upper case with `VIZ_` in front,
+/// like `VIZ_INDENT=4` or `VIZ_SORT_KEYS=1`. The arguments go after the ones from the config
+/// file and before the ones given by the user.
+pub fn env_args() -> Result<Vec<String>> {
+    let mut args = Vec::new();
+
+    for arg in Cli::command().get_arguments() {
+        let Some(long) = arg.get_long() else {
+            continue;
+        };
+        let name = format!("VIZ_{}", long.replace('-', "_").to_uppercase());
+        let Some(value) = var_os(&name) else {
+            continue;
+        };
+        let value = value
+            .into_string()
+            .map_err(|_| anyhow!("{name} is not valid UTF-8"))?;
+
+        if arg.get_action().takes_values() {
+            args.push(format!("--{long}={value}"));
+            continue;
+        }
+        match value.to_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => args.push(format!("--{long}")),
+            "" | "0" | "false" | "no" | "off" => {}
+            _ => bail!("{name} expects true or false, found `{value}`"),
+        }
+    }
+
+    Ok(args)
+}
+
+/// Converts the keys of the config into arguments of the command.
+fn to_args(table: &Table, command: &Command) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+
+    for (key, value) in table {
+        let Some(arg) = command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(key.as_str()))
+        else {
+            bail!("unknown option `{key}`");
+        };
+        let takes_value = arg.get_action().takes_values();
+
+        // Tables are pairs of a repeatable option, like `[map]` with `lock = "toml"`.
+        if let Value::Table(pairs) = value {
+            for (name, value) in pairs {
+                match value {
+                    Value::String(value) if takes_value => {
+                        args.push(format!("--{key}={name}={value}"))
+                    }
+                    _ => bail!("option `{key}` expects a table of strings"),
+                }
+            }
+            continue;
+        }
+
+        let values = match value {
+            Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            let value = match value {
+                Value::Boolean(true) if !takes_value => {
+                    args.push(format!("--{key}"));
+                    continue;
+                }
+                Value::Boolean(false) if !takes_value => continue,
+                Value::String(s) if takes_value => s.clone(),
+                Value::Integer(_) | Value::Float(_) | Value::Boolean(_) if takes_value => {
+                    value.to_string()
+                }
+                _ if takes_value => bail!("option `{key}` expects a value"),
+                _ => bail!("option `{key}` expects true or false"),
+            };
+            args.push(format!("--{key}={value}"));
+        }
+    }
+
+    Ok(args)
+}
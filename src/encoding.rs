@@ -0,0 +1,85 @@
+use crate::processors::Warning;
+use anyhow::{Result, bail};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, WINDOWS_1252};
+
+/// How many bytes from the start of the input are inspected to guess its encoding.
+const SNIFF_SIZE: usize = 1024;
+
+/// Decodes raw input into a string.
+///
+/// A byte order mark always takes precedence, unless the encoding is given explicitly.
+/// Without a BOM, the input is decoded as UTF-8, as UTF-16 if it looks like one,
+/// and as Latin-1 otherwise. Falling back to Latin-1 is reported as a warning.
+pub fn decode(
+    bytes: Vec<u8>,
+    encoding: Option<&'static Encoding>,
+    warnings: &mut Vec<Warning>,
+) -> Result<String> {
+    if let Some(encoding) = encoding {
+        let (contents, had_errors) = encoding.decode_with_bom_removal(&bytes);
+        if had_errors {
+            bail!("input is not valid {}", encoding.name());
+        }
+        return Ok(contents.into_owned());
+    }
+
+    if let Some((encoding, bom_length)) = Encoding::for_bom(&bytes) {
+        let (contents, had_errors) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+        if had_errors {
+            bail!(
+                "input starts with a {} BOM, but is not valid",
+                encoding.name()
+            );
+        }
+        return Ok(contents.into_owned());
+    }
+
+    let bytes = match String::from_utf8(bytes) {
+        Ok(contents) => return Ok(contents),
+        Err(e) => e.into_bytes(),
+    };
+
+    if let Some(encoding) = sniff_utf16(&bytes) {
+        let (contents, had_errors) = encoding.decode_without_bom_handling(&bytes);
+        if !had_errors {
+            return Ok(contents.into_owned());
+        }
+    }
+
+    warnings.push(Warning {
+        path: None,
+        message: "input is not valid UTF-8, decoded as Latin-1 (override with --encoding)"
+            .to_string(),
+    });
+    let (contents, _) = WINDOWS_1252.decode_without_bom_handling(&bytes);
+    Ok(contents.into_owned())
+}
+
+/// Guesses whether the input is UTF-16 without a BOM.
+///
+/// Text in structured data formats is mostly ASCII, so every other byte of UTF-16 is zero.
+fn sniff_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(SNIFF_SIZE)];
+    if sample.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let pairs = sample.len() / 2;
+    let zeros_at = |parity: usize| {
+        sample
+            .iter()
+            .skip(parity)
+            .step_by(2)
+            .filter(|&&b| b == 0)
+            .count()
+    };
+    let (even, odd) = (zeros_at(0), zeros_at(1));
+
+    if odd * 2 > pairs && even == 0 {
+        Some(UTF_16LE)
+    } else if even * 2 > pairs && odd == 0 {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
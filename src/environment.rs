@@ -0,0 +1,73 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::env::{self, vars_os};
+
+use crate::values::VizValue;
+
+/// Words in names of variables that hold secrets, like `GITHUB_TOKEN` or `DB_PASSWORD`.
+const SECRET_WORDS: [&str; 10] = [
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "PASS",
+    "KEY",
+    "APIKEY",
+    "CREDENTIALS",
+    "AUTH",
+    "PRIVATE",
+];
+
+/// Returns the environment variables of the process as an object, sorted by their names.
+///
+/// Only the variables whose names or values contain `grep`, ignoring case, are returned.
+/// With `redact`, values of variables that look like they hold secrets are hidden, and with
+/// `split_paths`, lists of paths like `PATH` are split into arrays. Names and values that are
+/// not valid Unicode are shown with replacement characters.
+pub fn variables(grep: Option<&str>, redact: bool, split_paths: bool) -> VizValue<'static> {
+    let grep = grep.map(str::to_lowercase);
+    let variables: BTreeMap<String, String> = vars_os()
+        .map(|(name, value)| {
+            let name = name.to_string_lossy().into_owned();
+            (name, value.to_string_lossy().into_owned())
+        })
+        .filter(|(name, value)| {
+            grep.as_ref().is_none_or(|grep| {
+                name.to_lowercase().contains(grep) || value.to_lowercase().contains(grep)
+            })
+        })
+        .collect();
+
+    let variables = variables.into_iter().map(|(name, value)| {
+        let value = if redact && is_secret(&name) {
+            VizValue::String(Cow::Borrowed("<redacted>"))
+        } else if split_paths && is_path_list(&name, &value) {
+            VizValue::Array(
+                env::split_paths(&value)
+                    .map(|path| VizValue::String(path.to_string_lossy().into_owned().into()))
+                    .collect(),
+            )
+        } else {
+            VizValue::String(value.into())
+        };
+        (Cow::Owned(name), value)
+    });
+    VizValue::Object(variables.collect())
+}
+
+/// Returns `true` if one of the words of the name is used for secrets, like in `AWS_SECRET_ACCESS_KEY`.
+fn is_secret(name: &str) -> bool {
+    name.to_uppercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| SECRET_WORDS.contains(&word))
+}
+
+/// Returns `true` if the variable is a list of paths, like `PATH`, `LD_LIBRARY_PATH` or `XDG_DATA_DIRS`.
+///
+/// Variables with a single path, like `SSL_CERT_FILE_PATH`, are kept as they are.
+fn is_path_list(name: &str, value: &str) -> bool {
+    let separator = if cfg!(windows) { ';' } else { ':' };
+    let name = name.to_uppercase();
+    (name.ends_with("PATH") || name.ends_with("PATHS") || name.ends_with("_DIRS"))
+        && value.contains(separator)
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+use vizo_core::VizError;
+
+/// Exit codes of the Vizo app, so shell scripts can tell what went wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// Any other error.
+    Failure = 1,
+    /// The input file does not exist.
+    FileNotFound = 2,
+    /// The input could not be parsed.
+    ParseError = 3,
+    /// The format of the input is not supported or could not be detected.
+    UnsupportedFormat = 4,
+    /// The input was parsed, but does not match what was asked for, like a schema, or the
+    /// documents of `diff --exit-code` differ.
+    NoMatch = 5,
+    /// The input exceeds a limit, like the one of `--max-depth` or `--max-size`.
+    Limit = 6,
+}
+
+impl ExitCode {
+    /// All exit codes, in order.
+    pub const ALL: [ExitCode; 6] = [
+        ExitCode::Failure,
+        ExitCode::FileNotFound,
+        ExitCode::ParseError,
+        ExitCode::UnsupportedFormat,
+        ExitCode::NoMatch,
+        ExitCode::Limit,
+    ];
+
+    /// Returns a short description of what the exit code means.
+    pub fn description(self) -> &'static str {
+        match self {
+            ExitCode::Failure => "Any other error.",
+            ExitCode::FileNotFound => "The input file does not exist.",
+            ExitCode::ParseError => "The input could not be parsed.",
+            ExitCode::UnsupportedFormat => {
+                "The format of the input is not supported or could not be detected."
+            }
+            ExitCode::NoMatch => {
+                "The document does not match the schema, the documents of diff --exit-code \
+                 differ, or --k8s-summary found no Kubernetes objects."
+            }
+            ExitCode::Limit => {
+                "The input exceeds a limit, like the one of --max-depth or --max-size."
+            }
+        }
+    }
+
+    /// Returns the exit code for an error, [`ExitCode::Failure`] if it has none attached.
+    ///
+    /// Errors of the core are told apart by their kind.
+    pub fn of(error: &anyhow::Error) -> Self {
+        if let Some(e) = error.downcast_ref::<ExitError>() {
+            return e.code;
+        }
+        match error.downcast_ref::<VizError>() {
+            Some(VizError::Parse(_) | VizError::Invalid { .. }) => ExitCode::ParseError,
+            Some(VizError::Limit(_)) => ExitCode::Limit,
+            Some(VizError::UnsupportedFormat(_)) => ExitCode::UnsupportedFormat,
+            _ => ExitCode::Failure,
+        }
+    }
+}
+
+/// An error with an exit code attached.
+#[derive(Debug)]
+pub struct ExitError {
+    /// Exit code the app should exit with.
+    pub code: ExitCode,
+
+    /// The underlying error.
+    pub error: anyhow::Error,
+}
+
+impl ExitError {
+    /// Attaches an exit code to the error.
+    pub fn new(code: ExitCode, error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            code,
+            error: error.into(),
+        }
+    }
+}
+
+impl fmt::Display for ExitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for ExitError {
+    /// Returns the wrapped error, so walking the chain of causes, like for broken pipes, sees it.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
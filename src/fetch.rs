@@ -0,0 +1,75 @@
+use anyhow::{Result, anyhow, bail};
+use std::io::ErrorKind;
+use std::process::Command;
+
+/// Returns `true` if the path is an HTTP or HTTPS URL.
+pub fn is_url(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// A downloaded response.
+pub struct Response {
+    /// Body of the response.
+    pub body: Vec<u8>,
+
+    /// Format of the body derived from its `Content-Type`, if it is a known one.
+    pub format: Option<&'static str>,
+}
+
+/// Downloads the URL with `curl`, which takes care of TLS, redirects and proxies.
+///
+/// Headers are passed as is, e.g. `Authorization: Bearer token`.
+pub fn fetch(url: &str, headers: &[String], max_size: u64) -> Result<Response> {
+    let mut command = Command::new("curl");
+    command.args(["--silent", "--show-error", "--location", "--fail"]);
+    // The content type is written after the body, separated by a newline.
+    command.args(["--write-out", "\n%{content_type}"]);
+    if max_size != 0 {
+        command.args(["--max-filesize", &max_size.to_string()]);
+    }
+    for header in headers {
+        command.args(["--header", header]);
+    }
+    command.arg("--").arg(url);
+
+    let output = command.output().map_err(|e| match e.kind() {
+        ErrorKind::NotFound => anyhow!("fetching URLs requires curl to be installed"),
+        _ => anyhow!("failed to run curl: {e}"),
+    })?;
+
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        let message = message.trim().trim_start_matches("curl: ");
+        bail!("failed to fetch {url}: {message}");
+    }
+
+    let mut body = output.stdout;
+    let split = body.iter().rposition(|b| *b == b'\n').unwrap_or(0);
+    let content_type = String::from_utf8_lossy(&body[split..]).trim().to_string();
+    body.truncate(split);
+
+    Ok(Response {
+        body,
+        format: format_of(&content_type),
+    })
+}
+
+/// Maps a `Content-Type` to the name of a format.
+fn format_of(content_type: &str) -> Option<&'static str> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    match mime.as_str() {
+        "application/json" | "text/json" => Some("json"),
+        "application/toml" | "text/toml" => Some("toml"),
+        "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => Some("yaml"),
+        mime if mime.ends_with("+json") => Some("json"),
+        mime if mime.ends_with("+yaml") => Some("yaml"),
+        _ => None,
+    }
+}
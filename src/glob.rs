@@ -0,0 +1,195 @@
+use crate::compression::inner_extension;
+use crate::plugin::PluginProcessor;
+use crate::processors::ProcessorRegistry;
+use anyhow::{Result, bail};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use vizo_core::pattern;
+
+/// Decides which files are picked up by patterns and walks: those with the extension of a
+/// processor of the registry, of a `--map` mapping to a language, or of a `viz-<lang>` plugin.
+pub struct Supported<'a> {
+    registry: &'a ProcessorRegistry,
+    mappings: &'a [(String, String)],
+    plugins: RefCell<HashMap<String, bool>>,
+}
+
+impl<'a> Supported<'a> {
+    pub fn new(registry: &'a ProcessorRegistry, mappings: &'a [(String, String)]) -> Self {
+        Self {
+            registry,
+            mappings,
+            plugins: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Returns `true` if the file has an extension of a supported format, possibly compressed.
+    pub fn is_supported(&self, path: &Path) -> bool {
+        let extension = inner_extension(path);
+        // Later mappings take precedence, like when the file is read.
+        let language = match self
+            .mappings
+            .iter()
+            .rev()
+            .find(|(from, _)| *from == extension)
+        {
+            Some((_, language)) => language.as_str(),
+            None => extension.as_str(),
+        };
+        if self.registry.contains(language) {
+            return true;
+        }
+
+        // Plugins are looked up on `PATH` once for every language.
+        *self
+            .plugins
+            .borrow_mut()
+            .entry(language.to_string())
+            .or_insert_with(|| PluginProcessor::find(language).is_some())
+    }
+}
+
+/// Returns `true` if the path contains glob wildcards.
+pub fn is_pattern(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// Expands a glob pattern into the paths of supported files that match it.
+///
+/// Supports `*` and `?` within a path component, `[abc]` and `[a-z]` character classes,
+/// and `**` for any number of directories. Results are sorted.
+///
+/// Only patterns with `**` walk the whole tree below their literal base; others read just the
+/// directories that match their components, one level at a time.
+pub fn expand(pattern: &str, supported: &Supported) -> Result<Vec<PathBuf>> {
+    let components: Vec<&str> = pattern.split('/').collect();
+    let literal = components
+        .iter()
+        .take_while(|component| !is_pattern(component))
+        .count();
+
+    let base = match components[..literal].join("/") {
+        base if base.is_empty() && pattern.starts_with('/') => "/".to_string(),
+        base if base.is_empty() => ".".to_string(),
+        base => base,
+    };
+    let rest = &components[literal..];
+
+    let matches = if rest.contains(&"**") {
+        walk(Path::new(&base), supported)
+            .into_iter()
+            .filter(|path| {
+                let Ok(relative) = path.strip_prefix(&base) else {
+                    return false;
+                };
+                let relative: Vec<String> = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect();
+                let relative: Vec<&str> = relative.iter().map(String::as_str).collect();
+                matches_path(rest, &relative)
+            })
+            .collect()
+    } else {
+        descend(Path::new(&base), rest, supported)
+    };
+
+    let mut paths: Vec<PathBuf> = matches
+        .into_iter()
+        .map(|path| match path.strip_prefix("./") {
+            Ok(stripped) if !pattern.starts_with("./") => stripped.to_path_buf(),
+            _ => path,
+        })
+        .collect();
+    paths.sort();
+
+    if paths.is_empty() {
+        bail!("no supported files match {pattern}");
+    }
+    Ok(paths)
+}
+
+/// Lists supported files in the directory and all of its subdirectories, sorted by path.
+///
+/// Hidden files and directories, like `.git`, are skipped. Symbolic links to directories
+/// are not followed, so links cannot form a loop.
+pub fn walk(dir: &Path, supported: &Supported) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => dirs.push(path),
+                Ok(_) if supported.is_supported(&path) && path.is_file() => files.push(path),
+                _ => {}
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// Lists supported files that match pattern components without `**`, reading only the
+/// directories whose names match the components before the last one.
+fn descend(base: &Path, pattern: &[&str], supported: &Supported) -> Vec<PathBuf> {
+    let Some((last, parents)) = pattern.split_last() else {
+        return Vec::new();
+    };
+
+    let mut dirs = vec![base.to_path_buf()];
+    for component in parents {
+        dirs = dirs
+            .iter()
+            .flat_map(|dir| entries(dir, component))
+            .filter(|path| path.is_dir())
+            .collect();
+    }
+    dirs.iter()
+        .flat_map(|dir| entries(dir, last))
+        .filter(|path| supported.is_supported(path) && path.is_file())
+        .collect()
+}
+
+/// Returns the entries of the directory whose names match the pattern component.
+fn entries(dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| matches_name(pattern, &entry.file_name().to_string_lossy()))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Matches path components against pattern components, where `**` matches any number of them.
+fn matches_path(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| matches_path(rest, &path[skip..])),
+        Some((first, rest)) => path
+            .split_first()
+            .is_some_and(|(name, path)| matches_name(first, name) && matches_path(rest, path)),
+    }
+}
+
+/// Matches a single path component against a pattern with `*`, `?` and `[...]`.
+fn matches_name(pattern: &str, name: &str) -> bool {
+    // Hidden files are only matched by patterns that start with a dot, like in shells.
+    if name.starts_with('.') && !pattern.starts_with('.') {
+        return false;
+    }
+
+    pattern::matches(pattern, name)
+}
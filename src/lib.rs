@@ -1,17 +1,68 @@
 //! Vizo (derived from 'Visualize') is a modular CLI tool designed to visualize structured data formats such as JSON, TOML, and YAML in a more pretty way.
 //! Its architecture supports customization, enabling users to incorporate specific processors during compilation to tailor functionality to their needs.
 
-/// A global values for Vizo.
-pub mod values;
+// Parsing and rendering live in the core crate, so other tools can embed them.
+pub use vizo_core::{
+    comments, datetime, diff, embedded, emit, flatten, har, hash, highlight, humanize, kubernetes,
+    lockfile, merge, openapi, prints, processors, query, schema, split, terraform, theme, values,
+};
 
 /// An arguments parser for Vizo app.
 pub mod args;
 
-/// A prints module for Vizo app to display formatted data.
-pub mod prints;
+/// Running of `cargo metadata` and the dependency trees of workspaces.
+pub mod cargo;
 
-/// A processors for Vizo to process data.
-pub mod processors;
+/// Reading of input from the system clipboard.
+pub mod clipboard;
+
+/// Generation of shell completion scripts.
+pub mod completions;
+
+/// Transparent decompression of gzip, zstd and xz input.
+pub mod compression;
+
+/// Defaults for the options loaded from the config file and environment variables.
+pub mod config;
+
+/// Detection and decoding of text encodings for input data.
+pub mod encoding;
+
+/// The environment variables of the process for `env`.
+pub mod environment;
+
+/// Exit codes of Vizo app.
+pub mod exit;
+
+/// Downloading of input from HTTP(S) URLs.
+pub mod fetch;
+
+/// Expansion of glob patterns and directories into file paths.
+pub mod glob;
+
+/// Generation of the man page.
+pub mod manpage;
+
+/// Processors that run external `viz-<lang>` executables for other formats.
+pub mod plugin;
+
+/// An interactive prompt for querying a document.
+pub mod repl;
+
+/// Detection of the terminal size.
+pub mod terminal;
+
+/// A progress indicator for slow reads and parses.
+pub mod progress;
+
+/// Logging of decisions and timings for `--verbose`.
+pub mod verbose;
+
+/// Durations of the phases of a run and the sizes of its input for `--timing`.
+pub mod timing;
+
+/// A watch mode for Vizo app to re-render files on change.
+pub mod watch;
 
 // An application module.
 pub mod app;
@@ -0,0 +1,197 @@
+use clap::{Arg, Command};
+use std::io::{self, Write};
+
+use crate::exit::ExitCode;
+
+/// Writes the man page of the command in roff.
+///
+/// Options and subcommands are taken from the definition of the command, so the page
+/// always matches `--help`. Formats, exit codes, files and environment are described as well.
+pub fn generate(command: &mut Command, out: &mut impl Write) -> io::Result<()> {
+    command.build();
+    let name = command.get_name().to_string();
+    let version = command.get_version().unwrap_or_default().to_string();
+    let about = command
+        .get_about()
+        .map(|a| a.to_string())
+        .unwrap_or_default();
+
+    writeln!(
+        out,
+        ".TH {} 1 \"\" \"{name} {version}\" \"User Commands\"",
+        name.to_uppercase()
+    )?;
+
+    writeln!(out, ".SH NAME")?;
+    writeln!(out, "{name} \\- {}", escape(&about))?;
+
+    writeln!(out, ".SH SYNOPSIS")?;
+    writeln!(out, ".B {name}")?;
+    writeln!(out, "[\\fIOPTIONS\\fR] [\\fIPATHS\\fR]...")?;
+    for sub in command.get_subcommands() {
+        writeln!(out, ".br")?;
+        writeln!(out, ".B {name} {}", sub.get_name())?;
+        let positionals: Vec<String> = sub
+            .get_positionals()
+            .map(|arg| format!("<\\fI{}\\fR>", value_name(arg)))
+            .collect();
+        if !positionals.is_empty() {
+            writeln!(out, "{}", positionals.join(" "))?;
+        }
+    }
+
+    writeln!(out, ".SH DESCRIPTION")?;
+    writeln!(
+        out,
+        "{} shows JSON, TOML and YAML documents as indented and colored trees.",
+        escape(&name)
+    )?;
+    writeln!(
+        out,
+        "It reads the files given as \\fIPATHS\\fR, which may also be glob patterns and \
+         HTTP(S) URLs, or the standard input if there are none."
+    )?;
+
+    writeln!(out, ".SH OPTIONS")?;
+    for arg in command.get_arguments().filter(|arg| !arg.is_hide_set()) {
+        let Some(long) = arg.get_long() else {
+            continue;
+        };
+        writeln!(out, ".TP")?;
+        let mut names = Vec::new();
+        if let Some(short) = arg.get_short() {
+            names.push(format!("\\fB\\-{short}\\fR"));
+        }
+        names.push(format!("\\fB\\-\\-{}\\fR", escape(long)));
+        let mut header = names.join(", ");
+        if arg.get_action().takes_values() {
+            header.push_str(&format!(" \\fI<{}>\\fR", value_name(arg)));
+        }
+        writeln!(out, "{header}")?;
+        writeln!(out, "{}", escape(&help(arg)))?;
+
+        let defaults: Vec<String> = arg
+            .get_default_values()
+            .iter()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect();
+        if arg.get_action().takes_values() && !defaults.is_empty() {
+            writeln!(out, ".br")?;
+            writeln!(out, "Default: {}", escape(&defaults.join(", ")))?;
+        }
+    }
+
+    let subcommands: Vec<&Command> = command.get_subcommands().collect();
+    if !subcommands.is_empty() {
+        writeln!(out, ".SH COMMANDS")?;
+        for sub in subcommands {
+            writeln!(out, ".TP")?;
+            writeln!(out, "\\fB{}\\fR", sub.get_name())?;
+            let about = sub.get_about().map(|a| a.to_string()).unwrap_or_default();
+            writeln!(out, "{}", escape(&about))?;
+            for arg in sub.get_positionals() {
+                let values: Vec<String> = arg
+                    .get_possible_values()
+                    .iter()
+                    .map(|value| value.get_name().to_string())
+                    .collect();
+                if !values.is_empty() {
+                    writeln!(out, ".br")?;
+                    writeln!(
+                        out,
+                        "\\fI{}\\fR is one of: {}",
+                        value_name(arg),
+                        escape(&values.join(", "))
+                    )?;
+                }
+            }
+        }
+    }
+
+    writeln!(out, ".SH FORMATS")?;
+    for (format, extensions) in [
+        ("JSON", "\\fI.json\\fR"),
+        ("TOML", "\\fI.toml\\fR"),
+        ("YAML", "\\fI.yaml\\fR and \\fI.yml\\fR"),
+    ] {
+        writeln!(out, ".TP")?;
+        writeln!(out, "\\fB{format}\\fR")?;
+        writeln!(out, "Files with the {extensions} extensions.")?;
+    }
+    writeln!(out, ".PP")?;
+    writeln!(
+        out,
+        "The format of other files and of URLs is detected from the content. \
+         Input compressed with gzip, zstd or xz is decompressed transparently, \
+         and the extension before \\fI.gz\\fR, \\fI.zst\\fR or \\fI.xz\\fR picks the format."
+    )?;
+
+    writeln!(out, ".SH EXIT STATUS")?;
+    writeln!(out, ".TP")?;
+    writeln!(out, "\\fB0\\fR")?;
+    writeln!(out, "Success.")?;
+    for code in ExitCode::ALL {
+        writeln!(out, ".TP")?;
+        writeln!(out, "\\fB{}\\fR", code as i32)?;
+        writeln!(out, "{}", escape(code.description()))?;
+    }
+
+    writeln!(out, ".SH FILES")?;
+    writeln!(out, ".TP")?;
+    writeln!(out, "\\fI~/.config/viz/config.toml\\fR")?;
+    writeln!(
+        out,
+        "Defaults for the options, keyed by their long names, like \\fBindent = 4\\fR. \
+         The directory follows \\fBXDG_CONFIG_HOME\\fR if it is set."
+    )?;
+
+    writeln!(out, ".SH ENVIRONMENT")?;
+    writeln!(out, ".TP")?;
+    writeln!(out, "\\fBVIZ_*\\fR")?;
+    writeln!(
+        out,
+        "Defaults for the options, like \\fBVIZ_INDENT=4\\fR or \\fBVIZ_SORT_KEYS=1\\fR. \
+         They override the config file, and the command line overrides them."
+    )?;
+    writeln!(out, ".TP")?;
+    writeln!(out, "\\fBNO_COLOR\\fR")?;
+    writeln!(
+        out,
+        "Disables colored output when set, even with \\fB\\-\\-force\\-color\\fR."
+    )
+}
+
+/// Returns the full help of the argument.
+fn help(arg: &Arg) -> String {
+    arg.get_long_help()
+        .or_else(|| arg.get_help())
+        .map(|help| help.to_string())
+        .unwrap_or_default()
+}
+
+/// Returns the name of the value that the argument takes.
+fn value_name(arg: &Arg) -> String {
+    arg.get_value_names()
+        .and_then(|names| names.first())
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| arg.get_id().as_str().to_uppercase())
+}
+
+/// Escapes text for roff.
+fn escape(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                return ".sp".to_string();
+            }
+            let line = line.replace('\\', "\\e").replace('-', "\\-");
+            // A line starting with a dot or a quote would be read as a request.
+            if line.starts_with(['.', '\'']) {
+                format!("\\&{line}")
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
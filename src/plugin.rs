@@ -0,0 +1,114 @@
+use std::env;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::processors::json::JSONProcessor;
+use crate::processors::{ParseOptions, Processor, Warning};
+use crate::values::VizValue;
+use vizo_core::{Result, VizError};
+
+/// A processor that runs an external `viz-<lang>` executable.
+///
+/// The executable gets the data on its standard input and must print it as JSON
+/// to its standard output. Its standard error is shown to the user as it is.
+pub struct PluginProcessor {
+    name: String,
+    program: PathBuf,
+}
+
+impl PluginProcessor {
+    /// Finds the `viz-<lang>` executable of the language on `PATH`.
+    pub fn find(language: &str) -> Option<Self> {
+        let valid = !language.is_empty()
+            && language
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return None;
+        }
+
+        let name = format!("viz-{language}");
+        let program = env::split_paths(&env::var_os("PATH")?).find_map(|dir| {
+            let candidates = [dir.join(&name), dir.join(format!("{name}.exe"))];
+            candidates.into_iter().find(|path| is_executable(path))
+        })?;
+        Some(Self { name, program })
+    }
+
+    /// Returns the path to the executable.
+    pub fn program(&self) -> &Path {
+        &self.program
+    }
+}
+
+impl Processor for PluginProcessor {
+    fn process_data<'a>(
+        &self,
+        data: &'a str,
+        options: &ParseOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<VizValue<'a>> {
+        let failed =
+            |e: io::Error| io::Error::new(e.kind(), format!("failed to run {}: {e}", self.name));
+
+        let mut child = Command::new(&self.program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(failed)?;
+
+        // The data is written from another thread, so a plugin that prints while it reads
+        // cannot block on a full pipe.
+        let mut stdin = child.stdin.take();
+        let output = thread::scope(|scope| {
+            scope.spawn(move || {
+                if let Some(stdin) = stdin.as_mut() {
+                    // A plugin may exit without reading all of the data.
+                    let _ = stdin.write_all(data.as_bytes());
+                }
+            });
+            child.wait_with_output()
+        })
+        .map_err(failed)?;
+
+        if !output.status.success() {
+            return Err(
+                io::Error::other(format!("{} failed with {}", self.name, output.status)).into(),
+            );
+        }
+
+        let json = String::from_utf8(output.stdout).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} printed output that is not UTF-8", self.name),
+            )
+        })?;
+        let value = JSONProcessor
+            .process_data(&json, options, warnings)
+            .map_err(|e| match e {
+                VizError::Parse(e) => io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} printed invalid JSON: {}", self.name, e.message),
+                )
+                .into(),
+                e => e,
+            })?;
+        Ok(value.into_owned())
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -0,0 +1,134 @@
+use crate::humanize::format_bytes;
+use std::io::{self, IsTerminal, Read, Write, stderr};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How long reading and parsing may take before the spinner appears.
+const SHOW_DELAY: Duration = Duration::from_millis(200);
+
+/// How often the spinner is redrawn.
+const TICK_INTERVAL: Duration = Duration::from_millis(80);
+
+/// Frames of the spinner animation.
+const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Size of the chunks in which input is read.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A state shared between [`Progress`] and its drawing thread.
+struct State {
+    phase: Mutex<&'static str>,
+    bytes: AtomicU64,
+    done: AtomicBool,
+}
+
+/// A spinner that is displayed on stderr while the input is read and parsed.
+///
+/// The spinner only appears if the work takes longer than [`SHOW_DELAY`] and stderr is a TTY.
+pub struct Progress {
+    state: Arc<State>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Progress {
+    /// Starts a new progress indicator.
+    pub fn start() -> Self {
+        let state = Arc::new(State {
+            phase: Mutex::new("reading"),
+            bytes: AtomicU64::new(0),
+            done: AtomicBool::new(false),
+        });
+
+        let handle = if stderr().is_terminal() {
+            let state = Arc::clone(&state);
+            Some(thread::spawn(move || draw(&state)))
+        } else {
+            None
+        };
+
+        Self { state, handle }
+    }
+
+    /// Creates a progress indicator that never displays anything.
+    pub fn hidden() -> Self {
+        Self {
+            state: Arc::new(State {
+                phase: Mutex::new(""),
+                bytes: AtomicU64::new(0),
+                done: AtomicBool::new(true),
+            }),
+            handle: None,
+        }
+    }
+
+    /// Sets the name of the current phase.
+    pub fn set_phase(&self, phase: &'static str) {
+        if let Ok(mut current) = self.state.phase.lock() {
+            *current = phase;
+        }
+    }
+
+    /// Reads everything from the reader, counting the bytes read.
+    pub fn read_to_end(&self, mut reader: impl Read) -> io::Result<Vec<u8>> {
+        let mut contents = Vec::new();
+        let mut chunk = vec![0; CHUNK_SIZE];
+
+        loop {
+            let read = match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            contents.extend_from_slice(&chunk[..read]);
+            self.state.bytes.fetch_add(read as u64, Ordering::Relaxed);
+        }
+
+        Ok(contents)
+    }
+
+    /// Stops the indicator and erases it from the screen.
+    pub fn finish(&mut self) {
+        self.state.done.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Draws the spinner until the work is done.
+fn draw(state: &State) {
+    let started = Instant::now();
+    let mut shown = false;
+    let mut frame = 0;
+
+    while !state.done.load(Ordering::Relaxed) {
+        if started.elapsed() >= SHOW_DELAY {
+            let phase = state.phase.lock().map(|p| *p).unwrap_or_default();
+            let bytes = state.bytes.load(Ordering::Relaxed);
+            let mut err = stderr().lock();
+            let _ = write!(
+                err,
+                "\r\x1b[2K{} {phase}... {}",
+                FRAMES[frame % FRAMES.len()],
+                format_bytes(bytes)
+            );
+            let _ = err.flush();
+            shown = true;
+            frame += 1;
+        }
+        thread::sleep(TICK_INTERVAL);
+    }
+
+    if shown {
+        let _ = write!(stderr(), "\r\x1b[2K");
+    }
+}
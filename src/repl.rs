@@ -0,0 +1,171 @@
+use crate::prints::{LineLimit, RenderOptions, print_root, print_truncation_marker};
+use crate::query::{Match, query};
+use crate::values::VizValue;
+use anyhow::{Result, bail};
+use colored::Colorize;
+use std::io::{self, BufRead, IsTerminal, Write, stdin, stdout};
+
+const HELP: &str = "\
+Enter a JSON Pointer, like /servers/0, or a JSONPath expression, like $.servers[*].host.
+
+Commands:
+  :help       Show this help
+  :history    List the queries of this session
+  !!          Run the last query again
+  !N          Run the query number N of the history
+  :quit       Leave, like Ctrl-D";
+
+/// Reads queries from stdin and prints the values that match them, until the input ends.
+///
+/// The document is only parsed once, so exploring it is instant.
+pub fn run(data: &VizValue, options: &RenderOptions) -> Result<()> {
+    let interactive = stdin().is_terminal();
+    session(
+        &mut stdin().lock(),
+        &mut stdout().lock(),
+        interactive,
+        data,
+        options,
+    )
+}
+
+/// Answers the queries of the input until it ends.
+///
+/// Only queries that could be evaluated are added to the history, so a typo is not run again
+/// by `!!`.
+fn session(
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+    interactive: bool,
+    data: &VizValue,
+    options: &RenderOptions,
+) -> Result<()> {
+    let mut history: Vec<String> = Vec::new();
+
+    if interactive {
+        writeln!(out, "{}", "Type :help for help.".bright_black())?;
+    }
+    loop {
+        if interactive {
+            write!(out, "{} ", "viz>".cyan().bold())?;
+            out.flush()?;
+        }
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            break;
+        }
+
+        let line = line.trim();
+        let expression = match line {
+            "" => continue,
+            ":q" | ":quit" | ":exit" => break,
+            ":help" => {
+                writeln!(out, "{HELP}")?;
+                continue;
+            }
+            ":history" => {
+                for (id, query) in history.iter().enumerate() {
+                    writeln!(out, "{:>4}  {query}", (id + 1).to_string().bright_black())?;
+                }
+                continue;
+            }
+            _ => match recall(line, &history) {
+                Ok(expression) => expression,
+                Err(e) => {
+                    writeln!(out, "{}: {e}", "error".red().bold())?;
+                    continue;
+                }
+            },
+        };
+
+        match query(data, &expression) {
+            Ok(matches) => {
+                print_matches(out, &matches, options)?;
+                history.push(expression);
+            }
+            Err(e) => writeln!(out, "{}: {e}", "error".red().bold())?,
+        }
+    }
+
+    if interactive {
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Replaces `!!` and `!N` with the queries from the history.
+fn recall(line: &str, history: &[String]) -> Result<String> {
+    let Some(reference) = line.strip_prefix('!') else {
+        return Ok(line.to_string());
+    };
+    let query = match reference {
+        "!" => history.last(),
+        number => match number.parse::<usize>() {
+            Ok(number) => number.checked_sub(1).and_then(|id| history.get(id)),
+            Err(_) => bail!("expected !! or !N, like !3"),
+        },
+    };
+    match query {
+        Some(query) => Ok(query.clone()),
+        None => bail!("no such query in the history"),
+    }
+}
+
+/// Prints the values that match a query, with their paths if there are several.
+fn print_matches(
+    out: &mut impl Write,
+    matches: &[Match],
+    options: &RenderOptions,
+) -> io::Result<()> {
+    match matches {
+        [] => writeln!(out, "{}", "no matches".bright_black())?,
+        [(_, value)] => print_value(out, value, options)?,
+        matches => {
+            for (path, value) in matches {
+                let path = path.to_string();
+                let path = if path.is_empty() { "/" } else { &path };
+                writeln!(out, "{}", path.blue().bold())?;
+                print_value(out, value, options)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_value(out: &mut impl Write, value: &VizValue, options: &RenderOptions) -> io::Result<()> {
+    let mut line_limit = LineLimit::new(options.limit);
+    print_root(out, value.clone(), &mut line_limit, options)?;
+    if let Some(limit) = options.limit.filter(|_| line_limit.is_truncated()) {
+        print_truncation_marker(out, limit, options)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vizo_core::{Format, parse};
+
+    #[test]
+    fn keeps_only_queries_that_were_evaluated_in_the_history() {
+        colored::control::set_override(false);
+        let data = parse(r#"{"name": "vizo"}"#, Format::Json).unwrap();
+        let mut input = "$[\n/name\n!!\n:history\n".as_bytes();
+        let mut out = Vec::new();
+        session(
+            &mut input,
+            &mut out,
+            false,
+            &data,
+            &RenderOptions::default(),
+        )
+        .unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let history: Vec<&str> = out
+            .lines()
+            .skip_while(|line| !line.contains("1  "))
+            .collect();
+        assert_eq!(history, ["   1  /name", "   2  /name"]);
+    }
+}
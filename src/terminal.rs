@@ -0,0 +1,215 @@
+use std::env::var;
+use std::io::{IsTerminal, stdout};
+
+use crate::theme::Background;
+
+/// Returns the width of the terminal in columns, or `None` if the output is not a terminal.
+///
+/// `COLUMNS` takes precedence over the size reported by the terminal.
+pub fn width() -> Option<usize> {
+    if !stdout().is_terminal() {
+        return None;
+    }
+
+    var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse().ok())
+        .filter(|columns| *columns > 0)
+        .or_else(|| query_size().map(|(columns, _)| columns))
+}
+
+/// Returns the height of the terminal in rows, or `None` if the output is not a terminal.
+///
+/// `LINES` takes precedence over the size reported by the terminal.
+pub fn height() -> Option<usize> {
+    if !stdout().is_terminal() {
+        return None;
+    }
+
+    var("LINES")
+        .ok()
+        .and_then(|lines| lines.trim().parse().ok())
+        .filter(|lines| *lines > 0)
+        .or_else(|| query_size().map(|(_, rows)| rows))
+}
+
+/// Returns `true` if the output is a terminal that is known to support OSC 8 hyperlinks.
+///
+/// Terminals do not report it, so it is guessed from the variables they set.
+pub fn supports_hyperlinks() -> bool {
+    if !stdout().is_terminal() {
+        return false;
+    }
+
+    let term = var("TERM").unwrap_or_default();
+    if term == "dumb" {
+        return false;
+    }
+    let program = var("TERM_PROGRAM").unwrap_or_default();
+    let vte_version = var("VTE_VERSION")
+        .ok()
+        .and_then(|version| version.parse::<u32>().ok());
+
+    ["iTerm.app", "WezTerm", "vscode", "ghostty", "Hyper"].contains(&program.as_str())
+        || vte_version.is_some_and(|version| version >= 5000)
+        || ["kitty", "alacritty", "foot", "wezterm"]
+            .iter()
+            .any(|name| term.contains(name))
+        || [
+            "WT_SESSION",
+            "KITTY_WINDOW_ID",
+            "KONSOLE_VERSION",
+            "DOMTERM",
+        ]
+        .iter()
+        .any(|name| var(name).is_ok())
+}
+
+/// Returns the background of the terminal, or `None` if it is not known.
+///
+/// It is read from `COLORFGBG`, which terminals like Konsole and rxvt set, and otherwise asked
+/// from the terminal with an OSC 11 query, which most others answer.
+pub fn background() -> Option<Background> {
+    if !stdout().is_terminal() || var("TERM").is_ok_and(|term| term == "dumb") {
+        return None;
+    }
+    var("COLORFGBG")
+        .ok()
+        .and_then(|value| parse_colorfgbg(&value))
+        .or_else(query_background)
+}
+
+/// Parses `COLORFGBG`, like `15;0`, whose last field is the ANSI color of the background.
+fn parse_colorfgbg(value: &str) -> Option<Background> {
+    let color: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+    // White and the bright colors but bright black are light.
+    Some(if color == 7 || color > 8 {
+        Background::Light
+    } else {
+        Background::Dark
+    })
+}
+
+/// Parses the answer to an OSC 11 query, like `\x1b]11;rgb:ffff/ffff/ffff\x07`, by the
+/// luminance of the color.
+fn parse_osc_background(answer: &str) -> Option<Background> {
+    let (_, rgb) = answer.split_once("rgb:")?;
+    let rgb = rgb.trim_end_matches(['\x07', '\x1b', '\\']);
+    let mut channels = rgb.split('/').map(|channel| {
+        let channel = channel.get(..channel.len().min(4))?;
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        Some(value as f64 / ((1u32 << (4 * channel.len())) - 1) as f64)
+    });
+    let (r, g, b) = (channels.next()??, channels.next()??, channels.next()??);
+    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    Some(if luminance > 0.5 {
+        Background::Light
+    } else {
+        Background::Dark
+    })
+}
+
+/// Asks the terminal for its background color with an OSC 11 query.
+///
+/// The terminal is put into raw mode with `stty` for the answer, which is waited for at most
+/// a tenth of a second, as terminals that do not support the query do not answer at all.
+#[cfg(unix)]
+fn query_background() -> Option<Background> {
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::process::{Command, Stdio};
+
+    let tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let stty = |args: &[&str]| {
+        Command::new("stty")
+            .args(args)
+            .stdin(tty.try_clone().ok()?)
+            .stderr(Stdio::null())
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+    };
+    let saved = stty(&["-g"])?;
+    let saved = String::from_utf8(saved.stdout).ok()?;
+    stty(&["raw", "-echo", "min", "0", "time", "1"])?;
+
+    let mut answer = Vec::new();
+    let mut tty = &tty;
+    if tty
+        .write_all(b"\x1b]11;?\x07")
+        .and_then(|_| tty.flush())
+        .is_ok()
+    {
+        let mut buffer = [0; 64];
+        // The answer ends with BEL or ST, and an empty read means that the time is up.
+        while let Ok(count @ 1..) = tty.read(&mut buffer) {
+            answer.extend_from_slice(&buffer[..count]);
+            if answer.ends_with(b"\x07") || answer.ends_with(b"\x1b\\") || answer.len() > 256 {
+                break;
+            }
+        }
+    }
+    stty(&[saved.trim()]);
+    parse_osc_background(&String::from_utf8_lossy(&answer))
+}
+
+#[cfg(not(unix))]
+fn query_background() -> Option<Background> {
+    None
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+fn query_size() -> Option<(usize, usize)> {
+    use std::ffi::{c_int, c_ulong};
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct WinSize {
+        rows: u16,
+        columns: u16,
+        x_pixels: u16,
+        y_pixels: u16,
+    }
+
+    unsafe extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    const TIOCGWINSZ: c_ulong = 0x5413;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    const TIOCGWINSZ: c_ulong = 0x40087468;
+
+    let mut size = WinSize::default();
+    // SAFETY: TIOCGWINSZ only writes a `winsize` struct, which `WinSize` matches.
+    let result = unsafe { ioctl(1, TIOCGWINSZ, &mut size as *mut WinSize) };
+    (result == 0 && size.columns > 0 && size.rows > 0)
+        .then_some((size.columns as usize, size.rows as usize))
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+)))]
+fn query_size() -> Option<(usize, usize)> {
+    None
+}
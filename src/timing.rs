@@ -0,0 +1,85 @@
+use crate::humanize::format_bytes;
+use crate::verbose::format_duration;
+use colored::Colorize;
+use std::io::{Write, stderr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Durations of the phases and the sizes of the largest input, collected for `--timing`.
+struct Stats {
+    started: Instant,
+    phases: Vec<(&'static str, Duration)>,
+    peak_bytes: usize,
+    peak_nodes: usize,
+}
+
+static STATS: Mutex<Option<Stats>> = Mutex::new(None);
+
+/// Starts collecting durations and sizes, which are printed by [`report`].
+pub fn enable() {
+    if let Ok(mut stats) = STATS.lock() {
+        *stats = Some(Stats {
+            started: Instant::now(),
+            phases: Vec::new(),
+            peak_bytes: 0,
+            peak_nodes: 0,
+        });
+    }
+}
+
+/// Returns `true` if durations and sizes are collected.
+pub fn enabled() -> bool {
+    STATS.lock().is_ok_and(|stats| stats.is_some())
+}
+
+/// Adds the duration to the phase, like `parse`, which adds up over several files.
+pub fn record(phase: &'static str, duration: Duration) {
+    if let Ok(mut stats) = STATS.lock()
+        && let Some(stats) = stats.as_mut()
+    {
+        match stats.phases.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, total)) => *total += duration,
+            None => stats.phases.push((phase, duration)),
+        }
+    }
+}
+
+/// Records the size of an input and the number of values in it, keeping the largest ones.
+pub fn record_size(bytes: usize, nodes: usize) {
+    if let Ok(mut stats) = STATS.lock()
+        && let Some(stats) = stats.as_mut()
+    {
+        stats.peak_bytes = stats.peak_bytes.max(bytes);
+        stats.peak_nodes = stats.peak_nodes.max(nodes);
+    }
+}
+
+/// Prints the collected durations and sizes to stderr, if they were collected.
+pub fn report() {
+    let Ok(stats) = STATS.lock() else {
+        return;
+    };
+    let Some(stats) = stats.as_ref() else {
+        return;
+    };
+
+    let phases: Vec<String> = stats
+        .phases
+        .iter()
+        .map(|(phase, duration)| format!("{phase} {}", format_duration(*duration)))
+        .chain([format!(
+            "total {}",
+            format_duration(stats.started.elapsed())
+        )])
+        .collect();
+    let mut err = stderr().lock();
+    // Like logs, the numbers are not worth failing over a closed stderr.
+    let _ = writeln!(err, "{}: {}", "timing".bright_black(), phases.join(", "));
+    let _ = writeln!(
+        err,
+        "{}: peak input {}, peak {} values",
+        "timing".bright_black(),
+        format_bytes(stats.peak_bytes as u64),
+        stats.peak_nodes
+    );
+}
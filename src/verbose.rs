@@ -0,0 +1,36 @@
+use colored::Colorize;
+use std::fmt;
+use std::io::{Write, stderr};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+/// Sets how much is logged: `0` logs nothing, `1` logs decisions and timings,
+/// and `2` also logs the details behind them, like every format that was tried.
+pub fn set_level(level: u8) {
+    LEVEL.store(level, Ordering::Relaxed);
+}
+
+/// Returns `true` if messages of the level are logged.
+pub fn enabled(level: u8) -> bool {
+    level <= LEVEL.load(Ordering::Relaxed)
+}
+
+/// Logs the message to stderr if its level is enabled.
+pub fn log(level: u8, message: fmt::Arguments) {
+    if enabled(level) {
+        // Like warnings, logs are not worth failing over a closed stderr.
+        let _ = writeln!(stderr().lock(), "{}: {message}", "verbose".bright_black());
+    }
+}
+
+/// Formats a duration for logs, like `12.3 ms`.
+pub fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_secs_f64() * 1000.0;
+    if millis < 1000.0 {
+        format!("{millis:.1} ms")
+    } else {
+        format!("{:.2} s", millis / 1000.0)
+    }
+}
@@ -0,0 +1,228 @@
+use crate::app::{Language, ReadOptions, get_file_content};
+use crate::progress::Progress;
+use crate::terminal;
+use anyhow::Result;
+use colored::Colorize;
+use std::fs;
+use std::io::{self, Write, stdout};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+/// How often the watched file is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Keeps track of the lines that are currently displayed on the screen.
+#[derive(Default)]
+struct Screen {
+    lines: Vec<String>,
+
+    /// Rows that each of the lines takes, or `None` if they were drawn from the top,
+    /// because the size of the terminal was not known or the render did not fit.
+    rows: Option<Vec<usize>>,
+
+    /// Width of the terminal the rows were counted for.
+    width: usize,
+}
+
+impl Screen {
+    /// Displays new lines, repainting only those that differ from the previous render.
+    ///
+    /// Lines that are wider than the terminal wrap onto several rows, so the rows are counted
+    /// to find where each line starts. Once a line takes another number of rows than before,
+    /// the lines after it move, and the rest of the screen is drawn again. Renders that are
+    /// taller than the screen scroll, so they are always drawn from the top.
+    fn repaint(
+        &mut self,
+        lines: Vec<String>,
+        size: Option<(usize, usize)>,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        let rows = size.and_then(|(width, height)| {
+            let rows: Vec<usize> = lines.iter().map(|line| rows(line, width)).collect();
+            // The cursor ends up on the row after the last line, which has to fit too.
+            (rows.iter().sum::<usize>() < height).then_some(rows)
+        });
+        let width = size.map_or(0, |(width, _)| width);
+
+        let mut frame = String::new();
+        match (&self.rows, &rows) {
+            (Some(previous), Some(current)) if self.width == width => {
+                let mut row = 1;
+                let mut moved = false;
+                for (id, line) in lines.iter().enumerate() {
+                    if previous.get(id) != Some(&current[id]) {
+                        moved = true;
+                        frame.push_str(&format!("\x1b[{row};1H\x1b[J"));
+                        for line in &lines[id..] {
+                            frame.push_str(line);
+                            frame.push('\n');
+                        }
+                        break;
+                    }
+                    if self.lines[id] != *line {
+                        for offset in 0..current[id] {
+                            frame.push_str(&format!("\x1b[{};1H\x1b[2K", row + offset));
+                        }
+                        frame.push_str(&format!("\x1b[{row};1H{line}"));
+                    }
+                    row += current[id];
+                }
+                if !moved {
+                    // Clears lines that are left over from a longer previous render.
+                    frame.push_str(&format!("\x1b[{row};1H\x1b[J"));
+                }
+            }
+            _ => {
+                frame.push_str("\x1b[H\x1b[J");
+                for line in &lines {
+                    frame.push_str(line);
+                    frame.push('\n');
+                }
+            }
+        }
+
+        // The render is written at once, so it does not flicker.
+        out.write_all(frame.as_bytes())?;
+        out.flush()?;
+        self.lines = lines;
+        self.rows = rows;
+        self.width = width;
+        Ok(())
+    }
+}
+
+/// Returns how many rows of a terminal of the width the line takes once it wraps.
+fn rows(line: &str, width: usize) -> usize {
+    visible_width(line).div_ceil(width.max(1)).max(1)
+}
+
+/// Returns the number of characters of the line that are shown, without escape sequences
+/// like colors and hyperlinks.
+fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            width += 1;
+            continue;
+        }
+        match chars.next() {
+            // CSI sequences, like colors, end with a letter.
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC sequences, like hyperlinks, end with BEL or ST.
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next() == Some('\\')) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    width
+}
+
+/// Watches the file and re-renders it every time it changes.
+///
+/// The `render` callback receives the file contents, its language and a buffer to render into.
+pub fn watch<F>(path: &str, options: ReadOptions, mut render: F) -> Result<()>
+where
+    F: FnMut(&str, &Language, &mut Vec<u8>) -> Result<()>,
+{
+    let mut screen = Screen::default();
+    let mut last_modified: Option<Option<SystemTime>> = None;
+    let mut out = stdout();
+
+    loop {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+
+        if last_modified != Some(modified) {
+            last_modified = Some(modified);
+
+            let mut buffer = Vec::new();
+            let result = get_file_content(path, &options, &Progress::hidden(), &mut Vec::new())
+                .and_then(|(contents, extension)| render(&contents, &extension, &mut buffer));
+
+            let lines = match result {
+                Ok(()) => String::from_utf8_lossy(&buffer)
+                    .lines()
+                    .map(str::to_string)
+                    .collect(),
+                Err(e) => vec![format!("{}: {}", "error".red().bold(), e)],
+            };
+
+            let size = terminal::width().zip(terminal::height());
+            screen.repaint(lines, size, &mut out)?;
+        }
+
+        sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn repaints_only_the_rows_of_changed_lines() {
+        let mut screen = Screen::default();
+        let size = Some((10, 24));
+        screen
+            .repaint(lines(&["a", "b", "c"]), size, &mut Vec::new())
+            .unwrap();
+
+        let mut out = Vec::new();
+        screen
+            .repaint(lines(&["a", "x", "c"]), size, &mut out)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[2;1H\x1b[2K\x1b[2;1Hx\x1b[4;1H\x1b[J"
+        );
+    }
+
+    #[test]
+    fn redraws_the_lines_after_one_that_wraps_onto_more_rows() {
+        let mut screen = Screen::default();
+        let size = Some((10, 24));
+        screen
+            .repaint(lines(&["a", "b", "c"]), size, &mut Vec::new())
+            .unwrap();
+
+        let mut out = Vec::new();
+        let long = "\x1b[31m".to_string() + &"b".repeat(15) + "\x1b[0m";
+        screen
+            .repaint(lines(&["a", &long, "c"]), size, &mut out)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("\x1b[2;1H\x1b[J{long}\nc\n")
+        );
+    }
+
+    #[test]
+    fn draws_renders_taller_than_the_screen_from_the_top() {
+        let mut screen = Screen::default();
+        let size = Some((10, 3));
+        screen
+            .repaint(lines(&["a", "b"]), size, &mut Vec::new())
+            .unwrap();
+
+        let mut out = Vec::new();
+        screen
+            .repaint(lines(&["a", "b", "c"]), size, &mut out)
+            .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[H\x1b[Ja\nb\nc\n");
+    }
+}
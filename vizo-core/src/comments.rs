@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+use std::mem;
+
+use crate::Format;
+use crate::values::escape_pointer;
+
+/// Comments of a document by the JSON Pointer of the key that follows them, like `/server/host`.
+///
+/// Every comment is a whole line with its `#`, without the indentation.
+pub type Comments = HashMap<String, Vec<String>>;
+
+/// What a line of a document holds.
+#[derive(Debug, Clone, PartialEq)]
+enum Line {
+    /// A comment on a line of its own.
+    Comment,
+
+    /// The starts of values, with the JSON Pointers to them from the outermost one, as a line
+    /// of YAML may start several, like `- name: a`.
+    Key(Vec<String>),
+
+    /// Anything else, like blank lines or lines of multi-line strings.
+    Other,
+}
+
+/// Collects the comments of a TOML or YAML document and attaches them to the key that follows them.
+///
+/// This is a best effort that reads the lines of the document without parsing it, so it can
+/// be wrong for unusual layouts, like multi-line flow collections in YAML. Comments at the end
+/// of lines and in multi-line arrays are not collected, and JSON has no comments at all.
+pub fn extract(text: &str, format: Format) -> Comments {
+    let mut comments = Comments::new();
+    let mut pending = Vec::new();
+    for (line, kind) in text.lines().zip(lines(text, format)) {
+        match kind {
+            Line::Comment => pending.push(line.trim().to_string()),
+            Line::Key(paths) if !pending.is_empty() => {
+                comments.insert(paths[0].clone(), mem::take(&mut pending));
+            }
+            _ => {}
+        }
+    }
+    comments
+}
+
+/// Inserts the comments into a TOML or YAML document, before the lines of their keys.
+///
+/// The comments are indented like the keys, and ones of keys that the document does not have
+/// are dropped.
+pub fn insert(text: &str, format: Format, comments: &Comments) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (line, kind) in text.lines().zip(lines(text, format)) {
+        if let Line::Key(paths) = kind {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            for comment in paths.iter().filter_map(|path| comments.get(path)).flatten() {
+                out.push_str(indent);
+                out.push_str(comment);
+                out.push('\n');
+            }
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Tells what each line of the document holds.
+fn lines(text: &str, format: Format) -> Vec<Line> {
+    match format {
+        Format::Json => text.lines().map(|_| Line::Other).collect(),
+        Format::Toml => toml_lines(text),
+        Format::Yaml => yaml_lines(text),
+    }
+}
+
+/// A step from a collection to one of its values, owned as it comes from the text.
+#[derive(Debug, Clone)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+fn pointer<'s>(segments: impl IntoIterator<Item = &'s Segment>) -> String {
+    segments
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Key(key) => format!("/{}", escape_pointer(key)),
+            Segment::Index(index) => format!("/{index}"),
+        })
+        .collect()
+}
+
+fn yaml_lines(text: &str) -> Vec<Line> {
+    let mut lines = Vec::new();
+    // Keys and items that contain the current line, with the columns they start at.
+    let mut stack: Vec<(usize, Segment)> = Vec::new();
+    // The column of the key of a block scalar, like `script: |`, whose lines are skipped.
+    let mut block_scalar: Option<usize> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let mut column = line.len() - trimmed.len();
+        if let Some(parent) = block_scalar {
+            if trimmed.is_empty() || column > parent {
+                lines.push(Line::Other);
+                continue;
+            }
+            block_scalar = None;
+        }
+        if trimmed.starts_with('#') {
+            lines.push(Line::Comment);
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with("---") || trimmed.starts_with("...") {
+            lines.push(Line::Other);
+            continue;
+        }
+
+        let mut starts = Vec::new();
+        let mut rest = trimmed;
+        // A line may hold several starts, like `- name: a`, which is an item and a key.
+        loop {
+            if let Some(item) = rest
+                .strip_prefix('-')
+                .filter(|item| item.is_empty() || item.starts_with(' '))
+            {
+                let mut index = 0;
+                while let Some((start, segment)) = stack.last() {
+                    match segment {
+                        Segment::Index(previous) if *start == column => index = previous + 1,
+                        _ if *start > column => {}
+                        _ => break,
+                    }
+                    stack.pop();
+                }
+                stack.push((column, Segment::Index(index)));
+                starts.push(pointer(stack.iter().map(|(_, segment)| segment)));
+
+                let value = item.trim_start();
+                if value.starts_with(['|', '>']) {
+                    block_scalar = Some(column);
+                }
+                column += rest.len() - value.len();
+                rest = value;
+                continue;
+            }
+            if let Some((key, value)) = split_yaml_key(rest) {
+                while stack.last().is_some_and(|(start, _)| *start >= column) {
+                    stack.pop();
+                }
+                stack.push((column, Segment::Key(key)));
+                starts.push(pointer(stack.iter().map(|(_, segment)| segment)));
+                if value.starts_with(['|', '>']) {
+                    block_scalar = Some(column);
+                }
+            }
+            break;
+        }
+
+        // Lines that do not start anything continue a value, like a long plain string.
+        if starts.is_empty() {
+            lines.push(Line::Other);
+        } else {
+            lines.push(Line::Key(starts));
+        }
+    }
+    lines
+}
+
+/// Splits a line of YAML into its key and the value after it, like `host: localhost`.
+fn split_yaml_key(text: &str) -> Option<(String, &str)> {
+    let (key, rest) = match text.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let mut escaped = false;
+            let end = text[1..].char_indices().find_map(|(id, c)| {
+                let found = c == quote && !escaped;
+                escaped = quote == '"' && c == '\\' && !escaped;
+                found.then_some(id + 1)
+            })?;
+            let key = &text[1..end];
+            let key = if quote == '"' {
+                key.replace("\\\"", "\"").replace("\\\\", "\\")
+            } else {
+                key.replace("''", "'")
+            };
+            (key, text[end + 1..].trim_start().strip_prefix(':')?)
+        }
+        '{' | '[' | '?' | '#' | '&' | '*' | '!' | '|' | '>' => return None,
+        _ => {
+            let end = text
+                .match_indices(':')
+                .map(|(id, _)| id)
+                .find(|id| text[id + 1..].is_empty() || text[id + 1..].starts_with([' ', '\t']))?;
+            (text[..end].trim_end().to_string(), &text[end + 1..])
+        }
+    };
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    Some((key, rest.trim_start()))
+}
+
+fn toml_lines(text: &str) -> Vec<Line> {
+    let mut lines = Vec::new();
+    // The path to the current table.
+    let mut table = String::new();
+    // The last indices of arrays of tables, like `[[servers]]`, by their paths.
+    let mut arrays: HashMap<String, usize> = HashMap::new();
+    // The state of values that span several lines.
+    let mut open_string: Option<&str> = None;
+    let mut depth = 0;
+
+    for line in text.lines() {
+        if open_string.is_some() || depth > 0 {
+            let mut rest = line;
+            if let Some(delimiter) = open_string {
+                match line.find(delimiter) {
+                    Some(end) => {
+                        open_string = None;
+                        rest = &line[end + delimiter.len()..];
+                    }
+                    None => rest = "",
+                }
+            }
+            if open_string.is_none() {
+                open_string = scan_toml_value(rest, &mut depth);
+            }
+            lines.push(Line::Other);
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            lines.push(Line::Comment);
+            continue;
+        }
+
+        let kind = if let Some(header) = trimmed.strip_prefix("[[") {
+            parse_toml_key(header).map(|(keys, _)| {
+                table = toml_table(&keys, true, &mut arrays);
+                Line::Key(vec![table.clone()])
+            })
+        } else if let Some(header) = trimmed.strip_prefix('[') {
+            parse_toml_key(header).map(|(keys, _)| {
+                table = toml_table(&keys, false, &mut arrays);
+                Line::Key(vec![table.clone()])
+            })
+        } else {
+            parse_toml_key(trimmed).and_then(|(keys, rest)| {
+                let value = rest.strip_prefix('=')?;
+                open_string = scan_toml_value(value, &mut depth);
+                let keys: Vec<Segment> = keys.into_iter().map(Segment::Key).collect();
+                Some(Line::Key(vec![format!("{table}{}", pointer(&keys))]))
+            })
+        };
+        lines.push(kind.unwrap_or(Line::Other));
+    }
+    lines
+}
+
+/// Returns the path to a table, where arrays of tables are followed by their last index.
+fn toml_table(keys: &[String], is_array: bool, arrays: &mut HashMap<String, usize>) -> String {
+    let mut path = String::new();
+    for (id, key) in keys.iter().enumerate() {
+        path.push_str(&pointer([&Segment::Key(key.clone())]));
+        let index = if is_array && id + 1 == keys.len() {
+            let index = arrays
+                .entry(path.clone())
+                .and_modify(|index| *index += 1)
+                .or_insert(0);
+            Some(*index)
+        } else {
+            arrays.get(&path).copied()
+        };
+        if let Some(index) = index {
+            path.push_str(&format!("/{index}"));
+        }
+    }
+    path
+}
+
+/// Parses a dotted key of TOML, like `server."host name"`, and returns the rest of the text.
+fn parse_toml_key(text: &str) -> Option<(Vec<String>, &str)> {
+    let mut keys = Vec::new();
+    let mut rest = text.trim_start();
+    loop {
+        let (key, after) = match rest.chars().next()? {
+            quote @ ('"' | '\'') => {
+                let mut escaped = false;
+                let end = rest[1..].char_indices().find_map(|(id, c)| {
+                    let found = c == quote && !escaped;
+                    escaped = quote == '"' && c == '\\' && !escaped;
+                    found.then_some(id + 1)
+                })?;
+                let key = &rest[1..end];
+                let key = if quote == '"' {
+                    key.replace("\\\"", "\"").replace("\\\\", "\\")
+                } else {
+                    key.to_string()
+                };
+                (key, &rest[end + 1..])
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                    .unwrap_or(rest.len());
+                if end == 0 {
+                    return None;
+                }
+                (rest[..end].to_string(), &rest[end..])
+            }
+        };
+        keys.push(key);
+        rest = after.trim_start();
+        match rest.strip_prefix('.') {
+            Some(after) => rest = after.trim_start(),
+            None => return Some((keys, rest)),
+        }
+    }
+}
+
+/// Follows the brackets of a value of TOML, and returns the delimiter of a multi-line
+/// string that is not closed on this line.
+fn scan_toml_value(text: &str, depth: &mut usize) -> Option<&'static str> {
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        rest = match c {
+            '#' => return None,
+            '"' | '\'' => {
+                let delimiter = if c == '"' { "\"\"\"" } else { "'''" };
+                if let Some(after) = rest.strip_prefix(delimiter) {
+                    match after.find(delimiter) {
+                        Some(end) => &after[end + delimiter.len()..],
+                        None => return Some(delimiter),
+                    }
+                } else {
+                    let mut escaped = false;
+                    let end = rest[1..].char_indices().find_map(|(id, d)| {
+                        let found = d == c && !escaped;
+                        escaped = c == '"' && d == '\\' && !escaped;
+                        found.then_some(id + 2)
+                    });
+                    &rest[end.unwrap_or(rest.len())..]
+                }
+            }
+            '[' | '{' => {
+                *depth += 1;
+                &rest[1..]
+            }
+            ']' | '}' => {
+                *depth = depth.saturating_sub(1);
+                &rest[1..]
+            }
+            c => &rest[c.len_utf8()..],
+        };
+    }
+    None
+}
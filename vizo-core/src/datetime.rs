@@ -0,0 +1,551 @@
+use std::borrow::Cow;
+
+use crate::error::{Result, VizError};
+use crate::values::VizValue;
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+
+/// A date or a date-time of ISO 8601, like `2024-11-02` or `2024-11-02T14:03:00.5+01:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DateTime<'a> {
+    /// Seconds since the epoch of the time as it is written, before its offset is applied.
+    pub local: i64,
+
+    /// Digits of the fraction of a second, without the dot.
+    pub fraction: &'a str,
+
+    /// Offset from UTC in seconds, `None` if the time does not have one.
+    pub offset: Option<i64>,
+
+    /// The character between the date and the time, like `T`.
+    pub separator: char,
+}
+
+impl DateTime<'_> {
+    /// Returns the seconds since the epoch, taking times without an offset as UTC.
+    pub fn seconds(&self) -> i64 {
+        self.local - self.offset.unwrap_or(0)
+    }
+}
+
+/// Returns `true` if the text is an ISO 8601 date or date-time, like the ones of RFC 3339.
+pub fn is_datetime(text: &str) -> bool {
+    parse(text).is_some()
+}
+
+/// Parses an ISO 8601 date or date-time, like `2024-11-02` or `2024-11-02T14:03:00+01:00`.
+pub(crate) fn parse(text: &str) -> Option<DateTime<'_>> {
+    let bytes = text.as_bytes();
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = bytes.get(range)?;
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        std::str::from_utf8(digits).ok()?.parse().ok()
+    };
+
+    if bytes.get(4) != Some(&b'-') || bytes.get(7) != Some(&b'-') {
+        return None;
+    }
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    if bytes.len() == 10 {
+        return Some(DateTime {
+            local: days * DAY,
+            fraction: "",
+            offset: None,
+            separator: 'T',
+        });
+    }
+
+    if !matches!(bytes[10], b'T' | b't' | b' ') || bytes.get(13) != Some(&b':') {
+        return None;
+    }
+    let (hour, minute) = (number(11..13)?, number(14..16)?);
+    let mut i = 16;
+    let mut second = 0;
+    if bytes.get(i) == Some(&b':') {
+        second = number(17..19)?;
+        i = 19;
+    }
+    let mut fraction = "";
+    if bytes.get(i) == Some(&b'.') {
+        let start = i + 1;
+        i = start;
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        fraction = &text[start..i];
+    }
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let offset = match bytes.get(i) {
+        None => None,
+        Some(b'Z' | b'z') if i + 1 == bytes.len() => Some(0),
+        Some(&sign @ (b'+' | b'-')) => {
+            let (hours, minutes) = match bytes.len() - i {
+                6 if bytes[i + 3] == b':' => (number(i + 1..i + 3)?, number(i + 4..i + 6)?),
+                5 => (number(i + 1..i + 3)?, number(i + 3..i + 5)?),
+                3 => (number(i + 1..i + 3)?, 0),
+                _ => return None,
+            };
+            let offset = hours * HOUR + minutes * MINUTE;
+            Some(if sign == b'-' { -offset } else { offset })
+        }
+        _ => return None,
+    };
+
+    Some(DateTime {
+        local: days * DAY + hour * HOUR + minute * MINUTE + second,
+        fraction,
+        offset,
+        separator: bytes[10] as char,
+    })
+}
+
+/// Converts the date-times with an offset in the value to the time zone, like
+/// `2024-11-02T15:03:00+01:00` to `2024-11-02T14:03:00Z` for UTC.
+///
+/// Dates and date-times without an offset do not tell their time zone, so they are kept.
+pub fn normalize(value: &mut VizValue, zone: &TimeZone) {
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        match value {
+            VizValue::String(text) | VizValue::DateTime(text) => {
+                if let Some(converted) = convert(text, zone) {
+                    *text = Cow::Owned(converted);
+                }
+            }
+            VizValue::Array(vec) => stack.extend(vec.iter_mut()),
+            VizValue::Object(map) => stack.extend(map.values_mut()),
+            VizValue::Tagged(_, value) => stack.push(value),
+            _ => {}
+        }
+    }
+}
+
+/// Converts a date-time with an offset to the time zone, `None` if the text is not one.
+fn convert(text: &str, zone: &TimeZone) -> Option<String> {
+    let datetime = parse(text).filter(|datetime| datetime.offset.is_some())?;
+    let seconds = datetime.seconds();
+    let offset = zone.offset_at(seconds);
+    let local = seconds + offset;
+
+    let (year, month, day) = civil_from_days(local.div_euclid(DAY));
+    let time = local.rem_euclid(DAY);
+    let fraction = if datetime.fraction.is_empty() {
+        String::new()
+    } else {
+        format!(".{}", datetime.fraction)
+    };
+    let offset = if offset == 0 {
+        "Z".to_string()
+    } else {
+        let sign = if offset < 0 { '-' } else { '+' };
+        let offset = offset.abs();
+        format!("{sign}{:02}:{:02}", offset / HOUR, offset % HOUR / MINUTE)
+    };
+    Some(format!(
+        "{year:04}-{month:02}-{day:02}{}{:02}:{:02}:{:02}{fraction}{offset}",
+        datetime.separator,
+        time / HOUR,
+        time % HOUR / MINUTE,
+        time % MINUTE
+    ))
+}
+
+/// A time zone, with the offsets from UTC it had over time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimeZone {
+    /// Times, in seconds since the epoch, from which the offsets are used, in order.
+    transitions: Vec<(i64, i64)>,
+
+    /// The offset before the first transition.
+    initial: i64,
+
+    /// The rule for the times after the last transition.
+    rule: Option<Rule>,
+}
+
+/// A rule of a time zone with an offset for standard time and, possibly, one for daylight time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    standard: i64,
+    daylight: Option<Daylight>,
+}
+
+/// When daylight time starts and ends, in local time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Daylight {
+    offset: i64,
+    start: (RuleDate, i64),
+    end: (RuleDate, i64),
+}
+
+/// A day of the year in a rule of a POSIX time zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleDate {
+    /// `Jn`, from 1 to 365, where February 29 is never counted.
+    Julian(i64),
+
+    /// `n`, from 0 to 365, where February 29 is counted in leap years.
+    Ordinal(i64),
+
+    /// `Mm.w.d`, the day `d` (0 is Sunday) of the week `w` (5 is the last one) of the month `m`.
+    Weekday { month: i64, week: i64, day: i64 },
+}
+
+impl TimeZone {
+    /// Returns UTC, which has no offset.
+    pub fn utc() -> Self {
+        Self::default()
+    }
+
+    /// Reads a time zone from a TZif file, like `/etc/localtime`.
+    pub fn from_tzif(bytes: &[u8]) -> Result<Self> {
+        let error = || VizError::invalid("time zone", "the file is not a valid TZif file");
+        let mut reader = Reader { bytes, position: 0 };
+        let (version, counts) = read_tzif_header(&mut reader).ok_or_else(error)?;
+        // Version 1 only has 32-bit times, and later versions repeat the data with 64-bit ones.
+        let (counts, time_size) = if version >= b'2' {
+            skip_tzif_data(&mut reader, &counts, 4).ok_or_else(error)?;
+            (read_tzif_header(&mut reader).ok_or_else(error)?.1, 8)
+        } else {
+            (counts, 4)
+        };
+        let [_, _, leap_count, time_count, type_count, char_count] = counts;
+
+        let mut times = Vec::with_capacity(time_count);
+        for _ in 0..time_count {
+            times.push(reader.int(time_size).ok_or_else(error)?);
+        }
+        let indices = reader.take(time_count).ok_or_else(error)?.to_vec();
+        let mut types = Vec::with_capacity(type_count);
+        for _ in 0..type_count {
+            let offset = reader.int(4).ok_or_else(error)?;
+            let is_dst = reader.take(2).ok_or_else(error)?[0] != 0;
+            types.push((offset, is_dst));
+        }
+        let skipped = char_count + leap_count * (time_size + 4) + counts[0] + counts[1];
+        reader.take(skipped).ok_or_else(error)?;
+
+        let offset = |index: u8| types.get(index as usize).map(|(offset, _)| *offset);
+        let transitions = times
+            .into_iter()
+            .zip(indices)
+            .map(|(time, index)| Some((time, offset(index)?)))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(error)?;
+        // Times before the first transition use the first type of standard time.
+        let initial = types
+            .iter()
+            .find(|(_, is_dst)| !is_dst)
+            .or(types.first())
+            .map_or(0, |(offset, _)| *offset);
+
+        let rule = if version >= b'2' {
+            let footer = String::from_utf8_lossy(&bytes[reader.position..]);
+            let footer = footer.trim_matches('\n');
+            if footer.is_empty() {
+                None
+            } else {
+                Some(parse_rule(footer).ok_or_else(error)?)
+            }
+        } else {
+            None
+        };
+        Ok(TimeZone {
+            transitions,
+            initial,
+            rule,
+        })
+    }
+
+    /// Reads a time zone from a POSIX `TZ` value, like `CET-1CEST,M3.5.0,M10.5.0/3`.
+    pub fn from_posix(text: &str) -> Result<Self> {
+        let rule = parse_rule(text)
+            .ok_or_else(|| VizError::invalid("time zone", format!("invalid TZ value: {text}")))?;
+        Ok(TimeZone {
+            rule: Some(rule),
+            ..TimeZone::default()
+        })
+    }
+
+    /// Returns the offset from UTC in seconds at a time, in seconds since the epoch.
+    pub fn offset_at(&self, seconds: i64) -> i64 {
+        let after = self
+            .transitions
+            .partition_point(|(time, _)| *time <= seconds);
+        match (after, &self.rule) {
+            (after, Some(rule)) if after == self.transitions.len() => rule.offset_at(seconds),
+            (0, _) => self.initial,
+            (after, _) => self.transitions[after - 1].1,
+        }
+    }
+}
+
+impl Rule {
+    fn offset_at(&self, seconds: i64) -> i64 {
+        let Some(daylight) = &self.daylight else {
+            return self.standard;
+        };
+        let (year, _, _) = civil_from_days((seconds + self.standard).div_euclid(DAY));
+        let start = daylight.start.0.days(year) * DAY + daylight.start.1 - self.standard;
+        let end = daylight.end.0.days(year) * DAY + daylight.end.1 - daylight.offset;
+        // In the southern hemisphere, daylight time spans the end of the year.
+        let is_daylight = if start < end {
+            (start..end).contains(&seconds)
+        } else {
+            !(end..start).contains(&seconds)
+        };
+        if is_daylight {
+            daylight.offset
+        } else {
+            self.standard
+        }
+    }
+}
+
+impl RuleDate {
+    /// Returns the day of the year as days since the epoch.
+    fn days(self, year: i64) -> i64 {
+        let first = days_from_civil(year, 1, 1);
+        let is_leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        match self {
+            RuleDate::Julian(day) => first + day - 1 + i64::from(is_leap && day >= 60),
+            RuleDate::Ordinal(day) => first + day,
+            RuleDate::Weekday { month, week, day } => {
+                let start = days_from_civil(year, month, 1);
+                let next = if month == 12 {
+                    days_from_civil(year + 1, 1, 1)
+                } else {
+                    days_from_civil(year, month + 1, 1)
+                };
+                // The epoch was a Thursday.
+                let weekday = (start + 4).rem_euclid(7);
+                let mut days = start + (day - weekday).rem_euclid(7) + (week - 1) * 7;
+                while days >= next {
+                    days -= 7;
+                }
+                days
+            }
+        }
+    }
+}
+
+/// Parses a POSIX `TZ` rule, like `EST5EDT,M3.2.0,M11.1.0`.
+fn parse_rule(text: &str) -> Option<Rule> {
+    let mut rest = skip_zone_name(text)?;
+    // POSIX offsets are west of Greenwich, so they have the opposite sign.
+    let (standard, after) = parse_duration(rest)?;
+    let standard = -standard;
+    rest = after;
+    if rest.is_empty() {
+        return Some(Rule {
+            standard,
+            daylight: None,
+        });
+    }
+
+    rest = skip_zone_name(rest)?;
+    let offset = match parse_duration(rest) {
+        Some((offset, after)) => {
+            rest = after;
+            -offset
+        }
+        None => standard + HOUR,
+    };
+    // Without dates, the rules of the United States are used, like most systems do.
+    let (start, end) = match rest.strip_prefix(',') {
+        Some(dates) => {
+            let (start, dates) = parse_rule_date(dates)?;
+            let (end, dates) = parse_rule_date(dates.strip_prefix(',')?)?;
+            if !dates.is_empty() {
+                return None;
+            }
+            (start, end)
+        }
+        None if rest.is_empty() => (
+            (
+                RuleDate::Weekday {
+                    month: 3,
+                    week: 2,
+                    day: 0,
+                },
+                2 * HOUR,
+            ),
+            (
+                RuleDate::Weekday {
+                    month: 11,
+                    week: 1,
+                    day: 0,
+                },
+                2 * HOUR,
+            ),
+        ),
+        None => return None,
+    };
+    Some(Rule {
+        standard,
+        daylight: Some(Daylight { offset, start, end }),
+    })
+}
+
+/// Skips the name of a zone, like `CET` or `<+03>`.
+fn skip_zone_name(text: &str) -> Option<&str> {
+    if let Some(quoted) = text.strip_prefix('<') {
+        return Some(&quoted[quoted.find('>')? + 1..]);
+    }
+    let end = text
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(text.len());
+    (end >= 3).then(|| &text[end..])
+}
+
+/// Parses a signed duration, like `-1`, `5:30` or `2:00:00`, in seconds.
+fn parse_duration(text: &str) -> Option<(i64, &str)> {
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(text) => (-1, text),
+        None => (1, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let mut seconds = 0;
+    let mut rest = text;
+    for (id, unit) in [HOUR, MINUTE, 1].into_iter().enumerate() {
+        if id > 0 {
+            match rest.strip_prefix(':') {
+                Some(after) => rest = after,
+                None => break,
+            }
+        }
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return None;
+        }
+        seconds += rest[..end].parse::<i64>().ok()? * unit;
+        rest = &rest[end..];
+    }
+    Some((sign * seconds, rest))
+}
+
+/// Parses a date of a rule with its optional time, like `M3.5.0/3`.
+fn parse_rule_date(text: &str) -> Option<((RuleDate, i64), &str)> {
+    let end = text.find([',', '/']).unwrap_or(text.len());
+    let (date, mut rest) = text.split_at(end);
+    let date = if let Some(julian) = date.strip_prefix('J') {
+        RuleDate::Julian(julian.parse().ok()?)
+    } else if let Some(weekday) = date.strip_prefix('M') {
+        let mut parts = weekday.split('.').map(str::parse);
+        let (month, week, day) = (
+            parts.next()?.ok()?,
+            parts.next()?.ok()?,
+            parts.next()?.ok()?,
+        );
+        if parts.next().is_some() || !(1..=12).contains(&month) {
+            return None;
+        }
+        RuleDate::Weekday { month, week, day }
+    } else {
+        RuleDate::Ordinal(date.parse().ok()?)
+    };
+
+    let mut time = 2 * HOUR;
+    if let Some(after) = rest.strip_prefix('/') {
+        (time, rest) = parse_duration(after)?;
+    }
+    Some(((date, time), rest))
+}
+
+/// Reads the version and the six counts of a header of a TZif file.
+fn read_tzif_header(reader: &mut Reader) -> Option<(u8, [usize; 6])> {
+    if reader.take(4)? != b"TZif" {
+        return None;
+    }
+    let version = reader.take(16)?[0];
+    let mut counts = [0; 6];
+    for count in &mut counts {
+        *count = usize::try_from(reader.int(4)?).ok()?;
+    }
+    Some((version, counts))
+}
+
+/// Skips the data of a TZif file that follows a header.
+fn skip_tzif_data(reader: &mut Reader, counts: &[usize; 6], time_size: usize) -> Option<()> {
+    let [
+        ut_count,
+        std_count,
+        leap_count,
+        time_count,
+        type_count,
+        char_count,
+    ] = *counts;
+    let size = time_count * (time_size + 1)
+        + type_count * 6
+        + char_count
+        + leap_count * (time_size + 4)
+        + std_count
+        + ut_count;
+    reader.take(size).map(|_| ())
+}
+
+/// Reads big-endian data, like the one of TZif files.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position.checked_add(len)?)?;
+        self.position += len;
+        Some(bytes)
+    }
+
+    /// Reads a signed integer of 4 or 8 bytes.
+    fn int(&mut self, size: usize) -> Option<i64> {
+        let bytes = self.take(size)?;
+        Some(match size {
+            4 => i32::from_be_bytes(bytes.try_into().ok()?) as i64,
+            _ => i64::from_be_bytes(bytes.try_into().ok()?),
+        })
+    }
+}
+
+/// Returns the number of days since the epoch of a date in the proleptic Gregorian calendar.
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the year, the month and the day of a number of days since the epoch.
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,855 @@
+use colored::Color;
+use std::io::{self, Write};
+
+use indexmap::IndexMap;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::mem;
+
+use crate::hash::canonical;
+use crate::path::{Path, PathSegment};
+use crate::prints::{DisplayType, LineLimit, RenderOptions, format_key, print_prettij};
+use crate::processors::Warning;
+use crate::theme::Paint;
+use crate::values::{VizValue, escape_pointer};
+
+/// A difference between two documents.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change<'v, 'a> {
+    /// The value is only in the new document.
+    Added {
+        path: Path<'v>,
+        value: &'v VizValue<'a>,
+    },
+
+    /// The value is only in the old document.
+    Removed {
+        path: Path<'v>,
+        value: &'v VizValue<'a>,
+    },
+
+    /// The value was replaced by a different one.
+    Changed {
+        path: Path<'v>,
+        old: &'v VizValue<'a>,
+        new: &'v VizValue<'a>,
+    },
+}
+
+impl<'v> Change<'v, '_> {
+    /// Returns the path to the value that changed.
+    pub fn path(&self) -> &Path<'v> {
+        match self {
+            Change::Added { path, .. } | Change::Removed { path, .. } => path,
+            Change::Changed { path, .. } => path,
+        }
+    }
+}
+
+/// A unit of work of the comparison, kept on a stack instead of recursing.
+enum Task<'v, 'a> {
+    Compare(Path<'v>, &'v VizValue<'a>, &'v VizValue<'a>),
+    Emit(Change<'v, 'a>),
+}
+
+/// Compares two documents and returns the changes in the order of the documents.
+///
+/// Objects are compared by their keys, so the order of keys does not matter,
+/// and arrays are compared element by element. Numbers are equal if they have
+/// the same value, like `1` and `1.0`, so documents can be compared across formats.
+pub fn diff<'v, 'a>(old: &'v VizValue<'a>, new: &'v VizValue<'a>) -> Vec<Change<'v, 'a>> {
+    let mut changes = Vec::new();
+    let mut stack = vec![Task::Compare(Path::root(), old, new)];
+
+    while let Some(task) = stack.pop() {
+        let (path, old, new) = match task {
+            Task::Emit(change) => {
+                changes.push(change);
+                continue;
+            }
+            Task::Compare(path, old, new) => (path, old, new),
+        };
+
+        let child = |segment| {
+            let mut path = path.clone();
+            path.push(segment);
+            path
+        };
+        let mut tasks = Vec::new();
+        match (old, new) {
+            (VizValue::Object(old_map), VizValue::Object(new_map)) => {
+                for (key, old_value) in old_map {
+                    let path = child(PathSegment::Key(key));
+                    tasks.push(match new_map.get(key) {
+                        Some(new_value) => Task::Compare(path, old_value, new_value),
+                        None => Task::Emit(Change::Removed {
+                            path,
+                            value: old_value,
+                        }),
+                    });
+                }
+                for (key, new_value) in new_map {
+                    if !old_map.contains_key(key) {
+                        tasks.push(Task::Emit(Change::Added {
+                            path: child(PathSegment::Key(key)),
+                            value: new_value,
+                        }));
+                    }
+                }
+            }
+            (VizValue::Array(old_vec), VizValue::Array(new_vec)) => {
+                for index in 0..old_vec.len().max(new_vec.len()) {
+                    let path = child(PathSegment::Index(index));
+                    tasks.push(match (old_vec.get(index), new_vec.get(index)) {
+                        (Some(old), Some(new)) => Task::Compare(path, old, new),
+                        (Some(value), None) => Task::Emit(Change::Removed { path, value }),
+                        (None, Some(value)) => Task::Emit(Change::Added { path, value }),
+                        (None, None) => unreachable!("the index is within one of the arrays"),
+                    });
+                }
+            }
+            (VizValue::Tagged(old_tag, old), VizValue::Tagged(new_tag, new))
+                if old_tag == new_tag =>
+            {
+                tasks.push(Task::Compare(path.clone(), old, new));
+            }
+            (old, new) if is_same(old, new) => {}
+            (old, new) => tasks.push(Task::Emit(Change::Changed {
+                path: path.clone(),
+                old,
+                new,
+            })),
+        }
+        stack.extend(tasks.into_iter().rev());
+    }
+
+    changes
+}
+
+/// Finds elements of arrays that equal an earlier element of the same array.
+///
+/// Returns the path to every repeated element with the path to its first occurrence.
+/// Elements are compared by their [canonical] form, so the order of keys does not matter
+/// and numbers are compared by their value.
+pub fn duplicates<'v>(value: &'v VizValue) -> Vec<(Path<'v>, Path<'v>)> {
+    let mut duplicates = Vec::new();
+    value.walk(|path, value| {
+        let VizValue::Array(vec) = value else {
+            return;
+        };
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for (index, element) in vec.iter().enumerate() {
+            match seen.entry(canonical(element)) {
+                Entry::Occupied(first) => {
+                    let mut duplicate = path.clone();
+                    duplicate.push(PathSegment::Index(index));
+                    let mut original = path.clone();
+                    original.push(PathSegment::Index(*first.get()));
+                    duplicates.push((duplicate, original));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(index);
+                }
+            }
+        }
+    });
+    duplicates
+}
+
+/// Returns `true` if the values are equal, where numbers are compared by their value.
+///
+/// Dates are equal to strings with the same text, since formats without dates,
+/// like JSON, keep them as strings. Arrays and objects are compared element by element.
+pub(crate) fn is_same(old: &VizValue, new: &VizValue) -> bool {
+    let is_number = |value: &VizValue| {
+        matches!(
+            value,
+            VizValue::Number(_) | VizValue::Float(_) | VizValue::RawNumber(_)
+        )
+    };
+
+    let mut stack = vec![(old, new)];
+    while let Some((old, new)) = stack.pop() {
+        match (old, new) {
+            (VizValue::DateTime(text), VizValue::String(other))
+            | (VizValue::String(other), VizValue::DateTime(text)) => {
+                if text != other {
+                    return false;
+                }
+            }
+            (VizValue::Array(old_vec), VizValue::Array(new_vec)) => {
+                if old_vec.len() != new_vec.len() {
+                    return false;
+                }
+                stack.extend(old_vec.iter().zip(new_vec));
+            }
+            (VizValue::Object(old_map), VizValue::Object(new_map)) => {
+                if old_map.len() != new_map.len() {
+                    return false;
+                }
+                for (key, old) in old_map {
+                    let Some(new) = new_map.get(key) else {
+                        return false;
+                    };
+                    stack.push((old, new));
+                }
+            }
+            (VizValue::Tagged(old_tag, old), VizValue::Tagged(new_tag, new)) => {
+                if old_tag != new_tag {
+                    return false;
+                }
+                stack.push((old, new));
+            }
+            (old, new) if is_number(old) && is_number(new) => {
+                if old != new && !old.as_f64().is_some_and(|old| Some(old) == new.as_f64()) {
+                    return false;
+                }
+            }
+            (old, new) => {
+                if old != new {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Converts the changes to a JSON Patch (RFC 6902) that turns the old document into the new one.
+///
+/// Elements removed from the end of an array are removed from the last one,
+/// so the indices stay valid while the patch is applied.
+pub fn to_json_patch(changes: &[Change]) -> VizValue<'static> {
+    let mut operations = Vec::new();
+    let mut id = 0;
+
+    while id < changes.len() {
+        // Elements of an array are only removed from its end, in a single run.
+        let run = match &changes[id] {
+            Change::Removed { path, .. } if matches!(path.last(), Some(PathSegment::Index(_))) => {
+                let parent = &path.segments()[..path.len() - 1];
+                changes[id..]
+                    .iter()
+                    .take_while(|change| {
+                        matches!(change, Change::Removed { path, .. }
+                            if path.len() == parent.len() + 1
+                                && path.segments().starts_with(parent)
+                                && matches!(path.last(), Some(PathSegment::Index(_))))
+                    })
+                    .count()
+            }
+            _ => 1,
+        };
+
+        for change in changes[id..id + run].iter().rev() {
+            let (op, value) = match change {
+                Change::Added { value, .. } => ("add", Some(*value)),
+                Change::Removed { .. } => ("remove", None),
+                Change::Changed { new, .. } => ("replace", Some(*new)),
+            };
+            let mut operation = IndexMap::new();
+            operation.insert(Cow::Borrowed("op"), VizValue::String(Cow::Borrowed(op)));
+            operation.insert(
+                Cow::Borrowed("path"),
+                VizValue::String(Cow::Owned(change.path().to_string())),
+            );
+            if let Some(value) = value {
+                operation.insert(Cow::Borrowed("value"), value.clone().into_owned());
+            }
+            operations.push(VizValue::Object(operation));
+        }
+        id += run;
+    }
+
+    VizValue::Array(operations)
+}
+
+/// Builds a JSON Merge Patch (RFC 7396) that turns the old document into the new one.
+///
+/// Removed keys are set to `null` and arrays are replaced as a whole, as merge patches
+/// cannot express changes of single elements. Returns an empty object if nothing changed.
+///
+/// Merge patches cannot set keys to `null` either, so such keys are removed when the patch
+/// is applied, and a warning is added for each of them.
+pub fn to_merge_patch(
+    old: &VizValue,
+    new: &VizValue,
+    warnings: &mut Vec<Warning>,
+) -> VizValue<'static> {
+    merge_patch(old, new, "", warnings).unwrap_or_else(|| VizValue::Object(IndexMap::new()))
+}
+
+fn merge_patch(
+    old: &VizValue,
+    new: &VizValue,
+    path: &str,
+    warnings: &mut Vec<Warning>,
+) -> Option<VizValue<'static>> {
+    let (VizValue::Object(old_map), VizValue::Object(new_map)) = (old, new) else {
+        if is_same(old, new) {
+            return None;
+        }
+        warn_about_nulls(new, path, warnings);
+        return Some(new.clone().into_owned());
+    };
+
+    let mut patch = IndexMap::new();
+    for key in old_map.keys().filter(|key| !new_map.contains_key(*key)) {
+        patch.insert(Cow::Owned(key.to_string()), VizValue::Null);
+    }
+    for (key, new_value) in new_map {
+        let path = format!("{path}/{}", escape_pointer(key));
+        let value = match old_map.get(key) {
+            Some(old_value) => merge_patch(old_value, new_value, &path, warnings),
+            None => {
+                warn_about_nulls(new_value, &path, warnings);
+                Some(new_value.clone().into_owned())
+            }
+        };
+        if let Some(value) = value {
+            patch.insert(Cow::Owned(key.to_string()), value);
+        }
+    }
+    (!patch.is_empty()).then_some(VizValue::Object(patch))
+}
+
+/// Warns about the nulls of the value that a merge patch would remove instead of setting,
+/// which are the value itself and the values of keys of its objects, but not the elements
+/// of arrays.
+fn warn_about_nulls(value: &VizValue, path: &str, warnings: &mut Vec<Warning>) {
+    let mut stack = vec![(value, path.to_string())];
+    while let Some((value, path)) = stack.pop() {
+        match value.untagged() {
+            // A null document replaces the old one as a whole.
+            VizValue::Null if !path.is_empty() => warnings.push(Warning {
+                path: Some(path),
+                message:
+                    "merge patches cannot set values to null, so the patch removes the key instead"
+                        .to_string(),
+            }),
+            VizValue::Object(map) => stack.extend(
+                map.iter()
+                    .rev()
+                    .map(|(key, value)| (value, format!("{path}/{}", escape_pointer(key)))),
+            ),
+            _ => {}
+        }
+    }
+}
+
+/// Prints the changes as a tree, where parents of the changes are printed around them,
+/// lines of added values start with `+` and lines of removed values with `-`.
+///
+/// A changed value is printed as the removed old value followed by the added new one.
+pub fn print_diff(
+    out: &mut impl Write,
+    changes: &[Change],
+    options: &RenderOptions,
+) -> io::Result<()> {
+    // Elements of a root array are printed inside of its brackets, like by `print_root`.
+    let root_array = changes.iter().any(|change| {
+        matches!(
+            change.path().segments().first(),
+            Some(PathSegment::Index(_))
+        )
+    });
+    let base = if root_array { options.indent } else { 0 };
+    if root_array {
+        print_context(
+            out,
+            &options.active_theme().punctuation.paint("[").to_string(),
+        )?;
+    }
+
+    // Segments of the parents that are currently open, with their closing brackets.
+    let mut open: Vec<(PathSegment, char)> = Vec::new();
+    for change in changes {
+        let segments = change.path().segments();
+        let parents = segments.len().saturating_sub(1);
+        let common = open
+            .iter()
+            .zip(segments)
+            .take(parents)
+            .take_while(|((open, _), segment)| open == *segment)
+            .count();
+
+        while open.len() > common {
+            close_parent(out, &mut open, base, options)?;
+        }
+        for depth in common..parents {
+            let (bracket, closing) = match segments[depth + 1] {
+                PathSegment::Key(_) => ("{", '}'),
+                PathSegment::Index(_) => ("[", ']'),
+            };
+            let indent = " ".repeat(base + depth * options.indent);
+            let bracket = options.active_theme().punctuation.paint(bracket);
+            let line = match segments[depth] {
+                PathSegment::Key(name) => {
+                    let equals = options.active_theme().punctuation.paint("=");
+                    format!("{indent}{} {equals} {bracket}", format_key(name, options))
+                }
+                PathSegment::Index(_) => format!("{indent}{bracket}"),
+            };
+            print_context(out, &line)?;
+            open.push((segments[depth], closing));
+        }
+
+        let indent = base + parents * options.indent;
+        let name = match segments.last() {
+            Some(PathSegment::Key(name)) => Some(*name),
+            _ => None,
+        };
+        match change {
+            Change::Added { value, .. } => print_change(out, '+', name, value, indent, options)?,
+            Change::Removed { value, .. } => print_change(out, '-', name, value, indent, options)?,
+            Change::Changed { old, new, .. } => {
+                print_change(out, '-', name, old, indent, options)?;
+                print_change(out, '+', name, new, indent, options)?;
+            }
+        }
+    }
+
+    while !open.is_empty() {
+        close_parent(out, &mut open, base, options)?;
+    }
+    if root_array {
+        print_context(
+            out,
+            &options.active_theme().punctuation.paint("]").to_string(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Prints the closing bracket of the innermost open parent.
+fn close_parent(
+    out: &mut impl Write,
+    open: &mut Vec<(PathSegment, char)>,
+    base: usize,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    if let Some((_, bracket)) = open.pop() {
+        let indent = " ".repeat(base + open.len() * options.indent);
+        let bracket = options
+            .active_theme()
+            .punctuation
+            .paint(&bracket.to_string());
+        print_context(out, &format!("{indent}{bracket}"))?;
+    }
+    Ok(())
+}
+
+/// Prints a line around the changes, aligned with the lines of the changes.
+fn print_context(out: &mut impl Write, line: &str) -> io::Result<()> {
+    writeln!(out, "  {line}")
+}
+
+/// Prints every line of an added or removed value after the marker.
+fn print_change(
+    out: &mut impl Write,
+    marker: char,
+    name: Option<&str>,
+    value: &VizValue,
+    indent: usize,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let display_type = match name {
+        Some(_) => DisplayType::Key,
+        None => DisplayType::ArrayElement,
+    };
+    let mut rendered = Vec::new();
+    print_prettij(
+        &mut rendered,
+        name.unwrap_or_default(),
+        value.clone(),
+        indent,
+        display_type,
+        true,
+        &mut LineLimit::new(None),
+        options,
+    )?;
+
+    let marker = match marker {
+        '+' => options.paint(Paint::new(Color::Green).bold()).paint("+"),
+        _ => options.paint(Paint::new(Color::Red).bold()).paint("-"),
+    };
+    for line in String::from_utf8_lossy(&rendered).lines() {
+        writeln!(out, "{marker} {line}")?;
+    }
+    Ok(())
+}
+
+/// A row of the side-by-side view: a line of the old document, of the new one, or both.
+enum Row {
+    Same(usize, usize),
+    Changed(Option<usize>, Option<usize>),
+}
+
+/// Prints both documents in two columns of the width, with the lines that differ marked.
+///
+/// The documents are rendered as trees and their lines are aligned, so lines that are
+/// in both documents are next to each other, and removed and added lines face each other.
+pub fn print_side_by_side(
+    out: &mut impl Write,
+    old: &VizValue,
+    new: &VizValue,
+    width: usize,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let column = (width.saturating_sub(3) / 2).max(20);
+    // Strings are wrapped to the column, and links and swatches would throw off the widths.
+    let options = &RenderOptions {
+        width: Some(column - 2),
+        hyperlinks: false,
+        color_swatches: false,
+        ..options.clone()
+    };
+    // Lines are compared and measured without colors.
+    let uncolored = &RenderOptions {
+        color: Some(false),
+        ..options.clone()
+    };
+    let (old_plain, old_colored) = (render_lines(old, uncolored), render_lines(old, options));
+    let (new_plain, new_colored) = (render_lines(new, uncolored), render_lines(new, options));
+    // Lines are aligned by what they mean, so `1` and `1.0` face each other unmarked.
+    let old_keys = comparison_lines(old, &old_plain, uncolored);
+    let new_keys = comparison_lines(new, &new_plain, uncolored);
+
+    let separator = options.active_theme().punctuation.paint("│");
+    let (red, green) = (Paint::new(Color::Red), Paint::new(Color::Green));
+    let (removed, added) = (
+        options.paint(red.bold()).paint("-"),
+        options.paint(green.bold()).paint("+"),
+    );
+    let (red, green) = (options.paint(red), options.paint(green));
+    let cell = |marker: &str, lines: &[String], plain: &[String], id: Option<usize>| {
+        let Some(id) = id else {
+            return " ".repeat(column);
+        };
+        let text: String = plain[id].chars().take(column - 2).collect();
+        let padding = " ".repeat(column - 2 - text.chars().count());
+        match marker {
+            "-" => format!("{removed} {}{padding}", red.paint(&text)),
+            "+" => format!("{added} {}{padding}", green.paint(&text)),
+            // Lines that fit keep their colors, longer ones are cut without them.
+            _ if text.len() == plain[id].len() => format!("  {}{padding}", lines[id]),
+            _ => format!("  {text}{padding}"),
+        }
+    };
+
+    for row in align(&old_keys, &new_keys) {
+        let (left, right) = match row {
+            Row::Same(old, new) => (
+                cell(" ", &old_colored, &old_plain, Some(old)),
+                cell(" ", &new_colored, &new_plain, Some(new)),
+            ),
+            Row::Changed(old, new) => (
+                cell("-", &old_colored, &old_plain, old),
+                cell("+", &new_colored, &new_plain, new),
+            ),
+        };
+        writeln!(out, "{left} {separator} {}", right.trim_end())?;
+    }
+    Ok(())
+}
+
+/// Renders the value into lines.
+fn render_lines(value: &VizValue, options: &RenderOptions) -> Vec<String> {
+    crate::render(value, options)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns the lines to compare the rendered lines of the value by, where numbers that are
+/// the same, like `1` and `1.0`, and dates and strings with the same text are written alike,
+/// like [`diff`] compares them.
+///
+/// If that changes how many lines there are, the rendered lines are compared as they are.
+fn comparison_lines(value: &VizValue, plain: &[String], options: &RenderOptions) -> Vec<String> {
+    let lines = render_lines(&normalize(value), options);
+    if lines.len() == plain.len() {
+        lines
+    } else {
+        plain.to_vec()
+    }
+}
+
+/// Returns a copy of the value with every number as a float and every date as a string.
+fn normalize<'a>(value: &VizValue<'a>) -> VizValue<'a> {
+    let mut value = value.clone();
+    let mut stack = vec![&mut value];
+    while let Some(value) = stack.pop() {
+        let number = value.as_f64();
+        match value {
+            VizValue::Array(items) => stack.extend(items.iter_mut()),
+            VizValue::Object(map) => stack.extend(map.values_mut()),
+            VizValue::Tagged(_, inner) => stack.push(inner),
+            VizValue::Number(_) | VizValue::RawNumber(_) if number.is_some() => {
+                *value = VizValue::Float(number.unwrap_or_default());
+            }
+            VizValue::DateTime(text) => *value = VizValue::String(mem::take(text)),
+            _ => {}
+        }
+    }
+    value
+}
+
+/// Aligns the lines of two documents, pairing up removed and added lines.
+fn align(old: &[String], new: &[String]) -> Vec<Row> {
+    let mut rows = Vec::new();
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let flush = |rows: &mut Vec<Row>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        for id in 0..removed.len().max(added.len()) {
+            rows.push(Row::Changed(
+                removed.get(id).copied(),
+                added.get(id).copied(),
+            ));
+        }
+        removed.clear();
+        added.clear();
+    };
+
+    for edit in edit_script(old, new) {
+        match edit {
+            Edit::Same(old, new) => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(Row::Same(old, new));
+            }
+            Edit::Removed(old) => removed.push(old),
+            Edit::Added(new) => added.push(new),
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+/// A step that turns the old lines into the new ones.
+#[derive(Debug, Clone, Copy)]
+enum Edit {
+    Same(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Finds the shortest edit script between the lines with the algorithm of Myers,
+/// which is fast when the documents are mostly the same.
+///
+/// The linear space variant is used, which splits the lines at the middle of the script and
+/// searches both halves again, so unrelated documents do not need a copy of the search for
+/// every edit.
+fn edit_script<'a>(old: &'a [String], new: &'a [String]) -> Vec<Edit> {
+    // Lines are compared as numbers, which is faster than comparing their text.
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    let mut intern = |lines: &'a [String]| -> Vec<usize> {
+        lines
+            .iter()
+            .map(|line| {
+                let next = ids.len();
+                *ids.entry(line.as_str()).or_insert(next)
+            })
+            .collect()
+    };
+    let (a, b) = (intern(old), intern(new));
+
+    let mut edits = Vec::with_capacity(old.len().max(new.len()));
+    diff_range(&a, &b, 0, 0, &mut edits);
+    edits
+}
+
+/// Appends the edits that turn `a` into `b`, whose lines start at `x` and `y` of the documents.
+fn diff_range(a: &[usize], b: &[usize], x: usize, y: usize, edits: &mut Vec<Edit>) {
+    // Lines at the start and the end that are the same do not need to be searched.
+    let prefix = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    edits.extend((0..prefix).map(|id| Edit::Same(x + id, y + id)));
+
+    let (inner_a, inner_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    let (x_inner, y_inner) = (x + prefix, y + prefix);
+    if inner_a.is_empty() {
+        edits.extend((0..inner_b.len()).map(|id| Edit::Added(y_inner + id)));
+    } else if inner_b.is_empty() {
+        edits.extend((0..inner_a.len()).map(|id| Edit::Removed(x_inner + id)));
+    } else {
+        match middle_snake(inner_a, inner_b) {
+            Some((split_x, split_y)) => {
+                let (a1, a2) = inner_a.split_at(split_x);
+                let (b1, b2) = inner_b.split_at(split_y);
+                diff_range(a1, b1, x_inner, y_inner, edits);
+                diff_range(a2, b2, x_inner + split_x, y_inner + split_y, edits);
+            }
+            None => {
+                edits.extend((0..inner_a.len()).map(|id| Edit::Removed(x_inner + id)));
+                edits.extend((0..inner_b.len()).map(|id| Edit::Added(y_inner + id)));
+            }
+        }
+    }
+
+    let (x_end, y_end) = (x + a.len() - suffix, y + b.len() - suffix);
+    edits.extend((0..suffix).map(|id| Edit::Same(x_end + id, y_end + id)));
+}
+
+/// Searches the shortest edit script from both ends at once, and returns the point where
+/// the two searches meet, which splits the script into two halves of about the same size.
+///
+/// Returns `None` if the lines have nothing in common, so every line is removed and added.
+fn middle_snake(a: &[usize], b: &[usize]) -> Option<(usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max_d = (n + m + 1) / 2;
+    let offset = max_d;
+    let length = 2 * max_d + 2;
+    // The furthest point of every diagonal from the start, and from the end backwards.
+    let mut forward = vec![-1isize; length as usize];
+    let mut backward = vec![-1isize; length as usize];
+    forward[(offset + 1) as usize] = 0;
+    backward[(offset + 1) as usize] = 0;
+
+    let delta = n - m;
+    // With an odd difference, the searches meet while going forward, otherwise backward.
+    let front = delta % 2 != 0;
+    // Diagonals that ran off the edges of the graph are not searched any further.
+    let (mut k1_start, mut k1_end, mut k2_start, mut k2_end) = (0, 0, 0, 0);
+
+    for d in 0..max_d {
+        for k1 in (-d + k1_start..=d - k1_end).step_by(2) {
+            let k1_offset = (offset + k1) as usize;
+            let mut x1 = if k1 == -d || (k1 != d && forward[k1_offset - 1] < forward[k1_offset + 1])
+            {
+                forward[k1_offset + 1]
+            } else {
+                forward[k1_offset - 1] + 1
+            };
+            let mut y1 = x1 - k1;
+            while x1 < n && y1 < m && a[x1 as usize] == b[y1 as usize] {
+                x1 += 1;
+                y1 += 1;
+            }
+            forward[k1_offset] = x1;
+            if x1 > n {
+                k1_end += 2;
+            } else if y1 > m {
+                k1_start += 2;
+            } else if front {
+                let k2_offset = offset + delta - k1;
+                if (0..length).contains(&k2_offset) && backward[k2_offset as usize] != -1 {
+                    let x2 = n - backward[k2_offset as usize];
+                    if x1 >= x2 {
+                        return Some((x1 as usize, y1 as usize));
+                    }
+                }
+            }
+        }
+
+        for k2 in (-d + k2_start..=d - k2_end).step_by(2) {
+            let k2_offset = (offset + k2) as usize;
+            let mut x2 =
+                if k2 == -d || (k2 != d && backward[k2_offset - 1] < backward[k2_offset + 1]) {
+                    backward[k2_offset + 1]
+                } else {
+                    backward[k2_offset - 1] + 1
+                };
+            let mut y2 = x2 - k2;
+            while x2 < n && y2 < m && a[(n - x2 - 1) as usize] == b[(m - y2 - 1) as usize] {
+                x2 += 1;
+                y2 += 1;
+            }
+            backward[k2_offset] = x2;
+            if x2 > n {
+                k2_end += 2;
+            } else if y2 > m {
+                k2_start += 2;
+            } else if !front {
+                let k1_offset = offset + delta - k2;
+                if (0..length).contains(&k1_offset) && forward[k1_offset as usize] != -1 {
+                    let x1 = forward[k1_offset as usize];
+                    let y1 = offset + x1 - k1_offset;
+                    if x1 >= n - x2 {
+                        return Some((x1 as usize, y1 as usize));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Length of the longest common subsequence, by dynamic programming.
+    fn lcs(a: &[String], b: &[String]) -> usize {
+        let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                table[i + 1][j + 1] = if x == y {
+                    table[i][j] + 1
+                } else {
+                    table[i][j + 1].max(table[i + 1][j])
+                };
+            }
+        }
+        table[a.len()][b.len()]
+    }
+
+    #[test]
+    fn finds_the_shortest_edit_script() {
+        // A linear congruential generator keeps the cases the same on every run.
+        let mut seed: u64 = 42;
+        let mut next = |limit: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % limit
+        };
+
+        for _ in 0..500 {
+            let mut lines =
+                |len| -> Vec<String> { (0..next(len)).map(|_| next(4).to_string()).collect() };
+            let (old, new) = (lines(20), lines(20));
+            let edits = edit_script(&old, &new);
+
+            let (mut x, mut y, mut same) = (0, 0, 0);
+            for edit in &edits {
+                match *edit {
+                    Edit::Same(a, b) => {
+                        assert_eq!((a, b), (x, y));
+                        assert_eq!(old[a], new[b]);
+                        (x, y, same) = (x + 1, y + 1, same + 1);
+                    }
+                    Edit::Removed(a) => {
+                        assert_eq!(a, x);
+                        x += 1;
+                    }
+                    Edit::Added(b) => {
+                        assert_eq!(b, y);
+                        y += 1;
+                    }
+                }
+            }
+            assert_eq!((x, y), (old.len(), new.len()), "{old:?} {new:?}");
+            assert_eq!(same, lcs(&old, &new), "{old:?} {new:?}");
+        }
+    }
+
+    #[test]
+    fn aligns_numbers_that_are_the_same_in_the_side_by_side_view() {
+        let old = crate::parse(r#"{"n": 1, "m": 2}"#, crate::Format::Json).unwrap();
+        let new = crate::parse(r#"{"n": 1.0, "m": 3}"#, crate::Format::Json).unwrap();
+        let mut out = Vec::new();
+        let options = RenderOptions::builder().color(Some(false)).build();
+        print_side_by_side(&mut out, &old, &new, 80, &options).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].starts_with("  n = 1 "), "{out}");
+        assert!(lines[1].starts_with("- m = 2 "), "{out}");
+    }
+}
@@ -0,0 +1,173 @@
+use std::borrow::Cow;
+
+use crate::processors::json::JSONProcessor;
+use crate::processors::{ParseOptions, Processor};
+use crate::values::VizValue;
+
+/// The tag of strings that were parsed as JSON.
+pub const JSON_TAG: &str = "!json";
+
+/// Parses strings that hold JSON objects or arrays, like `"{\"a\": 1}"`, into values.
+///
+/// The parsed values are tagged with [`JSON_TAG`], so they can be told apart from the
+/// rest of the document, and strings inside of them are parsed too. Strings that are
+/// not valid JSON are kept as they are.
+pub fn expand_json(value: &mut VizValue, options: &ParseOptions) {
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        if let VizValue::String(text) = value
+            && let Some(parsed) = parse_json(text, options)
+        {
+            *value = VizValue::Tagged(Cow::Borrowed(JSON_TAG), Box::new(parsed));
+        }
+        // A value has a single tag, so tagged strings, like decoded base64, get both tags.
+        if let VizValue::Tagged(tag, inner) = value
+            && let VizValue::String(text) = inner.as_ref()
+            && let Some(parsed) = parse_json(text, options)
+        {
+            *tag = Cow::Owned(format!("{tag} {JSON_TAG}"));
+            **inner = parsed;
+        }
+        match value {
+            VizValue::Array(vec) => stack.extend(vec.iter_mut()),
+            VizValue::Object(map) => stack.extend(map.values_mut()),
+            VizValue::Tagged(_, value) => stack.push(value),
+            _ => {}
+        }
+    }
+}
+
+/// Parses the text if it looks like a JSON object or array.
+fn parse_json(text: &str, options: &ParseOptions) -> Option<VizValue<'static>> {
+    let trimmed = text.trim();
+    let looks_like_json = (trimmed.starts_with('{') && trimmed.ends_with('}'))
+        || (trimmed.starts_with('[') && trimmed.ends_with(']'));
+    if !looks_like_json {
+        return None;
+    }
+    // Strict JSON, so text that only looks similar, like `[WARN]`, stays a string.
+    let options = ParseOptions {
+        lenient: false,
+        ..options.clone()
+    };
+    let parsed = JSONProcessor
+        .process_data(trimmed, &options, &mut Vec::new())
+        .ok()?;
+    Some(parsed.into_owned())
+}
+
+/// The tag of strings that were decoded from base64.
+pub const BASE64_TAG: &str = "!base64";
+
+/// How many bytes of binary data are shown in the hex dump.
+const HEX_DUMP_LIMIT: usize = 256;
+
+/// Decodes strings that look like base64, like the data of Kubernetes Secrets.
+///
+/// Strings that decode to text are replaced by the text, and ones that decode to binary
+/// data by a hex dump of it, an array of lines. Both are tagged with [`BASE64_TAG`].
+/// As short words are valid base64 too, only strings with a digit, a `+`, `/` or `=`,
+/// or both cases of letters are decoded, and binary data only for longer strings.
+pub fn expand_base64(value: &mut VizValue) {
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        match value {
+            VizValue::String(text) => {
+                if let Some(decoded) = decode_base64(text) {
+                    *value = VizValue::Tagged(Cow::Borrowed(BASE64_TAG), Box::new(decoded));
+                }
+            }
+            VizValue::Array(vec) => stack.extend(vec.iter_mut()),
+            VizValue::Object(map) => stack.extend(map.values_mut()),
+            VizValue::Tagged(_, value) => stack.push(value),
+            _ => {}
+        }
+    }
+}
+
+/// Decodes the text if it looks like base64, as described by [`expand_base64`].
+fn decode_base64(text: &str) -> Option<VizValue<'static>> {
+    let has_digit_or_symbol = text
+        .bytes()
+        .any(|b| b.is_ascii_digit() || matches!(b, b'+' | b'/' | b'=' | b'-' | b'_'));
+    let has_both_cases = text.bytes().any(|b| b.is_ascii_uppercase())
+        && text.bytes().any(|b| b.is_ascii_lowercase());
+    if text.len() < 8 || !text.len().is_multiple_of(4) || !(has_digit_or_symbol || has_both_cases) {
+        return None;
+    }
+
+    let bytes = base64(text)?;
+    let is_text = std::str::from_utf8(&bytes).is_ok_and(|decoded| {
+        decoded
+            .chars()
+            .all(|c| !c.is_control() || c.is_whitespace())
+    });
+    if is_text {
+        let decoded = String::from_utf8(bytes).expect("the bytes were checked to be UTF-8");
+        Some(VizValue::String(Cow::Owned(decoded)))
+    } else if text.len() >= 32 {
+        Some(hex_dump(&bytes))
+    } else {
+        None
+    }
+}
+
+/// Decodes standard or URL-safe base64 with padding, `None` if it is not valid.
+fn base64(text: &str) -> Option<Vec<u8>> {
+    let data = text.trim_end_matches('=');
+    if text.len() - data.len() > 2 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(data.len() / 4 * 3);
+    let (mut group, mut bits) = (0u32, 0);
+    for b in data.bytes() {
+        let value = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        group = (group << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((group >> bits) as u8);
+            group &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+/// Formats the bytes like `hexdump -C`, one line per 16 bytes.
+fn hex_dump(bytes: &[u8]) -> VizValue<'static> {
+    let mut lines: Vec<VizValue> = bytes
+        .chunks(16)
+        .take(HEX_DUMP_LIMIT / 16)
+        .enumerate()
+        .map(|(id, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{b:02x}")).collect();
+            let text: String = chunk
+                .iter()
+                .map(|b| match b {
+                    0x20..=0x7e => *b as char,
+                    _ => '.',
+                })
+                .collect();
+            VizValue::String(Cow::Owned(format!(
+                "{:08x}  {:<47}  |{text}|",
+                id * 16,
+                hex.join(" ")
+            )))
+        })
+        .collect();
+    if bytes.len() > HEX_DUMP_LIMIT {
+        let more = bytes.len() - HEX_DUMP_LIMIT;
+        lines.push(VizValue::String(Cow::Owned(format!(
+            "... {more} more bytes"
+        ))));
+    }
+    VizValue::Array(lines)
+}
@@ -0,0 +1,100 @@
+use crate::Format;
+use crate::error::{Result, VizError};
+use crate::values::VizValue;
+
+/// Serializes the value in the format, ending with a newline.
+pub fn to_format(value: &VizValue, format: Format) -> Result<String> {
+    match format {
+        Format::Json => to_json(value),
+        Format::Toml => to_toml(value),
+        Format::Yaml => to_yaml(value),
+    }
+}
+
+/// Serializes the value as pretty-printed JSON, ending with a newline.
+pub fn to_json(value: &VizValue) -> Result<String> {
+    let mut json = serde_json::to_string_pretty(value)
+        .map_err(|e| VizError::invalid("JSON", e.to_string()))?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Serializes the value as a YAML document, ending with a newline.
+///
+/// Tags are dropped, like in JSON.
+pub fn to_yaml(value: &VizValue) -> Result<String> {
+    let mut yaml = String::new();
+    yaml::YamlEmitter::new(&mut yaml)
+        .dump(&to_yaml_node(value))
+        .map_err(|e| VizError::invalid("YAML", e.to_string()))?;
+
+    // The emitter starts every document with a marker, which a single document does not need.
+    let mut yaml = yaml.strip_prefix("---\n").unwrap_or(&yaml).to_string();
+    yaml.push('\n');
+    Ok(yaml)
+}
+
+fn to_yaml_node(value: &VizValue) -> yaml::Yaml {
+    match value {
+        VizValue::String(s) | VizValue::DateTime(s) => yaml::Yaml::String(s.to_string()),
+        VizValue::Number(n) => yaml::Yaml::Integer(*n),
+        VizValue::Float(f) if f.is_nan() => yaml::Yaml::Real(".nan".to_string()),
+        VizValue::Float(f) if f.is_infinite() => {
+            let sign = if *f < 0.0 { "-" } else { "" };
+            yaml::Yaml::Real(format!("{sign}.inf"))
+        }
+        VizValue::Float(f) => yaml::Yaml::Real(format!("{f:?}")),
+        VizValue::RawNumber(s) => yaml::Yaml::Real(s.to_string()),
+        VizValue::Null => yaml::Yaml::Null,
+        VizValue::Bool(b) => yaml::Yaml::Boolean(*b),
+        VizValue::Array(vec) => yaml::Yaml::Array(vec.iter().map(to_yaml_node).collect()),
+        VizValue::Object(map) => yaml::Yaml::Hash(
+            map.iter()
+                .map(|(key, value)| (yaml::Yaml::String(key.to_string()), to_yaml_node(value)))
+                .collect(),
+        ),
+        VizValue::Tagged(_, value) => to_yaml_node(value),
+    }
+}
+
+/// Serializes the value as a TOML document.
+///
+/// The value must be an object, and nulls cannot be serialized, as TOML has neither
+/// other root values nor nulls.
+pub fn to_toml(value: &VizValue) -> Result<String> {
+    let toml::Value::Table(table) = to_toml_value(value)? else {
+        return Err(VizError::invalid(
+            "TOML",
+            "only objects can be written as a document",
+        ));
+    };
+    toml::to_string_pretty(&table).map_err(|e| VizError::invalid("TOML", e.to_string()))
+}
+
+fn to_toml_value(value: &VizValue) -> Result<toml::Value> {
+    let value = match value {
+        VizValue::String(s) => toml::Value::String(s.to_string()),
+        VizValue::DateTime(s) => match s.parse() {
+            Ok(datetime) => toml::Value::Datetime(datetime),
+            Err(_) => toml::Value::String(s.to_string()),
+        },
+        VizValue::Number(n) => toml::Value::Integer(*n),
+        VizValue::Float(f) => toml::Value::Float(*f),
+        VizValue::RawNumber(s) => match s.parse() {
+            Ok(f) => toml::Value::Float(f),
+            Err(_) => toml::Value::String(s.to_string()),
+        },
+        VizValue::Null => return Err(VizError::invalid("TOML", "null values cannot be written")),
+        VizValue::Bool(b) => toml::Value::Boolean(*b),
+        VizValue::Array(vec) => {
+            toml::Value::Array(vec.iter().map(to_toml_value).collect::<Result<_>>()?)
+        }
+        VizValue::Object(map) => toml::Value::Table(
+            map.iter()
+                .map(|(key, value)| Ok((key.to_string(), to_toml_value(value)?)))
+                .collect::<Result<_>>()?,
+        ),
+        VizValue::Tagged(_, value) => to_toml_value(value)?,
+    };
+    Ok(value)
+}
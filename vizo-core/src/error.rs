@@ -0,0 +1,82 @@
+use std::fmt;
+use std::io;
+
+use crate::processors::ParseError;
+
+/// A result of parsing or rendering.
+pub type Result<T, E = VizError> = std::result::Result<T, E>;
+
+/// An error of parsing or rendering, which tells what kind of failure happened.
+#[derive(Debug)]
+pub enum VizError {
+    /// Reading or writing failed.
+    Io(io::Error),
+
+    /// The data is invalid at a known location.
+    Parse(ParseError),
+
+    /// The data is invalid, but the location is not known.
+    Invalid {
+        /// Name of the format that failed to parse.
+        format: &'static str,
+
+        /// Description of the error.
+        message: String,
+    },
+
+    /// The document is nested too deep or contains too many values.
+    Limit(String),
+
+    /// There is no processor for the format or extension.
+    UnsupportedFormat(String),
+
+    /// A theme or a color is invalid.
+    Theme(String),
+}
+
+impl VizError {
+    /// Creates an error of invalid data without a location.
+    pub fn invalid(format: &'static str, message: impl Into<String>) -> Self {
+        VizError::Invalid {
+            format,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for VizError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VizError::Io(e) => e.fmt(f),
+            VizError::Parse(e) => e.fmt(f),
+            VizError::Invalid { format, message } => write!(f, "invalid {format}: {message}"),
+            VizError::Limit(message) | VizError::Theme(message) => f.write_str(message),
+            VizError::UnsupportedFormat(format) if format.is_empty() => {
+                f.write_str("unsupported file format.")
+            }
+            VizError::UnsupportedFormat(format) => write!(f, "unsupported file format `{format}`."),
+        }
+    }
+}
+
+impl std::error::Error for VizError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VizError::Io(e) => Some(e),
+            VizError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for VizError {
+    fn from(error: io::Error) -> Self {
+        VizError::Io(error)
+    }
+}
+
+impl From<ParseError> for VizError {
+    fn from(error: ParseError) -> Self {
+        VizError::Parse(error)
+    }
+}
@@ -0,0 +1,346 @@
+use indexmap::IndexMap;
+use std::borrow::Cow;
+use std::str::Chars;
+
+use crate::error::{Result, VizError};
+use crate::values::VizValue;
+
+/// Flattens the value into a map of paths to scalars, like `servers[0].host`.
+///
+/// Keys are joined with dots and indices of arrays are written in brackets. Keys that
+/// contain dots or brackets themselves are written in brackets too, like `labels[app.kubernetes.io/name]`,
+/// and quoted if they contain a closing bracket, like `["k]x"]`, so [`unflatten`] reads them back.
+/// Empty arrays and objects are kept as values, so nothing is lost, and a scalar document
+/// is put under an empty key.
+pub fn flatten<'a>(value: &VizValue<'a>) -> IndexMap<String, VizValue<'a>> {
+    let mut flat = IndexMap::new();
+    let mut stack = vec![(String::new(), value)];
+
+    while let Some((path, value)) = stack.pop() {
+        match value.untagged() {
+            VizValue::Array(vec) if !vec.is_empty() => stack.extend(
+                vec.iter()
+                    .enumerate()
+                    .rev()
+                    .map(|(index, value)| (format!("{path}[{index}]"), value)),
+            ),
+            VizValue::Object(map) if !map.is_empty() => stack.extend(
+                map.iter()
+                    .rev()
+                    .map(|(key, value)| (join_key(&path, key), value)),
+            ),
+            leaf => {
+                flat.insert(path, leaf.clone());
+            }
+        }
+    }
+
+    flat
+}
+
+/// Appends a key of an object to the path.
+fn join_key(path: &str, key: &str) -> String {
+    if key.contains(']') || key.starts_with('"') {
+        let quoted = key.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("{path}[\"{quoted}\"]")
+    } else if key.is_empty() || key.contains(['.', '[']) {
+        format!("{path}[{key}]")
+    } else if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+/// Writes the flat map as a Java properties file, like the ones of Spring.
+///
+/// Strings are written without quotes, and nulls and empty arrays or objects are
+/// written as empty values, since properties only have strings.
+pub fn to_properties(flat: &IndexMap<String, VizValue>) -> String {
+    let mut properties = String::new();
+    for (key, value) in flat {
+        let value = match value.untagged() {
+            VizValue::String(s) | VizValue::DateTime(s) | VizValue::RawNumber(s) => s.to_string(),
+            VizValue::Number(n) => n.to_string(),
+            VizValue::Float(f) => format!("{f:?}"),
+            VizValue::Bool(b) => b.to_string(),
+            _ => String::new(),
+        };
+        properties.push_str(&escape_property(key, true));
+        properties.push('=');
+        properties.push_str(&escape_property(&value, false));
+        properties.push('\n');
+    }
+    properties
+}
+
+/// Escapes the text of a key or value of a properties file.
+fn escape_property(text: &str, is_key: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (id, c) in text.chars().enumerate() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            // Keys end at these, and leading whitespace of values is skipped.
+            '=' | ':' | ' ' if is_key => escaped.extend(['\\', c]),
+            '#' | '!' if is_key && id == 0 => escaped.extend(['\\', c]),
+            ' ' if id == 0 => escaped.push_str("\\ "),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// How many elements of arrays [`unflatten`] fills with nulls, on top of one for every path.
+pub const SKIPPED_ELEMENTS: usize = 1024;
+
+/// Turns a flat map of paths, like the one of [`flatten`], back into nested values.
+///
+/// Brackets with a number are indices of arrays and other brackets are keys, so
+/// `a.b[0]` is the first element of the array `b`, and objects with keys that are
+/// numbers become arrays. Returns an error if two paths disagree, like
+/// `a = 1` and `a.b = 2`.
+///
+/// Indices may skip elements, which are then null, but all arrays together can only skip as
+/// many of them as there are paths, and [`SKIPPED_ELEMENTS`] more, so an index like
+/// `a[4000000000]` cannot exhaust memory.
+pub fn unflatten<'a>(
+    flat: impl IntoIterator<Item = (String, VizValue<'a>)>,
+) -> Result<VizValue<'a>> {
+    let flat: Vec<_> = flat.into_iter().collect();
+    let mut root = VizValue::Null;
+    let mut skipped_budget = flat.len() + SKIPPED_ELEMENTS;
+
+    for (path, value) in flat {
+        let segments = parse_path(&path).map_err(|e| VizError::invalid("path", e))?;
+        let mut current = &mut root;
+        for segment in &segments {
+            current = match segment {
+                Segment::Key(key) => {
+                    if matches!(current, VizValue::Null) {
+                        *current = VizValue::Object(IndexMap::new());
+                    }
+                    let VizValue::Object(map) = current else {
+                        return Err(VizError::invalid(
+                            "path",
+                            format!("{path}: the parent is not an object"),
+                        ));
+                    };
+                    map.entry(Cow::Owned(key.clone())).or_insert(VizValue::Null)
+                }
+                Segment::Index(index) => {
+                    if matches!(current, VizValue::Null) {
+                        *current = VizValue::Array(Vec::new());
+                    }
+                    let VizValue::Array(vec) = current else {
+                        return Err(VizError::invalid(
+                            "path",
+                            format!("{path}: the parent is not an array"),
+                        ));
+                    };
+                    if vec.len() <= *index {
+                        let skipped = index - vec.len();
+                        let len = index.checked_add(1).filter(|_| skipped <= skipped_budget);
+                        let Some(len) = len else {
+                            return Err(VizError::invalid(
+                                "path",
+                                format!(
+                                    "{path}: the index {index} is far past the end of the array"
+                                ),
+                            ));
+                        };
+                        skipped_budget -= skipped;
+                        vec.resize(len, VizValue::Null);
+                    }
+                    &mut vec[*index]
+                }
+            };
+        }
+
+        if !matches!(current, VizValue::Null) {
+            return Err(VizError::invalid(
+                "path",
+                format!("{path}: the value is given more than once"),
+            ));
+        }
+        *current = value;
+    }
+
+    Ok(root)
+}
+
+/// A segment of a flat path.
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Splits a flat path, like `servers[0].host`, into its segments.
+fn parse_path(path: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut rest = path;
+
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix("[\"") {
+            let (key, after) = parse_quoted(quoted)
+                .ok_or_else(|| format!("{path}: a quoted key is not closed"))?;
+            segments.push(Segment::Key(key));
+            rest = after;
+        } else if let Some(inner) = rest.strip_prefix('[') {
+            let end = inner
+                .find(']')
+                .ok_or_else(|| format!("{path}: a bracket is not closed"))?;
+            let name = &inner[..end];
+            segments.push(match name.parse() {
+                Ok(index) => Segment::Index(index),
+                Err(_) => Segment::Key(name.to_string()),
+            });
+            rest = &inner[end + 1..];
+        } else {
+            let rest_of_key = rest.strip_prefix('.').unwrap_or(rest);
+            let end = rest_of_key.find(['.', '[']).unwrap_or(rest_of_key.len());
+            segments.push(Segment::Key(rest_of_key[..end].to_string()));
+            rest = &rest_of_key[end..];
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Reads a key of a path up to its closing `"]`, where `\"` and `\\` are a quote and a backslash.
+///
+/// Returns the key and the rest of the path after the bracket.
+fn parse_quoted(text: &str) -> Option<(String, &str)> {
+    let mut key = String::new();
+    let mut chars = text.char_indices();
+    while let Some((id, c)) = chars.next() {
+        match c {
+            '\\' => key.push(chars.next()?.1),
+            '"' => return Some((key, text[id + 1..].strip_prefix(']')?)),
+            c => key.push(c),
+        }
+    }
+    None
+}
+
+/// Reads a Java properties file into a flat map of its keys to their values.
+///
+/// Values that look like booleans or numbers, like `true` or `8080`, are read as them,
+/// so they keep their types in structured configs, and other values are strings. Lines that end with a backslash continue on the next one, and later values of
+/// a key replace earlier ones, like Java does.
+pub fn parse_properties(text: &str) -> Result<IndexMap<String, VizValue<'static>>> {
+    let mut properties = IndexMap::new();
+    let mut lines = text.lines().enumerate();
+
+    while let Some((id, line)) = lines.next() {
+        let mut line = line.trim_start().to_string();
+        if line.is_empty() || line.starts_with(['#', '!']) {
+            continue;
+        }
+        while ends_with_continuation(&line) {
+            line.pop();
+            match lines.next() {
+                Some((_, next)) => line.push_str(next.trim_start()),
+                None => break,
+            }
+        }
+
+        let error =
+            |message: &str| VizError::invalid("properties", format!("line {}: {message}", id + 1));
+        let (key, rest) = split_property(&line);
+        let value = rest
+            .trim_start()
+            .strip_prefix(['=', ':'])
+            .unwrap_or(rest.trim_start())
+            .trim_start();
+        let key = unescape_property(key).map_err(error)?;
+        let value = unescape_property(value).map_err(error)?;
+        properties.insert(key, property_value(value));
+    }
+
+    Ok(properties)
+}
+
+/// Reads a value of a properties file as a boolean or a number if it looks like one.
+fn property_value(text: String) -> VizValue<'static> {
+    let digits = text.strip_prefix('-').unwrap_or(&text);
+    // Numbers with leading zeros are usually codes, like `007`, and `inf` or `NaN` are words.
+    let is_number = digits.starts_with(|c: char| c.is_ascii_digit())
+        && !(digits.starts_with('0') && digits[1..].starts_with(|c: char| c.is_ascii_digit()))
+        && text.parse::<f64>().is_ok();
+
+    match text.as_str() {
+        "true" => VizValue::Bool(true),
+        "false" => VizValue::Bool(false),
+        _ if is_number => VizValue::from_number(Cow::Owned(text)),
+        _ => VizValue::String(Cow::Owned(text)),
+    }
+}
+
+/// Returns `true` if the line ends with an odd number of backslashes.
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+/// Splits a line of a properties file at the first unescaped `=`, `:` or whitespace.
+fn split_property(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    for (id, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '=' | ':' | ' ' | '\t' | '\x0c' => return (&line[..id], &line[id..]),
+            _ => {}
+        }
+    }
+    (line, "")
+}
+
+/// Resolves the escapes of a key or value of a properties file, like `\n` and `\u00e9`.
+fn unescape_property(text: &str) -> std::result::Result<String, &'static str> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('f') => unescaped.push('\x0c'),
+            Some('u') => {
+                let mut code = unicode_escape(&mut chars)?;
+                // Characters past `\uffff` are written as surrogate pairs, like Java does.
+                if (0xd800..0xdc00).contains(&code) && chars.as_str().starts_with("\\u") {
+                    chars.nth(1);
+                    let low = unicode_escape(&mut chars)?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return Err(MALFORMED_UNICODE);
+                    }
+                    code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                }
+                unescaped.push(char::from_u32(code).ok_or(MALFORMED_UNICODE)?);
+            }
+            Some(c) => unescaped.push(c),
+            None => {}
+        }
+    }
+
+    Ok(unescaped)
+}
+
+const MALFORMED_UNICODE: &str = "malformed \\uXXXX escape";
+
+/// Reads the four hex digits of a `\uXXXX` escape.
+fn unicode_escape(chars: &mut Chars) -> std::result::Result<u32, &'static str> {
+    let hex: String = chars.by_ref().take(4).collect();
+    if hex.len() != 4 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(MALFORMED_UNICODE);
+    }
+    u32::from_str_radix(&hex, 16).map_err(|_| MALFORMED_UNICODE)
+}
@@ -0,0 +1,45 @@
+use crate::values::VizValue;
+
+/// A request of a HAR capture, with what a summary of the capture shows of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry<'v> {
+    /// Method of the request, like `GET`.
+    pub method: &'v str,
+
+    /// URL of the request.
+    pub url: &'v str,
+
+    /// Status of the response, which is `0` for requests that got no response.
+    pub status: Option<i64>,
+
+    /// Time from the start of the request to the end of the response, in milliseconds.
+    pub time: Option<f64>,
+}
+
+/// Returns the entries of a HAR capture, like the ones that browsers export, or `None` if the
+/// value is not one.
+pub fn entries<'v, 'a>(value: &'v VizValue<'a>) -> Option<&'v [VizValue<'a>]> {
+    match value.pointer("/log/entries")?.untagged() {
+        VizValue::Array(entries) => Some(entries),
+        _ => None,
+    }
+}
+
+/// Returns the request and the response of an entry of a HAR capture.
+pub fn entry<'v>(value: &'v VizValue) -> Entry<'v> {
+    Entry {
+        method: value.pointer_str("/request/method").unwrap_or("-"),
+        url: value.pointer_str("/request/url").unwrap_or("-"),
+        status: value
+            .pointer("/response/status")
+            .and_then(|status| match status.untagged() {
+                VizValue::Number(n) => Some(*n),
+                _ => None,
+            }),
+        // Browsers write `-1` for timings that are not known.
+        time: value
+            .pointer("/time")
+            .and_then(VizValue::as_f64)
+            .filter(|time| *time >= 0.0),
+    }
+}
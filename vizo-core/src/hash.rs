@@ -0,0 +1,263 @@
+use std::fmt::Write;
+
+use crate::values::VizValue;
+
+/// A hash function for [`hash`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    /// SHA-256, with 64 hex digits.
+    #[default]
+    Sha256,
+
+    /// SHA-512, with 128 hex digits.
+    Sha512,
+}
+
+impl Algorithm {
+    /// Returns the digest of the bytes.
+    pub fn digest(self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            Algorithm::Sha256 => sha256(bytes).to_vec(),
+            Algorithm::Sha512 => sha512(bytes).to_vec(),
+        }
+    }
+}
+
+/// Returns the digest of the canonical form of the value in hex.
+///
+/// Documents with the same data have the same hash, whatever their format, layout
+/// or order of keys is.
+pub fn hash(value: &VizValue, algorithm: Algorithm) -> String {
+    algorithm
+        .digest(canonical(value).as_bytes())
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// Returns the canonical form of the value: compact JSON with sorted keys.
+///
+/// Numbers are written by their value, so `1.0` and `1` are the same, dates are strings
+/// and tags are dropped, like in JSON, so the form does not depend on the source format.
+pub fn canonical(value: &VizValue) -> String {
+    let mut text = String::new();
+    write_canonical(&mut text, value);
+    text
+}
+
+fn write_canonical(text: &mut String, value: &VizValue) {
+    match value {
+        VizValue::String(s) | VizValue::DateTime(s) => write_string(text, s),
+        VizValue::Number(n) => text.push_str(&n.to_string()),
+        // Integral floats are written without a fraction, like `1`, so they equal integers.
+        VizValue::Float(f) => text.push_str(&f.to_string()),
+        // Large integers are kept exact, and other numbers are written like floats, so `1e3` is `1000`.
+        VizValue::RawNumber(s) => match s.parse::<f64>() {
+            Ok(f) if !is_integer(s) => text.push_str(&f.to_string()),
+            _ => text.push_str(s),
+        },
+        VizValue::Null => text.push_str("null"),
+        VizValue::Bool(b) => text.push_str(if *b { "true" } else { "false" }),
+        VizValue::Array(vec) => {
+            text.push('[');
+            for (id, value) in vec.iter().enumerate() {
+                if id > 0 {
+                    text.push(',');
+                }
+                write_canonical(text, value);
+            }
+            text.push(']');
+        }
+        VizValue::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            text.push('{');
+            for (id, (key, value)) in entries.into_iter().enumerate() {
+                if id > 0 {
+                    text.push(',');
+                }
+                write_string(text, key);
+                text.push(':');
+                write_canonical(text, value);
+            }
+            text.push('}');
+        }
+        VizValue::Tagged(_, value) => write_canonical(text, value),
+    }
+}
+
+fn is_integer(s: &str) -> bool {
+    s.trim_start_matches('-')
+        .bytes()
+        .all(|b| b.is_ascii_digit())
+}
+
+fn write_string(text: &mut String, s: &str) {
+    text.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\t' => text.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(text, "\\u{:04x}", c as u32);
+            }
+            c => text.push(c),
+        }
+    }
+    text.push('"');
+}
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Computes the SHA-256 digest (FIPS 180-4) of the bytes.
+fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    for block in pad(bytes, 64).chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (id, word) in block.chunks_exact(4).enumerate() {
+            w[id] = u32::from_be_bytes(word.try_into().expect("words have 4 bytes"));
+        }
+        for id in 16..64 {
+            let s0 = w[id - 15].rotate_right(7) ^ w[id - 15].rotate_right(18) ^ (w[id - 15] >> 3);
+            let s1 = w[id - 2].rotate_right(17) ^ w[id - 2].rotate_right(19) ^ (w[id - 2] >> 10);
+            w[id] = w[id - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[id - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for id in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA256_K[id])
+                .wrapping_add(w[id]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (value, new) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *value = value.wrapping_add(new);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (chunk, value) in digest.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+#[rustfmt::skip]
+const SHA512_K: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+/// Computes the SHA-512 digest (FIPS 180-4) of the bytes.
+fn sha512(bytes: &[u8]) -> [u8; 64] {
+    let mut state: [u64; 8] = [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ];
+
+    for block in pad(bytes, 128).chunks_exact(128) {
+        let mut w = [0u64; 80];
+        for (id, word) in block.chunks_exact(8).enumerate() {
+            w[id] = u64::from_be_bytes(word.try_into().expect("words have 8 bytes"));
+        }
+        for id in 16..80 {
+            let s0 = w[id - 15].rotate_right(1) ^ w[id - 15].rotate_right(8) ^ (w[id - 15] >> 7);
+            let s1 = w[id - 2].rotate_right(19) ^ w[id - 2].rotate_right(61) ^ (w[id - 2] >> 6);
+            w[id] = w[id - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[id - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for id in 0..80 {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA512_K[id])
+                .wrapping_add(w[id]);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (value, new) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *value = value.wrapping_add(new);
+        }
+    }
+
+    let mut digest = [0u8; 64];
+    for (chunk, value) in digest.chunks_exact_mut(8).zip(state) {
+        chunk.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+/// Pads the message to whole blocks: a one bit, zeros and the length in bits at the end.
+///
+/// The length takes 8 bytes for 64-byte blocks and 16 bytes for 128-byte blocks.
+fn pad(bytes: &[u8], block: usize) -> Vec<u8> {
+    let length = block / 8;
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while padded.len() % block != block - length {
+        padded.push(0);
+    }
+    let bits = (bytes.len() as u128) * 8;
+    padded.extend_from_slice(&bits.to_be_bytes()[16 - length..]);
+    padded
+}
@@ -0,0 +1,536 @@
+use crate::datetime;
+use crate::prints::LineLimit;
+use crate::theme::{Paint, Theme};
+use std::io::{self, Write};
+
+/// A kind of token in the source of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Plain,
+    Key,
+    String,
+    Number,
+    SpecialNumber,
+    Boolean,
+    Null,
+    DateTime,
+    Tag,
+    Punctuation,
+    Comment,
+}
+
+impl Token {
+    fn paint(self, theme: &Theme) -> Paint {
+        match self {
+            Token::Plain => Paint::PLAIN,
+            Token::Key => theme.key,
+            Token::String => theme.string,
+            Token::Number => theme.number,
+            Token::SpecialNumber => theme.special_number,
+            Token::Boolean => theme.boolean,
+            Token::Null => theme.null,
+            Token::DateTime => theme.datetime,
+            Token::Tag => theme.tag,
+            Token::Punctuation => theme.punctuation,
+            Token::Comment => theme.comment,
+        }
+    }
+}
+
+/// Prints the source of the document as is, only coloring its tokens.
+///
+/// The document is not parsed, so the layout, the order of keys and comments are kept,
+/// and invalid documents are shown too. Text in an unknown language is printed plain.
+pub fn highlight(
+    out: &mut impl Write,
+    contents: &str,
+    language: &str,
+    theme: &Theme,
+    limit: &mut LineLimit,
+) -> io::Result<()> {
+    let contents = contents.strip_suffix('\n').unwrap_or(contents);
+    if contents.is_empty() {
+        return Ok(());
+    }
+
+    let tokens = match language {
+        "json" | "har" | "tfstate" => json_tokens(contents),
+        "toml" => toml_tokens(contents),
+        "yaml" | "yml" => yaml_tokens(contents),
+        _ => vec![(Token::Plain, contents)],
+    };
+
+    if !limit.take() {
+        return Ok(());
+    }
+    for (token, text) in tokens {
+        let paint = token.paint(theme);
+        // Lines are painted one by one, so every line can be shown on its own, like in a pager.
+        for (n, line) in text.split('\n').enumerate() {
+            if n > 0 {
+                writeln!(out)?;
+                if !limit.take() {
+                    return Ok(());
+                }
+            }
+            write!(out, "{}", paint.paint(line))?;
+        }
+    }
+    writeln!(out)
+}
+
+/// Tokens of a text, with the text between them kept as plain tokens.
+struct Tokens<'a> {
+    text: &'a str,
+    tokens: Vec<(Token, &'a str)>,
+    end: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(text: &'a str) -> Self {
+        Tokens {
+            text,
+            tokens: Vec::new(),
+            end: 0,
+        }
+    }
+
+    /// Adds the token that spans from `start` to `end` and returns its end.
+    fn push(&mut self, token: Token, start: usize, end: usize) -> usize {
+        if start > self.end {
+            self.tokens
+                .push((Token::Plain, &self.text[self.end..start]));
+        }
+        if end > start {
+            self.tokens.push((token, &self.text[start..end]));
+        }
+        self.end = end;
+        end
+    }
+
+    /// Adds a quoted token with its quotes as punctuation, and returns its end.
+    fn push_quoted(&mut self, token: Token, start: usize, quoted: Quoted) -> usize {
+        let content_start = (start + quoted.quote_len).min(quoted.end);
+        let content_end = if quoted.closed {
+            quoted.end - quoted.quote_len
+        } else {
+            quoted.end
+        };
+        // Dates in strings are colored like the dates of TOML.
+        let token = match token {
+            Token::String if datetime::is_datetime(&self.text[content_start..content_end]) => {
+                Token::DateTime
+            }
+            token => token,
+        };
+        self.push(Token::Punctuation, start, content_start);
+        self.push(token, content_start, content_end);
+        self.push(Token::Punctuation, content_end, quoted.end)
+    }
+
+    /// Adds a comment that lasts until the end of the line, and returns its end.
+    fn push_comment(&mut self, start: usize) -> usize {
+        self.push(Token::Comment, start, line_end(self.text.as_bytes(), start))
+    }
+
+    fn finish(mut self) -> Vec<(Token, &'a str)> {
+        let len = self.text.len();
+        self.push(Token::Plain, len, len);
+        self.tokens
+    }
+}
+
+/// The end of a quoted text.
+#[derive(Clone, Copy)]
+struct Quoted {
+    end: usize,
+    quote_len: usize,
+    closed: bool,
+}
+
+/// Finds the end of the text quoted with `quote` that starts at `start`.
+///
+/// Unless `multiline` is set, a text that is not closed ends at the end of the line.
+fn find_quoted(bytes: &[u8], start: usize, quote: &[u8], escapes: bool, multiline: bool) -> Quoted {
+    let mut i = start + quote.len();
+    while i < bytes.len() {
+        if escapes && bytes[i] == b'\\' {
+            i += 2;
+            continue;
+        }
+        if !multiline && bytes[i] == b'\n' {
+            break;
+        }
+        if bytes[i..].starts_with(quote) {
+            let mut end = i + quote.len();
+            // Multi-line strings may end with quotes right before the closing ones, like `""""`.
+            if quote.len() > 1 {
+                while end < bytes.len() && bytes[end] == quote[0] && end - i < quote.len() + 2 {
+                    end += 1;
+                }
+            }
+            return Quoted {
+                end,
+                quote_len: quote.len(),
+                closed: true,
+            };
+        }
+        i += 1;
+    }
+
+    Quoted {
+        end: i.min(bytes.len()),
+        quote_len: quote.len(),
+        closed: false,
+    }
+}
+
+/// Returns the position of the end of the line that contains `i`.
+fn line_end(bytes: &[u8], i: usize) -> usize {
+    bytes[i..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(bytes.len(), |n| i + n)
+}
+
+/// Returns the end of the run of bytes that match the predicate, starting at `i`.
+fn run_end(bytes: &[u8], i: usize, matches: impl Fn(u8) -> bool) -> usize {
+    bytes[i..]
+        .iter()
+        .position(|&b| !matches(b))
+        .map_or(bytes.len(), |n| i + n)
+}
+
+/// Returns the position of the first byte after `i` that is not a space or a tab.
+fn skip_blanks(bytes: &[u8], i: usize) -> usize {
+    run_end(bytes, i, |b| b == b' ' || b == b'\t')
+}
+
+fn json_tokens(text: &str) -> Vec<(Token, &str)> {
+    let bytes = text.as_bytes();
+    let mut tokens = Tokens::new(text);
+
+    let mut i = 0;
+    while i < bytes.len() {
+        i = match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                let quoted = find_quoted(bytes, i, &[quote], true, false);
+                let after = skip_blanks(bytes, quoted.end);
+                let token = if bytes.get(after) == Some(&b':') {
+                    Token::Key
+                } else {
+                    Token::String
+                };
+                tokens.push_quoted(token, i, quoted)
+            }
+            // Comments are not JSON, but they are common in config files, like JSONC.
+            b'/' if bytes.get(i + 1) == Some(&b'/') => tokens.push_comment(i),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = text[i + 2..].find("*/").map_or(text.len(), |n| i + n + 4);
+                tokens.push(Token::Comment, i, end)
+            }
+            b'{' | b'}' | b'[' | b']' | b',' | b':' => tokens.push(Token::Punctuation, i, i + 1),
+            b'-' | b'+' | b'.' | b'0'..=b'9' => {
+                let end = run_end(bytes, i, |b| {
+                    b.is_ascii_alphanumeric() || matches!(b, b'.' | b'+' | b'-')
+                });
+                let token = if text[i..end]
+                    .trim_start_matches(['-', '+'])
+                    .starts_with(['I', 'N'])
+                {
+                    Token::SpecialNumber
+                } else {
+                    Token::Number
+                };
+                tokens.push(token, i, end)
+            }
+            b if b.is_ascii_alphabetic() || b == b'_' || b == b'$' => {
+                let end = run_end(bytes, i, |b| {
+                    b.is_ascii_alphanumeric() || b == b'_' || b == b'$'
+                });
+                let token = match &text[i..end] {
+                    "true" | "false" => Token::Boolean,
+                    "null" => Token::Null,
+                    "NaN" | "Infinity" => Token::SpecialNumber,
+                    // Unquoted keys, like in JSON5.
+                    _ if bytes.get(skip_blanks(bytes, end)) == Some(&b':') => Token::Key,
+                    _ => Token::Plain,
+                };
+                tokens.push(token, i, end)
+            }
+            _ => i + 1,
+        };
+    }
+
+    tokens.finish()
+}
+
+fn toml_tokens(text: &str) -> Vec<(Token, &str)> {
+    let bytes = text.as_bytes();
+    let mut tokens = Tokens::new(text);
+    let is_bare = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'-';
+
+    // Open arrays and inline tables, and whether a key or a value comes next.
+    let mut nesting: Vec<u8> = Vec::new();
+    let mut expect_key = true;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        i = match byte {
+            b'\n' => {
+                if nesting.is_empty() {
+                    expect_key = true;
+                }
+                i + 1
+            }
+            b' ' | b'\t' | b'\r' => i + 1,
+            b'#' => tokens.push_comment(i),
+            b'"' | b'\'' => {
+                let triple = [byte; 3];
+                let multiline = !expect_key && bytes[i..].starts_with(&triple);
+                let quote: &[u8] = if multiline { &triple } else { &triple[..1] };
+                let quoted = find_quoted(bytes, i, quote, byte == b'"', multiline);
+                let token = if expect_key {
+                    Token::Key
+                } else {
+                    Token::String
+                };
+                tokens.push_quoted(token, i, quoted)
+            }
+            // A header of a table, like `[package]` or `[[bin]]`.
+            b'[' if expect_key && nesting.is_empty() => {
+                let open = if bytes.get(i + 1) == Some(&b'[') {
+                    2
+                } else {
+                    1
+                };
+                let mut j = tokens.push(Token::Punctuation, i, i + open);
+                loop {
+                    j = skip_blanks(bytes, j);
+                    match bytes.get(j) {
+                        Some(b']') => {
+                            let close = if open == 2 && bytes.get(j + 1) == Some(&b']') {
+                                2
+                            } else {
+                                1
+                            };
+                            break tokens.push(Token::Punctuation, j, j + close);
+                        }
+                        Some(b'.') => j = tokens.push(Token::Punctuation, j, j + 1),
+                        Some(&quote @ (b'"' | b'\'')) => {
+                            let quoted = find_quoted(bytes, j, &[quote], quote == b'"', false);
+                            j = tokens.push_quoted(Token::Key, j, quoted);
+                        }
+                        Some(&b) if is_bare(b) => {
+                            j = tokens.push(Token::Key, j, run_end(bytes, j, is_bare));
+                        }
+                        _ => break j,
+                    }
+                }
+            }
+            b'[' | b'{' => {
+                nesting.push(byte);
+                expect_key = byte == b'{';
+                tokens.push(Token::Punctuation, i, i + 1)
+            }
+            b']' | b'}' => {
+                nesting.pop();
+                expect_key = false;
+                tokens.push(Token::Punctuation, i, i + 1)
+            }
+            b',' => {
+                expect_key = nesting.last() == Some(&b'{');
+                tokens.push(Token::Punctuation, i, i + 1)
+            }
+            b'=' => {
+                expect_key = false;
+                tokens.push(Token::Punctuation, i, i + 1)
+            }
+            b'.' if expect_key => tokens.push(Token::Punctuation, i, i + 1),
+            b if expect_key && is_bare(b) => tokens.push(Token::Key, i, run_end(bytes, i, is_bare)),
+            b'+' | b'-' | b'0'..=b'9' => {
+                let is_part = |b: u8| {
+                    b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b':' | b'+' | b'-')
+                };
+                let mut end = run_end(bytes, i, is_part);
+                let lexeme = &text[i..end];
+                let is_date = lexeme.len() >= 10 && lexeme.as_bytes()[4] == b'-';
+                let is_time = lexeme.len() >= 5 && lexeme.as_bytes()[2] == b':';
+                // A date and a time may be separated by a space, like `1979-05-27 07:32:00`.
+                if is_date
+                    && bytes.get(end) == Some(&b' ')
+                    && bytes.get(end + 1).is_some_and(u8::is_ascii_digit)
+                {
+                    end = run_end(bytes, end + 1, is_part);
+                }
+                let token = if is_date || is_time {
+                    Token::DateTime
+                } else if lexeme.ends_with("inf") || lexeme.ends_with("nan") {
+                    Token::SpecialNumber
+                } else {
+                    Token::Number
+                };
+                tokens.push(token, i, end)
+            }
+            b if b.is_ascii_alphabetic() => {
+                let end = run_end(bytes, i, |b| b.is_ascii_alphanumeric());
+                let token = match &text[i..end] {
+                    "true" | "false" => Token::Boolean,
+                    "inf" | "nan" => Token::SpecialNumber,
+                    _ => Token::Plain,
+                };
+                tokens.push(token, i, end)
+            }
+            _ => i + 1,
+        };
+    }
+
+    tokens.finish()
+}
+
+fn yaml_tokens(text: &str) -> Vec<(Token, &str)> {
+    let bytes = text.as_bytes();
+    let mut tokens = Tokens::new(text);
+    let is_blank = |b: Option<&u8>| matches!(b, None | Some(b' ' | b'\t' | b'\r' | b'\n'));
+
+    // The depth of flow collections, like `[a, b]`, where commas and brackets mean something.
+    let mut flow = 0usize;
+    // The indentation of the line that started a block scalar, like `key: |`.
+    let mut block_scalar: Option<usize> = None;
+    let mut line_indent = 0;
+    let mut line_start = true;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if line_start {
+            line_start = false;
+            let start = i;
+            i = run_end(bytes, i, |b| b == b' ');
+            line_indent = i - start;
+            let end = line_end(bytes, i);
+
+            // Lines of a block scalar are more indented than the line that started it.
+            if let Some(parent) = block_scalar {
+                if text[i..end].trim().is_empty() || line_indent > parent {
+                    i = tokens.push(Token::String, i, end);
+                    continue;
+                }
+                block_scalar = None;
+            }
+
+            if line_indent == 0 && flow == 0 {
+                let marker = ["---", "..."].iter().any(|m| text[i..].starts_with(m));
+                if marker && is_blank(bytes.get(i + 3)) {
+                    i = tokens.push(Token::Punctuation, i, i + 3);
+                } else if bytes.get(i) == Some(&b'%') {
+                    // Directives, like `%YAML 1.2`.
+                    i = tokens.push(Token::Tag, i, end);
+                }
+            }
+            continue;
+        }
+
+        let byte = bytes[i];
+        let next = bytes.get(i + 1);
+        i = match byte {
+            b'\n' => {
+                line_start = true;
+                i + 1
+            }
+            b' ' | b'\t' | b'\r' => i + 1,
+            b'#' if i == 0 || bytes[i - 1].is_ascii_whitespace() => tokens.push_comment(i),
+            b'-' | b'?' | b':' if is_blank(next) || (flow > 0 && byte == b':') => {
+                tokens.push(Token::Punctuation, i, i + 1)
+            }
+            b'[' | b'{' => {
+                flow += 1;
+                tokens.push(Token::Punctuation, i, i + 1)
+            }
+            b']' | b'}' => {
+                flow = flow.saturating_sub(1);
+                tokens.push(Token::Punctuation, i, i + 1)
+            }
+            b',' if flow > 0 => tokens.push(Token::Punctuation, i, i + 1),
+            // Anchors, aliases and tags.
+            b'&' | b'*' | b'!' => {
+                let end = run_end(bytes, i, |b| {
+                    !(b.is_ascii_whitespace() || flow > 0 && matches!(b, b',' | b']' | b'}'))
+                });
+                tokens.push(Token::Tag, i, end)
+            }
+            b'|' | b'>' if flow == 0 => {
+                block_scalar = Some(line_indent);
+                let end = run_end(bytes, i, |b| !b.is_ascii_whitespace());
+                tokens.push(Token::Punctuation, i, end)
+            }
+            quote @ (b'"' | b'\'') => {
+                let quoted = find_quoted(bytes, i, &[quote], quote == b'"', true);
+                let colon = skip_blanks(bytes, quoted.end);
+                if bytes.get(colon) == Some(&b':') {
+                    tokens.push_quoted(Token::Key, i, quoted);
+                    tokens.push(Token::Punctuation, colon, colon + 1)
+                } else {
+                    tokens.push_quoted(Token::String, i, quoted)
+                }
+            }
+            _ => {
+                let mut end = i;
+                let mut is_key = false;
+                while end < bytes.len() {
+                    let b = bytes[end];
+                    if b == b'\n' || (b == b'#' && bytes[end - 1].is_ascii_whitespace()) {
+                        break;
+                    }
+                    if flow > 0 && matches!(b, b',' | b'[' | b']' | b'{' | b'}') {
+                        break;
+                    }
+                    if b == b':' && (is_blank(bytes.get(end + 1)) || flow > 0) {
+                        is_key = true;
+                        break;
+                    }
+                    end += 1;
+                }
+                let scalar = text[i..end].trim_end();
+                let token = if is_key {
+                    Token::Key
+                } else {
+                    yaml_scalar_token(scalar)
+                };
+                tokens.push(token, i, i + scalar.len())
+            }
+        };
+    }
+
+    tokens.finish()
+}
+
+/// Returns the kind of a plain YAML scalar, following the core schema of YAML 1.2.
+fn yaml_scalar_token(scalar: &str) -> Token {
+    match scalar {
+        "null" | "Null" | "NULL" | "~" => return Token::Null,
+        "true" | "True" | "TRUE" | "false" | "False" | "FALSE" => return Token::Boolean,
+        _ => {}
+    }
+
+    let unsigned = scalar.strip_prefix(['-', '+']).unwrap_or(scalar);
+    if matches!(unsigned, ".inf" | ".Inf" | ".INF") || matches!(scalar, ".nan" | ".NaN" | ".NAN") {
+        return Token::SpecialNumber;
+    }
+
+    let is_number = if let Some(hex) = scalar.strip_prefix("0x") {
+        !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit())
+    } else if let Some(octal) = scalar.strip_prefix("0o") {
+        !octal.is_empty() && octal.bytes().all(|b| (b'0'..=b'7').contains(&b))
+    } else {
+        unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+            && unsigned.parse::<f64>().is_ok()
+    };
+    if is_number {
+        Token::Number
+    } else if datetime::is_datetime(scalar) {
+        Token::DateTime
+    } else {
+        Token::String
+    }
+}
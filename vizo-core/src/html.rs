@@ -0,0 +1,162 @@
+/// Colors of the 16 basic ANSI colors, the normal ones followed by the bright ones.
+const BASIC_COLORS: [&str; 16] = [
+    "#000000", "#cd3131", "#0dbc79", "#e5e510", "#2472c8", "#bc3fbc", "#11a8cd", "#e5e5e5",
+    "#666666", "#f14c4c", "#23d18b", "#f5f543", "#3b8eea", "#d670d6", "#29b8db", "#ffffff",
+];
+
+/// Converts text with ANSI colors and OSC 8 hyperlinks, like the output of
+/// [`crate::render_to_ansi`], to HTML with inline styles.
+///
+/// The result is a `<pre class="vizo">` block, so the layout of the output is kept.
+/// Other escape sequences are dropped.
+pub fn ansi_to_html(ansi: &str) -> String {
+    let mut html = String::from("<pre class=\"vizo\">");
+    let mut style = Style::default();
+    let mut rest = ansi;
+
+    while let Some(start) = rest.find('\x1b') {
+        escape_into(&mut html, &rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(params) = rest.strip_prefix("\x1b[") {
+            let end = params
+                .find(|c: char| !c.is_ascii_digit() && c != ';')
+                .unwrap_or(params.len());
+            if params[end..].starts_with('m') {
+                let next = style.with(&params[..end]);
+                if next != style {
+                    style.close(&mut html);
+                    next.open(&mut html);
+                    style = next;
+                }
+            }
+            rest = &params[(end + 1).min(params.len())..];
+        } else if let Some(link) = rest.strip_prefix("\x1b]8;;") {
+            let end = link.find("\x1b\\").unwrap_or(link.len());
+            // Spans are kept inside of links, so the elements stay nested.
+            style.close(&mut html);
+            match &link[..end] {
+                "" => html.push_str("</a>"),
+                url => {
+                    html.push_str("<a href=\"");
+                    escape_into(&mut html, url);
+                    html.push_str("\">");
+                }
+            }
+            style.open(&mut html);
+            rest = &link[(end + 2).min(link.len())..];
+        } else {
+            rest = &rest[1..];
+        }
+    }
+
+    escape_into(&mut html, rest);
+    style.close(&mut html);
+    html.push_str("</pre>");
+    html
+}
+
+/// A style that is set by SGR escape sequences.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Style {
+    color: Option<String>,
+    bold: bool,
+    dimmed: bool,
+    italic: bool,
+}
+
+impl Style {
+    /// Returns the style after applying the parameters of an SGR sequence, like `1;32`.
+    fn with(&self, params: &str) -> Style {
+        let mut style = self.clone();
+        let codes: Vec<u16> = params
+            .split(';')
+            .map(|code| code.parse().unwrap_or(0))
+            .collect();
+
+        let mut codes = codes.into_iter();
+        while let Some(code) = codes.next() {
+            match code {
+                0 => style = Style::default(),
+                1 => style.bold = true,
+                2 => style.dimmed = true,
+                3 => style.italic = true,
+                22 => (style.bold, style.dimmed) = (false, false),
+                23 => style.italic = false,
+                30..=37 => style.color = Some(BASIC_COLORS[usize::from(code - 30)].to_string()),
+                90..=97 => style.color = Some(BASIC_COLORS[usize::from(code - 82)].to_string()),
+                39 => style.color = None,
+                38 => match codes.next() {
+                    Some(5) => style.color = codes.next().map(indexed_color),
+                    Some(2) => {
+                        let mut channel = || codes.next().unwrap_or(0).min(255);
+                        let (r, g, b) = (channel(), channel(), channel());
+                        style.color = Some(format!("#{r:02x}{g:02x}{b:02x}"));
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        style
+    }
+
+    fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+
+    fn open(&self, html: &mut String) {
+        if self.is_plain() {
+            return;
+        }
+        html.push_str("<span style=\"");
+        if let Some(color) = &self.color {
+            html.push_str(&format!("color:{color};"));
+        }
+        if self.bold {
+            html.push_str("font-weight:bold;");
+        }
+        if self.dimmed {
+            html.push_str("opacity:0.6;");
+        }
+        if self.italic {
+            html.push_str("font-style:italic;");
+        }
+        html.push_str("\">");
+    }
+
+    fn close(&self, html: &mut String) {
+        if !self.is_plain() {
+            html.push_str("</span>");
+        }
+    }
+}
+
+/// Returns the color of an index in the 256-color palette.
+fn indexed_color(index: u16) -> String {
+    match index.min(255) {
+        index @ 0..=15 => BASIC_COLORS[usize::from(index)].to_string(),
+        index @ 16..=231 => {
+            let level = |value: u16| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            let (r, g, b) = (level(index / 36), level(index / 6 % 6), level(index % 6));
+            format!("#{r:02x}{g:02x}{b:02x}")
+        }
+        index => {
+            let gray = 8 + (index - 232) * 10;
+            format!("#{gray:02x}{gray:02x}{gray:02x}")
+        }
+    }
+}
+
+fn escape_into(html: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            c => html.push(c),
+        }
+    }
+}
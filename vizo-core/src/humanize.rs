@@ -0,0 +1,139 @@
+use crate::datetime::{self, civil_from_days};
+use crate::pattern;
+use crate::values::VizValue;
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+const MONTH: u64 = 30 * DAY;
+const YEAR: u64 = 365 * DAY;
+
+/// Patterns of keys whose values are sizes in bytes, matched by [`is_size_key`].
+pub const SIZE_KEYS: [&str; 8] = [
+    "size",
+    "*_size",
+    "*-size",
+    "bytes",
+    "*_bytes",
+    "*-bytes",
+    "content_length",
+    "content-length",
+];
+
+/// Returns `true` if the key matches one of the glob patterns, ignoring case.
+///
+/// Keys in camel case are also matched with their words split by `_`,
+/// so `fileSize` matches `*_size`.
+pub fn is_size_key(key: &str, patterns: &[String]) -> bool {
+    let lowercase = key.to_lowercase();
+    let mut snake_case = String::with_capacity(key.len() + 4);
+    let mut previous_lowercase = false;
+    for c in key.chars() {
+        if c.is_uppercase() && previous_lowercase {
+            snake_case.push('_');
+        }
+        previous_lowercase = c.is_lowercase();
+        snake_case.extend(c.to_lowercase());
+    }
+
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.to_lowercase();
+        pattern::matches(&pattern, &lowercase) || pattern::matches(&pattern, &snake_case)
+    })
+}
+
+/// Returns a hint with the size in binary units, like `1.4 GiB`,
+/// if the value is an integer of at least a kibibyte.
+pub fn size_hint(value: &VizValue) -> Option<String> {
+    let bytes = match value {
+        VizValue::Number(n) => u64::try_from(*n).ok()?,
+        VizValue::RawNumber(s) => s.parse().ok()?,
+        _ => return None,
+    };
+    (bytes >= 1024).then(|| format_bytes(bytes))
+}
+
+/// Formats a number of bytes in a human readable way.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[0])
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Returns a hint with the time in UTC and how long ago it was, like
+/// `2024-11-02 14:03 UTC, 3 days ago`, if the value looks like a timestamp.
+///
+/// Integers are taken as seconds or milliseconds since the epoch if they fall
+/// between 2001 and 2286, and strings are recognized as ISO 8601 dates and date-times.
+/// How long ago it was is only told if `now`, in seconds since the epoch, is given.
+pub fn time_hint(value: &VizValue, now: Option<i64>) -> Option<String> {
+    let (seconds, exact) = match value {
+        VizValue::Number(n) => (epoch_seconds(*n)?, true),
+        // Times without an offset are taken as UTC, which is close enough for telling how
+        // long ago they were.
+        VizValue::String(s) | VizValue::DateTime(s) => {
+            let datetime = datetime::parse(s)?;
+            (datetime.seconds(), datetime.offset.is_some())
+        }
+        _ => return None,
+    };
+
+    let ago = now.map(|now| relative_time(seconds, now));
+    match (exact, ago) {
+        (true, Some(ago)) => Some(format!("{}, {ago}", format_utc(seconds))),
+        (true, None) => Some(format_utc(seconds)),
+        (false, ago) => ago,
+    }
+}
+
+/// Converts a timestamp in seconds or milliseconds since the epoch to seconds.
+fn epoch_seconds(n: i64) -> Option<i64> {
+    match n {
+        1_000_000_000..10_000_000_000 => Some(n),
+        1_000_000_000_000..10_000_000_000_000 => Some(n / 1000),
+        _ => None,
+    }
+}
+
+/// Formats seconds since the epoch as a date and time in UTC, like `2024-11-02 14:03 UTC`.
+fn format_utc(seconds: i64) -> String {
+    let days = seconds.div_euclid(DAY as i64);
+    let time = seconds.rem_euclid(DAY as i64);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        time / HOUR as i64,
+        time % HOUR as i64 / MINUTE as i64
+    )
+}
+
+/// Describes how long ago the time was, like `3 days ago` or `in 2 hours`.
+fn relative_time(seconds: i64, now: i64) -> String {
+    let delta = now - seconds;
+    let (count, unit) = match delta.unsigned_abs() {
+        ..MINUTE => return "just now".to_string(),
+        elapsed @ ..HOUR => (elapsed / MINUTE, "minute"),
+        elapsed @ ..DAY => (elapsed / HOUR, "hour"),
+        elapsed @ ..MONTH => (elapsed / DAY, "day"),
+        elapsed @ ..YEAR => (elapsed / MONTH, "month"),
+        elapsed => (elapsed / YEAR, "year"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+
+    if delta > 0 {
+        format!("{count} {unit}{plural} ago")
+    } else {
+        format!("in {count} {unit}{plural}")
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt;
+
+use crate::values::VizValue;
+
+/// The identity of a Kubernetes object, shown as `kind/namespace/name`.
+///
+/// Objects of the cluster, like namespaces themselves, have no namespace and are shown
+/// as `kind/name`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Object<'v> {
+    /// Kind of the object, like `Deployment`.
+    pub kind: &'v str,
+
+    /// Namespace of the object, which objects of the cluster do not have.
+    pub namespace: Option<&'v str>,
+
+    /// Name of the object, or the prefix of the generated one.
+    pub name: Option<&'v str>,
+}
+
+impl fmt::Display for Object<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        for part in [self.namespace, self.name].into_iter().flatten() {
+            write!(f, "/{part}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the identity of a value if it looks like a Kubernetes object, which has a string
+/// `apiVersion` and `kind`.
+pub fn identify<'v>(value: &'v VizValue) -> Option<Object<'v>> {
+    value.pointer_str("/apiVersion")?;
+    Some(Object {
+        kind: value.pointer_str("/kind")?,
+        namespace: value.pointer_str("/metadata/namespace"),
+        name: value
+            .pointer_str("/metadata/name")
+            .or_else(|| value.pointer_str("/metadata/generateName")),
+    })
+}
+
+/// Returns the objects of a document, which are the items of a `List`, like the output of
+/// `kubectl get -o yaml`, or the document itself.
+pub fn objects<'v, 'a>(value: &'v VizValue<'a>) -> Vec<&'v VizValue<'a>> {
+    match identify(value) {
+        Some(object) if object.kind.ends_with("List") => match value.pointer("/items") {
+            Some(VizValue::Array(items)) => items.iter().collect(),
+            _ => Vec::new(),
+        },
+        Some(_) => vec![value],
+        None => Vec::new(),
+    }
+}
+
+/// Returns the images of the containers of an object, including its init containers.
+///
+/// Pods, their templates in workloads like deployments, and the ones of cron jobs are looked into.
+pub fn images<'v>(value: &'v VizValue) -> Vec<&'v str> {
+    let mut images = Vec::new();
+    for spec in [
+        "/spec",
+        "/spec/template/spec",
+        "/spec/jobTemplate/spec/template/spec",
+    ] {
+        for containers in ["initContainers", "containers"] {
+            if let Some(VizValue::Array(containers)) =
+                value.pointer(&format!("{spec}/{containers}"))
+            {
+                images.extend(containers.iter().filter_map(|c| c.pointer_str("/image")));
+            }
+        }
+    }
+    images
+}
+
+/// The number of replicas of a workload, and how many of them are ready if it has a status.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Replicas {
+    /// Number of replicas that should run.
+    pub desired: i64,
+
+    /// Number of replicas that are ready, if the object has a status.
+    pub ready: Option<i64>,
+}
+
+impl fmt::Display for Replicas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.ready {
+            Some(ready) => write!(f, "{ready}/{}", self.desired),
+            None => write!(f, "{}", self.desired),
+        }
+    }
+}
+
+/// Returns the replicas of a workload, like a deployment, where a missing count means one.
+///
+/// Daemon sets have as many replicas as nodes they are scheduled on, which only their status tells.
+pub fn replicas(value: &VizValue) -> Option<Replicas> {
+    let kind = value.pointer_str("/kind")?;
+    let status = value.pointer("/status").is_some();
+    match kind {
+        "Deployment" | "StatefulSet" | "ReplicaSet" | "ReplicationController" => Some(Replicas {
+            desired: number(value, "/spec/replicas").unwrap_or(1),
+            ready: status.then(|| number(value, "/status/readyReplicas").unwrap_or(0)),
+        }),
+        "DaemonSet" if status => Some(Replicas {
+            desired: number(value, "/status/desiredNumberScheduled").unwrap_or(0),
+            ready: Some(number(value, "/status/numberReady").unwrap_or(0)),
+        }),
+        _ => None,
+    }
+}
+
+fn number(value: &VizValue, pointer: &str) -> Option<i64> {
+    match value.pointer(pointer)?.untagged() {
+        VizValue::Number(n) => Some(*n),
+        _ => None,
+    }
+}
@@ -0,0 +1,193 @@
+//! The core of Vizo: parsing of JSON, TOML and YAML into [`VizValue`]
+//! and rendering of values as indented and colored trees.
+//!
+//! ```
+//! use vizo_core::{Format, RenderOptions, parse, render};
+//!
+//! let value = parse(r#"{"name": "vizo"}"#, Format::Json).unwrap();
+//! let output = render(&value, &RenderOptions::default());
+//! assert!(output.contains("name"));
+//! ```
+//!
+//! The core does not print to the terminal or exit the process, so it also builds
+//! for `wasm32-unknown-unknown`, where [`render_to_html`] can power a web page.
+
+use std::io::{self, Write};
+
+use crate::prints::{LineLimit, print_root, print_truncation_marker};
+use crate::processors::{ParseOptions, Warning};
+
+pub use crate::error::{Result, VizError};
+pub use crate::prints::{RenderOptions, RenderOptionsBuilder};
+pub use crate::processors::{Processor, ProcessorRegistry};
+pub use crate::values::VizValue;
+
+/// Errors of parsing and rendering.
+pub mod error;
+
+/// A global values for Vizo.
+pub mod values;
+
+/// Structural comparison of documents.
+pub mod diff;
+
+/// A prints module for Vizo app to display formatted data.
+pub mod prints;
+
+/// Serialization of values back into structured data formats.
+pub mod emit;
+
+/// Comments of documents, so they can be carried over to converted ones.
+pub mod comments;
+
+/// Syntax highlighting of documents that are printed as they are.
+pub mod highlight;
+
+/// Dates and times in documents, and their conversion between time zones.
+pub mod datetime;
+
+/// Human-readable hints for values, like how long ago a timestamp was.
+pub mod humanize;
+
+/// Summaries of the requests of HAR captures.
+pub mod har;
+
+/// Names and versions of the packages of lockfiles.
+pub mod lockfile;
+
+/// Overviews of the paths, operations and schemas of OpenAPI documents.
+pub mod openapi;
+
+/// Summaries of the resources of Terraform states.
+pub mod terraform;
+
+/// Recognition of Kubernetes objects and their kinds, names, images and replicas.
+pub mod kubernetes;
+
+/// Expansion of data that is embedded in strings, like JSON.
+pub mod embedded;
+
+/// Paths to values inside of documents.
+pub mod path;
+
+/// Selection of values with JSON Pointers and JSONPath expressions.
+pub mod query;
+
+/// Deep merging of documents.
+pub mod merge;
+
+/// Checksums of documents that do not depend on their format.
+pub mod hash;
+
+/// Conversion between nested documents and flat maps of paths, like properties files.
+pub mod flatten;
+
+/// Splitting of streams that hold several documents.
+pub mod split;
+
+/// Matching of names against glob patterns.
+pub mod pattern;
+
+/// Conversion of the colored output to HTML.
+pub mod html;
+
+/// Color themes of the output.
+pub mod theme;
+
+/// A processors for Vizo to process data.
+pub mod processors;
+
+/// A JSON Schema validator for parsed documents.
+pub mod schema;
+
+/// A structured data format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    /// Returns the format by its name or file extension, like `json` or `yml`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" | "har" | "tfstate" => Some(Format::Json),
+            "toml" => Some(Format::Toml),
+            "yaml" | "yml" => Some(Format::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format of the content.
+    pub fn detect(content: &str) -> Option<Self> {
+        processors::detect::detect(content).and_then(Format::from_name)
+    }
+}
+
+/// Parses the content with the default options.
+pub fn parse(content: &str, format: Format) -> Result<VizValue<'_>> {
+    parse_with(content, format, &ParseOptions::default(), &mut Vec::new())
+}
+
+/// Parses the content, pushing non-fatal problems, like duplicate keys, to `warnings`.
+pub fn parse_with<'a>(
+    content: &'a str,
+    format: Format,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> Result<VizValue<'a>> {
+    match format {
+        Format::Json => processors::json::JSONProcessor.process_data(content, options, warnings),
+        Format::Toml => processors::toml::TOMLProcessor.process_data(content, options, warnings),
+        Format::Yaml => processors::yaml::YAMLProcessor.process_data(content, options, warnings),
+    }
+}
+
+/// Renders the value the way Vizo prints it.
+///
+/// Whether colors are used is decided by [`RenderOptions::color`], which follows
+/// [`colored::control`] if not set, and `Theme::preset("plain")` leaves the text uncolored
+/// either way.
+pub fn render(value: &VizValue, options: &RenderOptions) -> String {
+    let mut out = Vec::new();
+    // Writing to a vector never fails.
+    let _ = render_to(&mut out, value, options);
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Renders the value into the writer, like [`render`] does.
+///
+/// Errors of the writer are returned as they are, so a reader that went away,
+/// like `head`, shows up as [`io::ErrorKind::BrokenPipe`].
+pub fn render_to(
+    out: &mut impl Write,
+    value: &VizValue,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let mut limit = LineLimit::new(options.limit);
+    print_root(out, value.clone(), &mut limit, options)?;
+    if let Some(lines) = options.limit.filter(|_| limit.is_truncated()) {
+        print_truncation_marker(out, lines, options)?;
+    }
+    Ok(())
+}
+
+/// Renders the value with ANSI colors, even if colors are disabled for the program.
+///
+/// Like in terminals, truecolor themes are approximated with basic colors unless
+/// `COLORTERM` is `truecolor` or `24bit`.
+pub fn render_to_ansi(value: &VizValue, options: &RenderOptions) -> String {
+    let options = RenderOptions {
+        color: Some(true),
+        ..options.clone()
+    };
+    render(value, &options)
+}
+
+/// Renders the value as HTML, with the colors of the theme as inline styles.
+///
+/// URLs become links if [`RenderOptions::hyperlinks`] is set.
+pub fn render_to_html(value: &VizValue, options: &RenderOptions) -> String {
+    html::ansi_to_html(&render_to_ansi(value, options))
+}
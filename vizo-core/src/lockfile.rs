@@ -0,0 +1,50 @@
+use crate::values::VizValue;
+
+/// Returns the names and versions of the packages of a lockfile, or `None` if the value is not
+/// one that is known.
+///
+/// `Cargo.lock` and `poetry.lock` list their packages in `[[package]]` tables, and
+/// `package-lock.json` by their paths in `node_modules`, or as nested `dependencies` before
+/// version 2 of the format.
+pub fn packages(lockfile: &VizValue) -> Option<Vec<(String, String)>> {
+    if let Some(VizValue::Array(packages)) = lockfile.pointer("/package") {
+        let packages = packages
+            .iter()
+            .filter_map(|package| {
+                Some((
+                    package.pointer_str("/name")?.to_string(),
+                    package.pointer_str("/version")?.to_string(),
+                ))
+            })
+            .collect();
+        return Some(packages);
+    }
+
+    lockfile.pointer("/lockfileVersion")?;
+    let mut packages = Vec::new();
+    if let Some(VizValue::Object(entries)) = lockfile.pointer("/packages") {
+        for (path, package) in entries {
+            // The root is the project itself, and links point to packages of the workspace.
+            let Some((_, name)) = path.rsplit_once("node_modules/") else {
+                continue;
+            };
+            if let Some(version) = package.pointer_str("/version")
+                && package.pointer("/link") != Some(&VizValue::Bool(true))
+            {
+                let name = package.pointer_str("/name").unwrap_or(name);
+                packages.push((name.to_string(), version.to_string()));
+            }
+        }
+    } else if let Some(dependencies) = lockfile.pointer("/dependencies") {
+        let mut stack = vec![dependencies];
+        while let Some(VizValue::Object(dependencies)) = stack.pop() {
+            for (name, package) in dependencies {
+                if let Some(version) = package.pointer_str("/version") {
+                    packages.push((name.to_string(), version.to_string()));
+                }
+                stack.extend(package.pointer("/dependencies"));
+            }
+        }
+    }
+    Some(packages)
+}
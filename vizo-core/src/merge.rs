@@ -0,0 +1,37 @@
+use crate::values::VizValue;
+use std::mem;
+
+/// How arrays of the documents are merged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayMerge {
+    /// Arrays of later documents replace the earlier ones.
+    #[default]
+    Replace,
+
+    /// Elements of later arrays are appended to the earlier ones.
+    Append,
+}
+
+/// Merges `overlay` into `base`, like layered configs are.
+///
+/// Objects are merged key by key, where keys of `overlay` win, and new keys are added
+/// at the end. Arrays are merged as `arrays` tells, and any other value of `overlay`,
+/// including `null`, replaces the value of `base`.
+pub fn merge<'a>(base: &mut VizValue<'a>, mut overlay: VizValue<'a>, arrays: ArrayMerge) {
+    match (base, &mut overlay) {
+        (VizValue::Object(base), VizValue::Object(entries)) => {
+            for (key, value) in mem::take(entries) {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value, arrays),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (VizValue::Array(base), VizValue::Array(items)) if arrays == ArrayMerge::Append => {
+            base.append(items);
+        }
+        (base, _) => *base = overlay,
+    }
+}
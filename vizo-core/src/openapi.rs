@@ -0,0 +1,71 @@
+use std::borrow::Cow;
+
+use crate::values::VizValue;
+
+/// Methods of operations on a path, in the order the overview lists them.
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Returns `true` if the value is an OpenAPI document, or a Swagger one of version 2.
+pub fn is_openapi(value: &VizValue) -> bool {
+    ["/openapi", "/swagger"]
+        .iter()
+        .any(|pointer| matches!(value.pointer(pointer), Some(VizValue::String(_))))
+}
+
+/// Builds an overview of an OpenAPI document: its title and version, the operations of every
+/// path with their summaries and status codes, and the names of its schemas.
+pub fn overview<'a>(document: &VizValue<'a>) -> VizValue<'a> {
+    let mut overview = Vec::new();
+    for key in ["/openapi", "/swagger", "/info/title", "/info/version"] {
+        if let Some(value) = document.pointer(key) {
+            let name = key.rsplit('/').next().unwrap_or(key);
+            overview.push((Cow::Borrowed(name), value.clone()));
+        }
+    }
+
+    if let Some(VizValue::Object(paths)) = document.pointer("/paths").map(VizValue::untagged) {
+        let paths = paths.iter().map(|(path, item)| {
+            let operations = METHODS.iter().filter_map(|method| {
+                let operation = item.pointer(&format!("/{method}"))?;
+                Some((Cow::Borrowed(*method), summarize(operation)))
+            });
+            (path.clone(), VizValue::Object(operations.collect()))
+        });
+        overview.push((Cow::Borrowed("paths"), VizValue::Object(paths.collect())));
+    }
+
+    // Swagger 2 keeps its schemas in `definitions`.
+    for pointer in ["/components/schemas", "/definitions"] {
+        if let Some(VizValue::Object(schemas)) = document.pointer(pointer).map(VizValue::untagged) {
+            let names = schemas.keys().map(|name| VizValue::String(name.clone()));
+            overview.push((Cow::Borrowed("schemas"), VizValue::Array(names.collect())));
+        }
+    }
+    VizValue::Object(overview.into_iter().collect())
+}
+
+/// Returns the summary, or the ID, of an operation and the status codes of its responses,
+/// like `200, 404`.
+fn summarize<'a>(operation: &VizValue<'a>) -> VizValue<'a> {
+    let mut summary = Vec::new();
+    let title = ["/summary", "/operationId"]
+        .iter()
+        .find_map(|pointer| operation.pointer(pointer));
+    if let Some(title) = title {
+        summary.push((Cow::Borrowed("summary"), title.clone()));
+    }
+    if operation.pointer("/deprecated") == Some(&VizValue::Bool(true)) {
+        summary.push((Cow::Borrowed("deprecated"), VizValue::Bool(true)));
+    }
+    if let Some(VizValue::Object(responses)) =
+        operation.pointer("/responses").map(VizValue::untagged)
+    {
+        // The codes are short, so one line of them keeps the overview compact.
+        let codes: Vec<&str> = responses.keys().map(|code| code.as_ref()).collect();
+        let codes = VizValue::String(Cow::Owned(codes.join(", ")));
+        summary.push((Cow::Borrowed("responses"), codes));
+    }
+    VizValue::Object(summary.into_iter().collect())
+}
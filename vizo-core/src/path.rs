@@ -0,0 +1,75 @@
+use std::fmt;
+
+use crate::values::escape_pointer;
+
+/// A step from a collection to one of its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathSegment<'a> {
+    /// A key of an object.
+    Key(&'a str),
+
+    /// An index in an array.
+    Index(usize),
+}
+
+/// A path from the root of a document to a value, like `/servers/0/name`.
+///
+/// It is displayed as a JSON Pointer, where the root is an empty string.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Path<'a> {
+    segments: Vec<PathSegment<'a>>,
+}
+
+impl<'a> Path<'a> {
+    /// Creates the path to the root.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// Returns the segments of the path, from the root down.
+    pub fn segments(&self) -> &[PathSegment<'a>] {
+        &self.segments
+    }
+
+    /// Returns the number of segments, which is the nesting depth of the value.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns `true` if the path points to the root.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Returns the last segment, `None` for the root.
+    pub fn last(&self) -> Option<&PathSegment<'a>> {
+        self.segments.last()
+    }
+
+    /// Appends a segment to the path.
+    pub fn push(&mut self, segment: PathSegment<'a>) {
+        self.segments.push(segment);
+    }
+
+    /// Removes the last segment of the path.
+    pub fn pop(&mut self) -> Option<PathSegment<'a>> {
+        self.segments.pop()
+    }
+
+    /// Shortens the path to the given number of segments.
+    pub fn truncate(&mut self, len: usize) {
+        self.segments.truncate(len);
+    }
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for segment in &self.segments {
+            match segment {
+                PathSegment::Key(key) => write!(f, "/{}", escape_pointer(key))?,
+                PathSegment::Index(index) => write!(f, "/{index}")?,
+            }
+        }
+        Ok(())
+    }
+}
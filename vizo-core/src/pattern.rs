@@ -0,0 +1,66 @@
+/// Matches a name against a pattern with `*`, `?` and `[...]`.
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        let step = match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+                continue;
+            }
+            Some('?') => Some(1),
+            Some('[') => match_class(&pattern[p..], name[n]),
+            Some(c) if *c == name[n] => Some(1),
+            _ => None,
+        };
+
+        match step {
+            Some(length) => {
+                p += length;
+                n += 1;
+            }
+            None => match backtrack {
+                // Lets the last `*` consume one more character and tries again.
+                Some((star, consumed)) => {
+                    p = star + 1;
+                    n = consumed + 1;
+                    backtrack = Some((star, consumed + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Matches a character against a `[...]` class at the start of the pattern.
+///
+/// Returns the length of the class if the character matches.
+fn match_class(pattern: &[char], c: char) -> Option<usize> {
+    let end = pattern.iter().skip(2).position(|ch| *ch == ']')? + 2;
+    let class = &pattern[1..end];
+    let (negated, class) = match class.first() {
+        Some('!' | '^') => (true, &class[1..]),
+        _ => (false, class),
+    };
+
+    let mut matched = false;
+    let mut id = 0;
+    while id < class.len() {
+        if id + 2 < class.len() && class[id + 1] == '-' {
+            matched |= (class[id]..=class[id + 2]).contains(&c);
+            id += 3;
+        } else {
+            matched |= class[id] == c;
+            id += 1;
+        }
+    }
+
+    (matched != negated).then_some(end + 1)
+}
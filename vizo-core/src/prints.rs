@@ -0,0 +1,1116 @@
+use crate::datetime;
+use crate::humanize;
+use crate::schema::Violation;
+use crate::theme::{Paint, Theme};
+use crate::values::{VizValue, escape_pointer};
+use colored::{Color, Colorize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::mem;
+
+/// Messages attached to values, keyed by the JSON Pointer path of the value.
+pub type Annotations = HashMap<String, Vec<String>>;
+
+/// Short hints printed dimmed after scalar values, like `✓`, keyed by the JSON Pointer path of the value.
+pub type Hints = HashMap<String, String>;
+
+/// Positions of numbers on a gradient, from `0.0` for the lowest to `1.0` for the highest,
+/// keyed by the JSON Pointer path of the value.
+pub type Heat = HashMap<String, f64>;
+
+/// How floating point numbers are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FloatFormat {
+    /// The shortest text that reads back as the same number, like `0.1`.
+    #[default]
+    Auto,
+
+    /// A fixed number of decimal places, like `3.14` for two.
+    Fixed(usize),
+
+    /// Exponent notation, like `1.5e-7`.
+    Scientific,
+}
+
+impl FloatFormat {
+    /// Formats the number.
+    pub fn format(self, number: f64) -> String {
+        match self {
+            FloatFormat::Auto => number.to_string(),
+            FloatFormat::Fixed(places) => format!("{number:.places$}"),
+            FloatFormat::Scientific => format!("{number:e}"),
+        }
+    }
+}
+
+/// The enum that indicates how to display key.
+#[derive(Eq, PartialEq)]
+pub enum DisplayType {
+    /// Displays with key name and it's value
+    Key,
+
+    /// Only displays it's value
+    ArrayElement,
+}
+
+/// A budget of lines that can be rendered before the output gets truncated.
+pub struct LineLimit {
+    remaining: Option<usize>,
+    truncated: bool,
+}
+
+impl LineLimit {
+    /// Creates a new limit. `None` means that output is not limited.
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            remaining: limit,
+            truncated: false,
+        }
+    }
+
+    /// Reserves a line for printing. Returns `false` if the limit is exhausted.
+    pub fn take(&mut self) -> bool {
+        match self.remaining.as_mut() {
+            None => true,
+            Some(0) => {
+                self.truncated = true;
+                false
+            }
+            Some(n) => {
+                *n -= 1;
+                true
+            }
+        }
+    }
+
+    /// Returns `true` if some lines were not printed because of the limit.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+/// Prints the marker that indicates that output was truncated.
+pub fn print_truncation_marker(
+    out: &mut impl Write,
+    limit: usize,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let marker = format!("... output truncated after {limit} lines (adjust with --limit)");
+    writeln!(
+        out,
+        "{}",
+        options.paint(Paint::new(Color::BrightBlack)).paint(&marker)
+    )
+}
+
+/// Prints the list of schema violations.
+pub fn print_violations(out: &mut impl Write, violations: &[Violation]) -> io::Result<()> {
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    let count = violations.len();
+    writeln!(
+        out,
+        "\n{}: {count} {}",
+        "schema".red().bold(),
+        if count == 1 {
+            "violation"
+        } else {
+            "violations"
+        }
+    )?;
+    for violation in violations {
+        let path = if violation.path.is_empty() {
+            "/"
+        } else {
+            &violation.path
+        };
+        writeln!(out, "  {}: {}", path.blue(), violation.message)?;
+    }
+
+    Ok(())
+}
+
+/// Options that control how values are rendered.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// Number of spaces per level of nesting.
+    pub indent: usize,
+
+    /// Maximum number of lines to render. `None` means that output is not limited.
+    pub limit: Option<usize>,
+
+    /// Prints control characters and escape sequences in strings as is instead of escaping them.
+    pub raw_strings: bool,
+
+    /// Width to wrap long strings at. `None` disables wrapping.
+    pub width: Option<usize>,
+
+    /// Truncates strings longer than this number of characters.
+    pub max_string_length: Option<usize>,
+
+    /// Colors of the tokens.
+    pub theme: Theme,
+
+    /// Prints colors, or leaves every token uncolored with `false`. `None` follows
+    /// [`colored::control`], like the rest of the output of a program.
+    pub color: Option<bool>,
+
+    /// Prints all keys without quotes, even the ones that are not identifiers.
+    pub bare_keys: bool,
+
+    /// Text that nulls are printed as.
+    pub null_text: String,
+
+    /// Prints empty arrays and objects on a single line, like `[]`.
+    pub compact_empty: bool,
+
+    /// Folds arrays and objects at this level of nesting into the number of their elements,
+    /// where the keys of the root object are at level 1. `None` prints every level.
+    pub depth: Option<usize>,
+
+    /// Follows large integers with their digits grouped by this separator, like `(1,234,567)`.
+    pub number_separator: Option<String>,
+
+    /// Follows timestamps with their time in UTC and how long ago it was.
+    pub human_time: bool,
+
+    /// The current time in seconds since the epoch, which timestamps are told how long ago
+    /// they were from. `None` follows them with their time in UTC only.
+    pub now: Option<i64>,
+
+    /// How floats are printed.
+    pub float_format: FloatFormat,
+
+    /// Follows integers under keys that match these patterns with their size, like `(1.4 GiB)`.
+    pub size_keys: Option<Vec<String>>,
+
+    /// Prints URLs as OSC 8 hyperlinks.
+    pub hyperlinks: bool,
+
+    /// Prints keys of objects in alphabetical order.
+    pub sort_keys: bool,
+
+    /// Follows hex colors, like `#ff8800`, with a block of the color, when colors are enabled.
+    pub color_swatches: bool,
+
+    /// Messages to print next to values, like schema violations.
+    pub annotations: Annotations,
+
+    /// Hints to print after values, like whether a path exists.
+    pub hints: Hints,
+
+    /// Numbers to color on a gradient from green to red, like latencies.
+    pub heat: Heat,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            limit: None,
+            raw_strings: false,
+            width: None,
+            max_string_length: None,
+            theme: Theme::default(),
+            color: None,
+            bare_keys: false,
+            null_text: "null".to_string(),
+            compact_empty: false,
+            depth: None,
+            number_separator: None,
+            human_time: false,
+            now: None,
+            float_format: FloatFormat::Auto,
+            size_keys: None,
+            hyperlinks: false,
+            sort_keys: false,
+            color_swatches: false,
+            annotations: Annotations::new(),
+            hints: Hints::new(),
+            heat: Heat::new(),
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Starts building options from the defaults.
+    pub fn builder() -> RenderOptionsBuilder {
+        RenderOptionsBuilder {
+            options: RenderOptions::default(),
+        }
+    }
+
+    /// Returns `true` if colors are printed.
+    pub fn colors(&self) -> bool {
+        self.color
+            .unwrap_or_else(|| colored::control::SHOULD_COLORIZE.should_colorize())
+    }
+
+    /// Returns the theme that tokens are painted with, which is [`Theme::UNCOLORED`]
+    /// if colors are not printed.
+    pub fn active_theme(&self) -> &Theme {
+        if self.colors() {
+            &self.theme
+        } else {
+            &Theme::UNCOLORED
+        }
+    }
+
+    /// Returns the paint as is if colors are printed, and a plain paint otherwise.
+    pub(crate) fn paint(&self, paint: Paint) -> Paint {
+        if self.colors() { paint } else { Paint::PLAIN }
+    }
+}
+
+/// A builder of [`RenderOptions`], where every option that is not set keeps its default.
+///
+/// ```
+/// use vizo_core::RenderOptions;
+///
+/// let options = RenderOptions::builder().indent(4).sort_keys(true).build();
+/// assert_eq!(options.indent, 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RenderOptionsBuilder {
+    options: RenderOptions,
+}
+
+impl RenderOptionsBuilder {
+    /// Sets the number of spaces per level of nesting.
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.options.indent = indent;
+        self
+    }
+
+    /// Sets the maximum number of lines to render.
+    pub fn limit(mut self, limit: Option<usize>) -> Self {
+        self.options.limit = limit;
+        self
+    }
+
+    /// Prints control characters in strings as is instead of escaping them.
+    pub fn raw_strings(mut self, raw_strings: bool) -> Self {
+        self.options.raw_strings = raw_strings;
+        self
+    }
+
+    /// Sets the width to wrap long strings at.
+    pub fn width(mut self, width: Option<usize>) -> Self {
+        self.options.width = width;
+        self
+    }
+
+    /// Sets the number of characters that longer strings are truncated to.
+    pub fn max_string_length(mut self, max_string_length: Option<usize>) -> Self {
+        self.options.max_string_length = max_string_length;
+        self
+    }
+
+    /// Sets the colors of the tokens.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.options.theme = theme;
+        self
+    }
+
+    /// Prints colors, or leaves every token uncolored with `false`.
+    pub fn color(mut self, color: Option<bool>) -> Self {
+        self.options.color = color;
+        self
+    }
+
+    /// Prints all keys without quotes.
+    pub fn bare_keys(mut self, bare_keys: bool) -> Self {
+        self.options.bare_keys = bare_keys;
+        self
+    }
+
+    /// Sets the text that nulls are printed as.
+    pub fn null_text(mut self, null_text: impl Into<String>) -> Self {
+        self.options.null_text = null_text.into();
+        self
+    }
+
+    /// Prints empty arrays and objects on a single line.
+    pub fn compact_empty(mut self, compact_empty: bool) -> Self {
+        self.options.compact_empty = compact_empty;
+        self
+    }
+
+    /// Folds arrays and objects at this level of nesting.
+    pub fn depth(mut self, depth: Option<usize>) -> Self {
+        self.options.depth = depth;
+        self
+    }
+
+    /// Sets the separator that large integers are grouped with in their hints.
+    pub fn number_separator(mut self, number_separator: Option<String>) -> Self {
+        self.options.number_separator = number_separator;
+        self
+    }
+
+    /// Follows timestamps with their time in UTC and how long ago it was.
+    pub fn human_time(mut self, human_time: bool) -> Self {
+        self.options.human_time = human_time;
+        self
+    }
+
+    /// Sets the current time in seconds since the epoch, for telling how long ago timestamps were.
+    pub fn now(mut self, now: Option<i64>) -> Self {
+        self.options.now = now;
+        self
+    }
+
+    /// Sets how floats are printed.
+    pub fn float_format(mut self, float_format: FloatFormat) -> Self {
+        self.options.float_format = float_format;
+        self
+    }
+
+    /// Sets the patterns of keys whose integers are followed by their size.
+    pub fn size_keys(mut self, size_keys: Option<Vec<String>>) -> Self {
+        self.options.size_keys = size_keys;
+        self
+    }
+
+    /// Prints URLs as OSC 8 hyperlinks.
+    pub fn hyperlinks(mut self, hyperlinks: bool) -> Self {
+        self.options.hyperlinks = hyperlinks;
+        self
+    }
+
+    /// Prints keys of objects in alphabetical order.
+    pub fn sort_keys(mut self, sort_keys: bool) -> Self {
+        self.options.sort_keys = sort_keys;
+        self
+    }
+
+    /// Follows hex colors with a block of the color.
+    pub fn color_swatches(mut self, color_swatches: bool) -> Self {
+        self.options.color_swatches = color_swatches;
+        self
+    }
+
+    /// Sets the messages to print next to values.
+    pub fn annotations(mut self, annotations: Annotations) -> Self {
+        self.options.annotations = annotations;
+        self
+    }
+
+    /// Sets the hints to print after values.
+    pub fn hints(mut self, hints: Hints) -> Self {
+        self.options.hints = hints;
+        self
+    }
+
+    /// Sets the numbers to color on a gradient.
+    pub fn heat(mut self, heat: Heat) -> Self {
+        self.options.heat = heat;
+        self
+    }
+
+    /// Returns the options.
+    pub fn build(self) -> RenderOptions {
+        self.options
+    }
+}
+
+/// A unit of work for the renderer.
+enum Task<'a> {
+    /// Renders a value with its name.
+    Value {
+        name: Cow<'a, str>,
+        value: VizValue<'a>,
+        path: String,
+        indent: usize,
+        depth: usize,
+        display_type: DisplayType,
+        is_last: bool,
+    },
+
+    /// Prints a closing bracket of an array or an object.
+    Close {
+        bracket: char,
+        indent: usize,
+        is_last: bool,
+    },
+}
+
+/// Prints the root value of a document in Prettij markup language.
+///
+/// Entries of a root object are printed without enclosing braces,
+/// while any other value is printed as is.
+pub fn print_root(
+    out: &mut impl Write,
+    mut value: VizValue<'_>,
+    limit: &mut LineLimit,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    if options.sort_keys {
+        value.sort_keys();
+    }
+
+    let track_paths = tracks_paths(options);
+    let stack = match &mut value {
+        VizValue::Object(map) => mem::take(map)
+            .into_iter()
+            .rev()
+            .map(|(name, value)| Task::Value {
+                path: child_path(track_paths, "", &name),
+                name,
+                value,
+                indent: 0,
+                depth: 1,
+                display_type: DisplayType::Key,
+                is_last: true,
+            })
+            .collect(),
+        _ => vec![Task::Value {
+            name: Cow::Borrowed(""),
+            value,
+            path: String::new(),
+            indent: 0,
+            depth: 0,
+            display_type: DisplayType::ArrayElement,
+            is_last: true,
+        }],
+    };
+
+    render(out, stack, limit, options)
+}
+
+/// Prints VizValue in Prettij markup language.
+///
+/// Values whose paths are present in the annotations of `options` are highlighted. The value
+/// is treated as a top-level entry named `name` when computing paths.
+#[allow(clippy::too_many_arguments)]
+pub fn print_prettij<'a>(
+    out: &mut impl Write,
+    name: &'a str,
+    value: VizValue<'a>,
+    initial_indent: usize,
+    display_type: DisplayType,
+    is_last: bool,
+    limit: &mut LineLimit,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let stack = vec![Task::Value {
+        name: Cow::Borrowed(name),
+        value,
+        path: child_path(tracks_paths(options), "", name),
+        indent: initial_indent,
+        depth: 1,
+        display_type,
+        is_last,
+    }];
+
+    render(out, stack, limit, options)
+}
+
+/// Returns `true` if paths of values are needed to look up their annotations, hints or heat.
+fn tracks_paths(options: &RenderOptions) -> bool {
+    !options.annotations.is_empty() || !options.hints.is_empty() || !options.heat.is_empty()
+}
+
+/// Builds the path of a child value. Paths are only needed to look up annotations,
+/// so nothing is allocated when they are not tracked.
+fn child_path(track_paths: bool, parent: &str, segment: &str) -> String {
+    if track_paths {
+        format!("{parent}/{}", escape_pointer(segment))
+    } else {
+        String::new()
+    }
+}
+
+/// Processes the tasks until the stack is empty.
+///
+/// The renderer uses an explicit work stack instead of recursion,
+/// so the nesting depth is only bounded by available memory.
+fn render<'a>(
+    out: &mut impl Write,
+    mut stack: Vec<Task<'a>>,
+    limit: &mut LineLimit,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let annotations = &options.annotations;
+    let track_paths = tracks_paths(options);
+    let theme = options.active_theme();
+    let raw_strings = options.raw_strings;
+
+    while let Some(task) = stack.pop() {
+        if !limit.take() {
+            return Ok(());
+        }
+
+        match task {
+            Task::Close {
+                bracket,
+                indent,
+                is_last,
+            } => {
+                let indent_str = " ".repeat(indent);
+                // Only objects were followed by a comma in array elements.
+                let comma = if bracket == '}' && !is_last { "," } else { "" };
+                let punctuation = theme.punctuation;
+                writeln!(
+                    out,
+                    "{indent_str}{}{}",
+                    punctuation.paint(&bracket.to_string()),
+                    punctuation.paint(comma)
+                )?;
+            }
+            Task::Value {
+                name,
+                mut value,
+                path,
+                indent,
+                depth,
+                display_type,
+                is_last,
+            } => {
+                let indent_str = " ".repeat(indent);
+                let note = annotations.get(&path);
+                let is_bare = options.bare_keys || is_bare_key(&name);
+                let name = match is_bare {
+                    true => escape(&name, raw_strings),
+                    false => escape_quoted(&name, raw_strings),
+                };
+                let painted = match note {
+                    Some(_) => theme.annotation.bold().paint(&name),
+                    None => theme.key.paint(&name),
+                };
+                let (key, key_width) = if is_bare {
+                    (painted.to_string(), name.chars().count())
+                } else {
+                    let quote = theme.punctuation.paint("\"");
+                    (format!("{quote}{painted}{quote}"), name.chars().count() + 2)
+                };
+                let equals = theme.punctuation.paint("=");
+                let suffix = note
+                    .map(|messages| {
+                        let message = format!("◀ {}", messages.join("; "));
+                        format!("  {}", theme.annotation.paint(&message))
+                    })
+                    .unwrap_or_default();
+                let (tag, tag_width, mut value) = match value {
+                    VizValue::Tagged(ref tag, ref mut inner) => {
+                        let tag = escape(tag, raw_strings);
+                        let tag_width = tag.chars().count() + 1;
+                        (
+                            format!("{} ", theme.tag.paint(&tag)),
+                            tag_width,
+                            mem::take(&mut **inner),
+                        )
+                    }
+                    value => (String::new(), 0, value),
+                };
+
+                let is_empty = match &value {
+                    VizValue::Array(vec) => vec.is_empty(),
+                    VizValue::Object(map) => map.is_empty(),
+                    _ => false,
+                };
+                if options.compact_empty && is_empty {
+                    let brackets = match value {
+                        VizValue::Array(_) => "[]",
+                        _ => "{}",
+                    };
+                    let brackets = theme.punctuation.paint(brackets);
+                    if display_type == DisplayType::Key {
+                        writeln!(out, "{indent_str}{key} {equals} {tag}{brackets}{suffix}")?;
+                    } else {
+                        let comma = theme.punctuation.paint(if is_last { "" } else { "," });
+                        writeln!(out, "{indent_str}{tag}{brackets}{comma}{suffix}")?;
+                    }
+                    continue;
+                }
+                if !is_empty && options.depth.is_some_and(|max| depth >= max) {
+                    let folded = match &value {
+                        VizValue::Array(vec) => Some(format!("[… {}]", plural(vec.len(), "item"))),
+                        VizValue::Object(map) => {
+                            Some(format!("{{… {}}}", plural(map.len(), "key")))
+                        }
+                        _ => None,
+                    };
+                    if let Some(folded) = folded {
+                        let folded = theme.muted.paint(&folded);
+                        if display_type == DisplayType::Key {
+                            writeln!(out, "{indent_str}{key} {equals} {tag}{folded}{suffix}")?;
+                        } else {
+                            let comma = theme.punctuation.paint(if is_last { "" } else { "," });
+                            writeln!(out, "{indent_str}{tag}{folded}{comma}{suffix}")?;
+                        }
+                        continue;
+                    }
+                }
+
+                match &mut value {
+                    VizValue::Array(vec) => {
+                        let bracket = theme.punctuation.paint("[");
+                        if display_type == DisplayType::Key {
+                            writeln!(out, "{indent_str}{key} {equals} {tag}{bracket}{suffix}")?;
+                        } else {
+                            writeln!(out, "{indent_str}{tag}{bracket}{suffix}")?;
+                        }
+
+                        stack.push(Task::Close {
+                            bracket: ']',
+                            indent,
+                            is_last,
+                        });
+                        let next_indent = indent + options.indent;
+                        let vec_len = vec.len();
+                        for (id, item) in mem::take(vec).into_iter().enumerate().rev() {
+                            stack.push(Task::Value {
+                                name: Cow::Borrowed(""),
+                                value: item,
+                                path: child_path(track_paths, &path, &id.to_string()),
+                                indent: next_indent,
+                                depth: depth + 1,
+                                display_type: DisplayType::ArrayElement,
+                                is_last: id == vec_len - 1,
+                            });
+                        }
+                    }
+                    VizValue::Object(map) => {
+                        let bracket = theme.punctuation.paint("{");
+                        if display_type == DisplayType::Key {
+                            writeln!(out, "{indent_str}{key} {equals} {tag}{bracket}{suffix}")?;
+                        } else {
+                            writeln!(out, "{indent_str}{tag}{bracket}{suffix}")?;
+                        }
+
+                        stack.push(Task::Close {
+                            bracket: '}',
+                            indent,
+                            is_last,
+                        });
+                        let next_indent = indent + options.indent;
+                        let map_len = map.len();
+                        for (id, (k, v)) in mem::take(map).into_iter().enumerate().rev() {
+                            stack.push(Task::Value {
+                                path: child_path(track_paths, &path, &k),
+                                name: k,
+                                value: v,
+                                indent: next_indent,
+                                depth: depth + 1,
+                                display_type: DisplayType::Key,
+                                is_last: id == map_len - 1,
+                            });
+                        }
+                    }
+                    _ => {
+                        let (prefix, column, comma) = match display_type {
+                            DisplayType::Key => (
+                                format!("{indent_str}{key} {equals} {tag}"),
+                                indent + key_width + 3 + tag_width,
+                                "",
+                            ),
+                            DisplayType::ArrayElement => (
+                                format!("{indent_str}{tag}"),
+                                indent + tag_width,
+                                if !is_last { "," } else { "" },
+                            ),
+                        };
+                        let wrap_width = options.width.map(|width| (width, column));
+                        let size_key = display_type == DisplayType::Key
+                            && options
+                                .size_keys
+                                .as_ref()
+                                .is_some_and(|keys| humanize::is_size_key(&name, keys));
+                        let hint = options
+                            .hints
+                            .get(&path)
+                            .map(|hint| format!(" {}", theme.muted.paint(hint)))
+                            .unwrap_or_default();
+                        let number = match options.heat.get(&path) {
+                            Some(position) => options.paint(heat_paint(*position)),
+                            None => theme.number,
+                        };
+                        print_scalar(
+                            out,
+                            &prefix,
+                            &value,
+                            &format!("{hint}{}{suffix}", theme.punctuation.paint(comma)),
+                            wrap_width,
+                            size_key,
+                            number,
+                            options,
+                        )?
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the count with the noun, like `3 keys`.
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{count} {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+/// Prints a single non-collection value between an already formatted prefix and suffix.
+///
+/// `wrap` is the width of the terminal and the column the value starts at. Strings that
+/// do not fit are continued on the next lines, aligned under the start of the string.
+/// `size_key` tells that the value is under a key that holds a size, and `number` is the paint
+/// of numbers, which differs from the theme for heat.
+#[allow(clippy::too_many_arguments)]
+fn print_scalar(
+    out: &mut impl Write,
+    prefix: &str,
+    value: &VizValue<'_>,
+    suffix: &str,
+    wrap: Option<(usize, usize)>,
+    size_key: bool,
+    number: Paint,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let theme = options.active_theme();
+    let raw_strings = options.raw_strings;
+    let max_string_length = options.max_string_length;
+    let number_separator = options.number_separator.as_deref();
+
+    let size_hint = size_key.then(|| humanize::size_hint(value)).flatten();
+    let hint = size_hint
+        .or_else(|| {
+            options
+                .human_time
+                .then(|| humanize::time_hint(value, options.now))
+                .flatten()
+        })
+        .map(|hint| format!(" {}", theme.muted.paint(&format!("({hint})"))))
+        .unwrap_or_default();
+    let swatch = match &value {
+        VizValue::String(s) if options.color_swatches && options.colors() => {
+            color_swatch(s).unwrap_or_default()
+        }
+        _ => String::new(),
+    };
+    let formatted = match value {
+        VizValue::String(s) if options.hyperlinks && is_link(s) => {
+            let formatted =
+                format_string(s, raw_strings, wrap, max_string_length, theme.string, theme);
+            hyperlink(s, &formatted)
+        }
+        // Dates in strings, like in JSON, are colored like the dates of TOML.
+        VizValue::String(s) if datetime::is_datetime(s) => format_string(
+            s,
+            raw_strings,
+            wrap,
+            max_string_length,
+            theme.datetime,
+            theme,
+        ),
+        VizValue::String(s) => {
+            format_string(s, raw_strings, wrap, max_string_length, theme.string, theme)
+        }
+        VizValue::Number(n) => format_integer(&n.to_string(), number_separator, number, theme),
+        VizValue::Float(f) if f.is_nan() => theme.special_number.paint("NaN").to_string(),
+        VizValue::Float(f) if f.is_infinite() => {
+            let sign = if *f < 0.0 { "-" } else { "" };
+            let infinity = format!("{sign}Infinity");
+            theme.special_number.paint(&infinity).to_string()
+        }
+        VizValue::Float(f) => number.paint(&options.float_format.format(*f)).to_string(),
+        // Numbers with more precision than a float are only rounded when it is asked for.
+        VizValue::RawNumber(s) if options.float_format != FloatFormat::Auto && is_fraction(s) => {
+            match s.parse::<f64>() {
+                Ok(f) => number.paint(&options.float_format.format(f)).to_string(),
+                Err(_) => number.paint(s).to_string(),
+            }
+        }
+        VizValue::RawNumber(s) => format_integer(s, number_separator, number, theme),
+        VizValue::DateTime(s) => theme.datetime.paint(&escape(s, raw_strings)).to_string(),
+        VizValue::Null => theme.null.paint(&options.null_text).to_string(),
+        VizValue::Bool(b) => theme.boolean.paint(&b.to_string()).to_string(),
+        VizValue::Array(_) | VizValue::Object(_) | VizValue::Tagged(..) => {
+            unreachable!("collections and tags are handled by the renderer")
+        }
+    };
+
+    writeln!(out, "{prefix}{formatted}{swatch}{hint}{suffix}")
+}
+
+/// Returns `true` if the number has a fraction or an exponent, like `0.1` or `1e5`.
+fn is_fraction(number: &str) -> bool {
+    number.contains(['.', 'e', 'E'])
+}
+
+/// Colors of the gradient of heat, from the lowest numbers to the highest.
+const HEAT_GRADIENT: [(u8, u8, u8); 3] = [(40, 200, 70), (230, 200, 60), (230, 70, 60)];
+
+/// Returns the paint of a position on the gradient of heat, from `0.0` to `1.0`.
+fn heat_paint(position: f64) -> Paint {
+    let scaled = position.clamp(0.0, 1.0) * (HEAT_GRADIENT.len() - 1) as f64;
+    let id = (scaled as usize).min(HEAT_GRADIENT.len() - 2);
+    let (from, to) = (HEAT_GRADIENT[id], HEAT_GRADIENT[id + 1]);
+    let fraction = scaled - id as f64;
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * fraction).round() as u32;
+    Paint::rgb(mix(from.0, to.0) << 16 | mix(from.1, to.1) << 8 | mix(from.2, to.2))
+}
+
+/// Returns a block of the color if the text is a hex color, like `#ff8800` or `#f80`.
+///
+/// Without colors, the block would only be noise, so it is only printed with them.
+fn color_swatch(text: &str) -> Option<String> {
+    let hex = text.strip_prefix('#')?;
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    let (r, g, b) = match hex.len() {
+        3 => {
+            let short = |id: usize| channel(&hex[id..=id]).map(|c| c * 17);
+            (short(0)?, short(1)?, short(2)?)
+        }
+        6 => (
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        ),
+        _ => return None,
+    };
+    let rgb = u32::from(r) << 16 | u32::from(g) << 8 | u32::from(b);
+    Some(format!(" {}", Paint::rgb(rgb).paint("██")))
+}
+
+/// Returns `true` if the string is a URL that can be put into a hyperlink as is.
+fn is_link(text: &str) -> bool {
+    let lower = text.to_ascii_lowercase();
+    let is_url = lower.starts_with("http://") || lower.starts_with("https://");
+    // Control characters in the URL could end the escape sequence early.
+    is_url && text.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Makes every line of the text, without its indentation, an OSC 8 hyperlink to the URL.
+fn hyperlink(url: &str, text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            let content = line.trim_start_matches(' ');
+            let indent = &line[..line.len() - content.len()];
+            format!("{indent}\x1b]8;;{url}\x1b\\{content}\x1b]8;;\x1b\\")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Formats a key of an object the way it is printed before the `=`.
+pub(crate) fn format_key(name: &str, options: &RenderOptions) -> String {
+    let theme = options.active_theme();
+    if options.bare_keys || is_bare_key(name) {
+        theme
+            .key
+            .paint(&escape(name, options.raw_strings))
+            .to_string()
+    } else {
+        let name = escape_quoted(name, options.raw_strings);
+        let painted = theme.key.paint(&name);
+        let quote = theme.punctuation.paint("\"");
+        format!("{quote}{painted}{quote}")
+    }
+}
+
+/// Returns `true` if the key can be printed without quotes, which is when it is made
+/// of letters, digits, `_` and `-` only.
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Formats a quoted string, truncated to `max_length` characters and wrapped if needed.
+///
+/// The contents are painted with `paint`, and the quotes and the marker with the theme.
+fn format_string(
+    text: &str,
+    raw: bool,
+    wrap: Option<(usize, usize)>,
+    max_length: Option<usize>,
+    paint: Paint,
+    theme: &Theme,
+) -> String {
+    let (text, omitted) = match max_length.and_then(|max| text.char_indices().nth(max)) {
+        Some((end, _)) => (&text[..end], text[end..].chars().count()),
+        None => (text, 0),
+    };
+
+    let quoted = match wrap {
+        // Raw strings may contain escape sequences, which cannot be measured or split.
+        Some((width, column)) if !raw => {
+            let escaped = escape_quoted(text, false);
+            // Leaves room for the quotes and a comma.
+            let available = width.saturating_sub(column + 3).max(MIN_WRAP_WIDTH);
+            let padding = format!("\n{}", " ".repeat(column + 1));
+            let lines: Vec<String> = wrap_text(&escaped, available)
+                .iter()
+                .map(|line| paint.paint(line).to_string())
+                .collect();
+            lines.join(&padding)
+        }
+        _ => paint.paint(&escape_quoted(text, raw)).to_string(),
+    };
+    let quote = theme.punctuation.paint("\"");
+    let quoted = format!("{quote}{quoted}{quote}");
+
+    if omitted == 0 {
+        return quoted;
+    }
+    let unit = if omitted == 1 { "char" } else { "chars" };
+    let marker = format!("… (+{} {unit})", group_thousands(omitted));
+    format!("{quoted} {}", theme.muted.paint(&marker))
+}
+
+/// The fewest digits an integer needs to be followed by its grouped digits.
+const MIN_GROUPED_DIGITS: usize = 5;
+
+/// Formats a number as it is written, followed by its grouped digits
+/// if it is a large integer and `separator` is set, like `1234567 (1,234,567)`.
+fn format_integer(number: &str, separator: Option<&str>, paint: Paint, theme: &Theme) -> String {
+    let painted = paint.paint(number);
+    let Some(separator) = separator else {
+        return painted.to_string();
+    };
+
+    let (sign, digits) = match number.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", number),
+    };
+    if digits.len() < MIN_GROUPED_DIGITS || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return painted.to_string();
+    }
+
+    let grouped = format!("({sign}{})", group_digits(digits, separator));
+    format!("{painted} {}", theme.muted.paint(&grouped))
+}
+
+/// Formats a number with commas between groups of thousands, like `12,345`.
+fn group_thousands(n: usize) -> String {
+    group_digits(&n.to_string(), ",")
+}
+
+/// Puts the separator between groups of three digits, counting from the right.
+fn group_digits(digits: &str, separator: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * separator.len());
+    for (id, digit) in digits.chars().enumerate() {
+        if id > 0 && (digits.len() - id).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// The narrowest column that wrapped strings are given, even if they start far to the right.
+const MIN_WRAP_WIDTH: usize = 20;
+
+/// Splits the text into lines of at most `width` characters.
+///
+/// Lines are broken after a space if there is one in the second half of the line.
+/// Escape sequences like `\n` or `\u{202e}` are never split.
+fn wrap_text(text: &str, width: usize) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut remaining = text.chars().count();
+    let mut start = 0;
+
+    while remaining > width {
+        let (mut end, mut count) = (start, 0);
+        let mut space = None;
+        while end < text.len() {
+            let unit = unit_len(&text[end..]);
+            let chars = text[end..end + unit].chars().count();
+            if count + chars > width {
+                break;
+            }
+            end += unit;
+            count += chars;
+            if text[..end].ends_with(' ') {
+                space = Some((end, count));
+            }
+        }
+
+        if let Some((space_end, space_count)) = space.filter(|(_, count)| *count > width / 2) {
+            (end, count) = (space_end, space_count);
+        } else if end == start {
+            // A single escape sequence is wider than the line, so it gets a line of its own.
+            end += unit_len(&text[end..]);
+            count = text[start..end].chars().count();
+        }
+        lines.push(&text[start..end]);
+        start = end;
+        remaining -= count;
+    }
+
+    lines.push(&text[start..]);
+    lines
+}
+
+/// Returns the length in bytes of the character or the escape sequence at the start of the text.
+fn unit_len(text: &str) -> usize {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some('\\'), Some('x'))
+            if text.len() >= 4
+                && text.is_char_boundary(4)
+                && text[2..4].chars().all(|c| c.is_ascii_hexdigit()) =>
+        {
+            4
+        }
+        (Some('\\'), Some('u')) if text[2..].starts_with('{') => {
+            text.find('}').map_or(text.len(), |end| end + 1)
+        }
+        (Some('\\'), Some(c)) => 1 + c.len_utf8(),
+        (Some(c), _) => c.len_utf8(),
+        (None, _) => 0,
+    }
+}
+
+/// Escapes control characters, so values cannot move the cursor or change colors
+/// of the terminal. The text is returned as is if `raw` is set.
+fn escape(text: &str, raw: bool) -> Cow<'_, str> {
+    escape_text(text, raw, false)
+}
+
+/// Escapes a text that is printed between quotes, where backslashes and quotes are
+/// escaped too, so they cannot be mistaken for escape sequences or the end of the text.
+fn escape_quoted(text: &str, raw: bool) -> Cow<'_, str> {
+    escape_text(text, raw, true)
+}
+
+fn escape_text(text: &str, raw: bool, quoted: bool) -> Cow<'_, str> {
+    let needs_escape = |c: char| is_unsafe(c) || (quoted && matches!(c, '\\' | '"'));
+    if raw || !text.chars().any(needs_escape) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\\' | '"' if quoted => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_ascii() && is_unsafe(c) => escaped.push_str(&format!("\\x{:02x}", c as u32)),
+            c if is_unsafe(c) => escaped.push_str(&format!("\\u{{{:04x}}}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Returns `true` for control characters and for characters that reorder the text around them.
+fn is_unsafe(c: char) -> bool {
+    c.is_control() || matches!(c, '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+}
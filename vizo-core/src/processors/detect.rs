@@ -0,0 +1,83 @@
+/// Names of the formats that can be processed.
+pub const FORMATS: [&str; 6] = ["json", "toml", "yaml", "yml", "har", "tfstate"];
+
+/// Guesses the format of the data by looking at its first meaningful line.
+///
+/// Returns `None` if the content does not look like any known format.
+pub fn detect(data: &str) -> Option<&'static str> {
+    let data = data.trim_start_matches('\u{feff}');
+    let mut lines = data
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+    let line = lines.next()?;
+
+    if line.starts_with("---") || line.starts_with("%YAML") {
+        return Some("yaml");
+    }
+    if line.starts_with('{') {
+        return Some("json");
+    }
+    if line.starts_with('[') {
+        return Some(if is_table_header(line) {
+            "toml"
+        } else {
+            "json"
+        });
+    }
+    if line.starts_with('"') && !line.contains('=') && !line.contains(':') {
+        return Some("json");
+    }
+    if line.starts_with("- ") || line == "-" {
+        return Some("yaml");
+    }
+
+    let key_end = line.find(['=', ':'])?;
+    let key = line[..key_end].trim();
+    if key.is_empty() || !is_key(key) {
+        return None;
+    }
+
+    match line.as_bytes()[key_end] {
+        b'=' => Some("toml"),
+        _ => Some("yaml"),
+    }
+}
+
+/// Returns `true` if the line looks like a TOML table header, e.g. `[package]` or `[[bin]]`.
+fn is_table_header(line: &str) -> bool {
+    let line = line.split(" #").next().unwrap_or(line).trim_end();
+    let name = line
+        .strip_prefix("[[")
+        .and_then(|l| l.strip_suffix("]]"))
+        .or_else(|| line.strip_prefix('[').and_then(|l| l.strip_suffix(']')));
+
+    name.is_some_and(|name| {
+        !name.trim().is_empty() && name.split('.').all(|k| is_header_key(k.trim()))
+    })
+}
+
+/// Returns `true` if the text looks like a key of a table header.
+///
+/// Quoted keys and numbers are left out, since `["a", "b"]`, `[1]` and `[1.5]` are far more
+/// often JSON arrays than TOML tables, and so are the literals of JSON, like `[true]`.
+fn is_header_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.bytes().all(|b| b.is_ascii_digit())
+        && !matches!(key, "true" | "false" | "null")
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-'))
+}
+
+/// Returns `true` if the text looks like a bare or quoted key.
+fn is_key(key: &str) -> bool {
+    let quoted = key.len() >= 2
+        && ((key.starts_with('"') && key.ends_with('"'))
+            || (key.starts_with('\'') && key.ends_with('\'')));
+
+    quoted
+        || key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ' '))
+}
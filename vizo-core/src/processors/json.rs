@@ -0,0 +1,59 @@
+use super::{ParseError, ParseOptions, Processor, Warning, lenient};
+use crate::error::Result;
+use crate::values::{KeyTracker, VizValue};
+
+/// A processor that processes JSON structured data.
+/// Implements [`crate::processors::Processor`] trait.
+pub struct JSONProcessor;
+impl Processor for JSONProcessor {
+    fn process_data<'a>(
+        &self,
+        data: &'a str,
+        options: &ParseOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<VizValue<'a>> {
+        if options.lenient
+            && let Some(fixed) = lenient::fix_json(data, warnings)
+        {
+            let options = ParseOptions {
+                lenient: false,
+                ..options.clone()
+            };
+            let mut values = self.process_data(&fixed, &options, warnings)?;
+            lenient::restore_special_floats(&mut values);
+            return Ok(values.into_owned());
+        }
+
+        let tracker = KeyTracker::new(options.strict).with_max_depth(options.max_depth);
+        let mut deserializer = serde_json::Deserializer::from_str(data);
+        // The depth is limited by the tracker instead, as configured with `--max-depth`, and the
+        // stack grows as deep as the document goes.
+        deserializer.disable_recursion_limit();
+        let values = VizValue::deserialize_tracked(
+            serde_stacker::Deserializer::new(&mut deserializer),
+            &tracker,
+        )
+        .and_then(|values| deserializer.end().map(|_| values));
+        if let Some(depth) = tracker.exceeded_depth() {
+            options.check_depth(depth)?;
+        }
+        let values = values.map_err(|e| {
+            let message = e.to_string();
+            // The location is reported separately, so it is stripped from the message.
+            let message = match message.rsplit_once(" at line ") {
+                Some((message, _)) => message.to_string(),
+                None => message,
+            };
+            ParseError::new("JSON", message, e.line(), e.column(), data)
+        })?;
+        options.check_value(&values)?;
+
+        warnings.extend(
+            tracker
+                .into_duplicates()
+                .into_iter()
+                .map(Warning::duplicate_key),
+        );
+        Ok(values)
+    }
+}
@@ -0,0 +1,242 @@
+use super::Warning;
+use crate::values::VizValue;
+
+/// Strings that stand in for `NaN`, `Infinity` and `-Infinity` until the data is parsed.
+///
+/// The placeholders start with an escaped NUL character, which is unlikely to appear in real data.
+const SPECIAL_FLOATS: [(&str, &str, f64); 4] = [
+    ("NaN", "\"\\u0000NaN\"", f64::NAN),
+    ("Infinity", "\"\\u0000Infinity\"", f64::INFINITY),
+    ("+Infinity", "\"\\u0000+Infinity\"", f64::INFINITY),
+    ("-Infinity", "\"\\u0000-Infinity\"", f64::NEG_INFINITY),
+];
+
+/// Removes comments and trailing commas from JSON data and quotes `NaN` and `Infinity`.
+///
+/// Removed characters are replaced with spaces, so the positions reported by the parser
+/// still point to the original source, except for columns after a special float.
+/// Returns `None` if nothing had to be fixed.
+pub fn fix_json(data: &str, warnings: &mut Vec<Warning>) -> Option<String> {
+    let mut bytes = data.as_bytes().to_vec();
+    let mut fixes = Vec::new();
+
+    strip_comments(&mut bytes, &mut fixes);
+    strip_trailing_commas(&mut bytes, &mut fixes);
+    let bytes = quote_special_floats(bytes, &mut fixes);
+
+    if fixes.is_empty() {
+        return None;
+    }
+
+    // Fixes are collected by separate passes, so they are sorted by line here.
+    fixes.sort_by_key(|(line, _)| *line);
+    warnings.extend(fixes.into_iter().map(|(line, fix)| Warning {
+        path: None,
+        message: format!("line {line}: {fix}"),
+    }));
+
+    // Only ASCII characters and whole comments are replaced, so the data stays valid UTF-8.
+    String::from_utf8(bytes).ok()
+}
+
+/// Turns the placeholders of `NaN` and `Infinity` left by [`fix_json`] back into floats.
+pub fn restore_special_floats(value: &mut VizValue) {
+    let mut stack = vec![value];
+
+    while let Some(value) = stack.pop() {
+        match value {
+            VizValue::String(s) if s.starts_with('\0') => {
+                if let Some((_, _, f)) = SPECIAL_FLOATS
+                    .iter()
+                    .find(|(token, _, _)| s.strip_prefix('\0') == Some(token))
+                {
+                    *value = VizValue::Float(*f);
+                }
+            }
+            VizValue::Array(vec) => stack.extend(vec.iter_mut()),
+            VizValue::Object(map) => stack.extend(map.values_mut()),
+            _ => {}
+        }
+    }
+}
+
+/// Replaces `//`, `#` and `/* */` comments outside of strings with spaces.
+fn strip_comments(bytes: &mut [u8], fixes: &mut Vec<(usize, &'static str)>) {
+    let mut line = 1;
+    let mut in_string = false;
+    let mut id = 0;
+
+    while id < bytes.len() {
+        let byte = bytes[id];
+        if byte == b'\n' {
+            line += 1;
+        }
+
+        if in_string {
+            match byte {
+                b'\\' => id += 1,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            id += 1;
+            continue;
+        }
+
+        match (byte, bytes.get(id + 1)) {
+            (b'"', _) => in_string = true,
+            (b'#', _) | (b'/', Some(b'/')) => {
+                fixes.push((line, "removed a line comment"));
+                while id < bytes.len() && bytes[id] != b'\n' {
+                    bytes[id] = b' ';
+                    id += 1;
+                }
+                continue;
+            }
+            (b'/', Some(b'*')) => {
+                fixes.push((line, "removed a block comment"));
+                let end = bytes[id + 2..]
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                    .map(|pos| id + 2 + pos + 2)
+                    .unwrap_or(bytes.len());
+                for byte in &mut bytes[id..end] {
+                    if *byte == b'\n' {
+                        line += 1;
+                    } else {
+                        *byte = b' ';
+                    }
+                }
+                id = end;
+                continue;
+            }
+            _ => {}
+        }
+
+        id += 1;
+    }
+}
+
+/// Replaces commas that are followed by a closing bracket with spaces.
+fn strip_trailing_commas(bytes: &mut [u8], fixes: &mut Vec<(usize, &'static str)>) {
+    let mut line = 1;
+    let mut in_string = false;
+    let mut id = 0;
+
+    while id < bytes.len() {
+        let byte = bytes[id];
+        if byte == b'\n' {
+            line += 1;
+        }
+
+        if in_string {
+            match byte {
+                b'\\' => id += 1,
+                b'"' => in_string = false,
+                _ => {}
+            }
+        } else if byte == b'"' {
+            in_string = true;
+        } else if byte == b',' {
+            let next = bytes[id + 1..]
+                .iter()
+                .find(|b| !b.is_ascii_whitespace())
+                .copied();
+            if matches!(next, Some(b']' | b'}')) {
+                bytes[id] = b' ';
+                fixes.push((line, "removed a trailing comma"));
+            }
+        }
+
+        id += 1;
+    }
+}
+
+/// Replaces `NaN` and `Infinity` outside of strings with placeholders that JSON accepts.
+fn quote_special_floats(bytes: Vec<u8>, fixes: &mut Vec<(usize, &'static str)>) -> Vec<u8> {
+    let is_word = |b: &u8| b.is_ascii_alphanumeric() || *b == b'_';
+    let mut fixed = Vec::with_capacity(bytes.len());
+    let mut line = 1;
+    let mut in_string = false;
+    let mut id = 0;
+
+    'bytes: while id < bytes.len() {
+        let byte = bytes[id];
+        if byte == b'\n' {
+            line += 1;
+        }
+
+        if in_string {
+            match byte {
+                b'\\' => {
+                    fixed.push(byte);
+                    id += 1;
+                }
+                b'"' => in_string = false,
+                _ => {}
+            }
+        } else if byte == b'"' {
+            in_string = true;
+        } else if id == 0 || !is_word(&bytes[id - 1]) {
+            for (token, placeholder, _) in SPECIAL_FLOATS {
+                let end = id + token.len();
+                if bytes[id..].starts_with(token.as_bytes()) && !bytes.get(end).is_some_and(is_word)
+                {
+                    fixes.push((line, "quoted a special float"));
+                    fixed.extend_from_slice(placeholder.as_bytes());
+                    id = end;
+                    continue 'bytes;
+                }
+            }
+        }
+
+        if let Some(&byte) = bytes.get(id) {
+            fixed.push(byte);
+        }
+        id += 1;
+    }
+
+    fixed
+}
+
+/// Replaces tabs in YAML indentation with spaces.
+///
+/// The fix is only applied when every indented line uses tabs alone,
+/// because mixing tabs and spaces makes the intended nesting ambiguous.
+/// Returns `None` if nothing had to be fixed.
+pub fn fix_yaml(data: &str, warnings: &mut Vec<Warning>) -> Option<String> {
+    let mut tabbed = 0;
+    for line in data.lines() {
+        let indent = indentation(line);
+        if indent.contains('\t') {
+            if indent.contains(' ') {
+                return None;
+            }
+            tabbed += 1;
+        } else if !indent.is_empty() && !line.trim().is_empty() {
+            return None;
+        }
+    }
+
+    if tabbed == 0 {
+        return None;
+    }
+
+    let mut fixed = String::with_capacity(data.len() + tabbed);
+    for line in data.split_inclusive('\n') {
+        let indent = indentation(line);
+        fixed.push_str(&"  ".repeat(indent.len()));
+        fixed.push_str(&line[indent.len()..]);
+    }
+
+    warnings.push(Warning {
+        path: None,
+        message: format!("replaced tab indentation with spaces on {tabbed} lines"),
+    });
+    Some(fixed)
+}
+
+/// Returns the leading whitespace of the line.
+fn indentation(line: &str) -> &str {
+    let content = line.trim_start_matches([' ', '\t']);
+    &line[..line.len() - content.len()]
+}
@@ -0,0 +1,238 @@
+use colored::Colorize;
+use std::fmt;
+
+use crate::error::{Result, VizError};
+use crate::values::VizValue;
+
+/// Detection of the data format by its content.
+pub mod detect;
+
+/// A processor for JSON.
+pub mod json;
+
+/// Fixes for common mistakes that are applied in lenient mode.
+pub mod lenient;
+
+/// A processor for TOML.
+pub mod toml;
+
+/// A processor for YAML.
+pub mod yaml;
+
+/// A registry of processors by the extensions they handle.
+pub mod registry;
+
+pub use registry::ProcessorRegistry;
+
+/// Options that control how processors parse data.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Maximum nesting depth of arrays and objects. `0` disables the check.
+    pub max_depth: usize,
+
+    /// Maximum number of values in the document. `0` disables the check.
+    pub max_nodes: usize,
+
+    /// Treats duplicate keys as errors instead of warnings.
+    pub strict: bool,
+
+    /// Tolerates common mistakes like comments and trailing commas in JSON.
+    pub lenient: bool,
+
+    /// Version of the YAML specification used to interpret plain scalars.
+    pub yaml_version: yaml::YamlVersion,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 512,
+            max_nodes: 10_000_000,
+            strict: false,
+            lenient: false,
+            yaml_version: yaml::YamlVersion::default(),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Returns an error if the depth exceeds the limit.
+    pub fn check_depth(&self, depth: usize) -> Result<()> {
+        if self.max_depth != 0 && depth > self.max_depth {
+            return Err(VizError::Limit(format!(
+                "document is nested deeper than {} levels (adjust with --max-depth)",
+                self.max_depth
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the number of nodes exceeds the limit.
+    pub fn check_nodes(&self, nodes: usize) -> Result<()> {
+        if self.max_nodes != 0 && nodes > self.max_nodes {
+            return Err(VizError::Limit(format!(
+                "document contains more than {} values (adjust with --max-nodes)",
+                self.max_nodes
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks an already parsed value against the depth and node limits.
+    pub fn check_value(&self, value: &VizValue) -> Result<()> {
+        let mut nodes = 0;
+        let mut stack = vec![(value, 0)];
+
+        while let Some((value, depth)) = stack.pop() {
+            nodes += 1;
+            self.check_nodes(nodes)?;
+            self.check_depth(depth)?;
+
+            match value {
+                VizValue::Array(vec) => stack.extend(vec.iter().map(|v| (v, depth + 1))),
+                VizValue::Object(map) => stack.extend(map.values().map(|v| (v, depth + 1))),
+                // A tag does not add a level of nesting.
+                VizValue::Tagged(_, value) => {
+                    nodes -= 1;
+                    stack.push((value, depth));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An error that occurred at a specific location of the parsed data.
+///
+/// When displayed, it includes the offending source line with a caret pointing to the column.
+#[derive(Debug)]
+pub struct ParseError {
+    /// Name of the format that failed to parse.
+    pub format: &'static str,
+
+    /// Description of the error.
+    pub message: String,
+
+    /// Line of the error, starting from 1.
+    pub line: usize,
+
+    /// Column of the error in characters, starting from 1.
+    pub column: usize,
+
+    /// The source line the error points to.
+    pub source_line: Option<String>,
+}
+
+impl ParseError {
+    /// Creates a new error and captures the offending line from the source.
+    pub fn new(
+        format: &'static str,
+        message: impl Into<String>,
+        line: usize,
+        column: usize,
+        source: &str,
+    ) -> Self {
+        let source_line = line
+            .checked_sub(1)
+            .and_then(|id| source.lines().nth(id))
+            .map(str::to_string);
+
+        Self {
+            format,
+            message: message.into(),
+            line,
+            column: column.max(1),
+            source_line,
+        }
+    }
+
+    /// Creates a new error from a byte offset into the source.
+    pub fn at_offset(
+        format: &'static str,
+        message: impl Into<String>,
+        offset: usize,
+        source: &str,
+    ) -> Self {
+        let before = &source[..source.floor_char_boundary(offset)];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|id| id + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        Self::new(format, message, line, column, source)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {} at line {} column {}: {}",
+            self.format, self.line, self.column, self.message
+        )?;
+
+        if let Some(source_line) = &self.source_line {
+            let number = self.line.to_string();
+            let padding = " ".repeat(number.len());
+            // Keeps tabs in place, so the caret stays aligned with the source line.
+            let offset: String = source_line
+                .chars()
+                .take(self.column - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+
+            write!(f, "\n{padding} {}", "|".blue().bold())?;
+            write!(
+                f,
+                "\n{} {} {source_line}",
+                number.blue().bold(),
+                "|".blue().bold()
+            )?;
+            write!(
+                f,
+                "\n{padding} {} {offset}{}",
+                "|".blue().bold(),
+                "^".red().bold()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A non-fatal problem found while parsing the data.
+#[derive(Debug, Clone)]
+pub struct Warning {
+    /// JSON Pointer to the affected value, if the problem is tied to one.
+    pub path: Option<String>,
+
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl Warning {
+    /// Creates a warning about a duplicate key at the given path.
+    pub fn duplicate_key(path: String) -> Self {
+        Self {
+            path: Some(path),
+            message: "duplicate key, the last value is used".to_string(),
+        }
+    }
+}
+
+/// A base trait for processors.
+///
+/// Processors are registered in a [`ProcessorRegistry`] to handle their extensions.
+pub trait Processor {
+    /// Processes the data and returns a `VizValue`.
+    ///
+    /// Non-fatal problems are pushed to `warnings`.
+    fn process_data<'a>(
+        &self,
+        data: &'a str,
+        options: &ParseOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<VizValue<'a>>;
+}
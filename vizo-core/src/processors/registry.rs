@@ -0,0 +1,73 @@
+use indexmap::IndexMap;
+
+use super::json::JSONProcessor;
+use super::toml::TOMLProcessor;
+use super::yaml::YAMLProcessor;
+use super::{ParseOptions, Processor, Warning};
+use crate::error::{Result, VizError};
+use crate::values::VizValue;
+
+/// Processors by the extensions or language names they handle, like `json`.
+///
+/// The default registry contains the built-in processors. Registering a processor
+/// for an extension that is already handled replaces the previous one.
+pub struct ProcessorRegistry {
+    processors: IndexMap<String, Box<dyn Processor>>,
+}
+
+impl ProcessorRegistry {
+    /// Creates a registry without any processors.
+    pub fn empty() -> Self {
+        Self {
+            processors: IndexMap::new(),
+        }
+    }
+
+    /// Registers the processor for the extension.
+    pub fn register(&mut self, extension: impl Into<String>, processor: Box<dyn Processor>) {
+        self.processors.insert(extension.into(), processor);
+    }
+
+    /// Returns the processor of the extension, if there is one.
+    pub fn get(&self, extension: &str) -> Option<&dyn Processor> {
+        self.processors.get(extension).map(Box::as_ref)
+    }
+
+    /// Parses the data with the processor of the extension.
+    ///
+    /// Returns [`VizError::UnsupportedFormat`] if there is no processor for it.
+    pub fn parse<'a>(
+        &self,
+        extension: &str,
+        data: &'a str,
+        options: &ParseOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<VizValue<'a>> {
+        self.get(extension)
+            .ok_or_else(|| VizError::UnsupportedFormat(extension.to_string()))?
+            .process_data(data, options, warnings)
+    }
+
+    /// Returns `true` if there is a processor for the extension.
+    pub fn contains(&self, extension: &str) -> bool {
+        self.processors.contains_key(extension)
+    }
+
+    /// Returns the extensions that have processors, in the order they were registered.
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.processors.keys().map(String::as_str)
+    }
+}
+
+impl Default for ProcessorRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register("json", Box::new(JSONProcessor));
+        registry.register("toml", Box::new(TOMLProcessor));
+        registry.register("yaml", Box::new(YAMLProcessor));
+        registry.register("yml", Box::new(YAMLProcessor));
+        registry.register("har", Box::new(JSONProcessor));
+        registry.register("tfstate", Box::new(JSONProcessor));
+        registry
+    }
+}
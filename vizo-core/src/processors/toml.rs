@@ -0,0 +1,59 @@
+use crate::values::VizValue;
+
+use super::{ParseError, ParseOptions, Processor, Warning};
+use crate::error::{Result, VizError};
+use toml_parser::Source;
+use toml_parser::lexer::TokenKind;
+
+/// A processor that processes TOML structured data.
+/// Implements [`crate::processors::Processor`] trait.
+pub struct TOMLProcessor;
+impl Processor for TOMLProcessor {
+    fn process_data<'a>(
+        &self,
+        data: &'a str,
+        options: &ParseOptions,
+        _warnings: &mut Vec<Warning>,
+    ) -> Result<VizValue<'a>> {
+        // The parser of `toml` recurses for every level of nesting, so the depth is measured
+        // first, both to check it and to give the parser enough stack.
+        let depth = nesting(data);
+        options.check_depth(depth)?;
+
+        let stack_size = STACK_PER_LEVEL.saturating_mul(depth + 16);
+        let values: VizValue<'_> = stacker::maybe_grow(stack_size, stack_size, || {
+            toml::from_str(data)
+        })
+        .map_err(|e| match e.span() {
+            Some(span) => ParseError::at_offset("TOML", e.message(), span.start, data).into(),
+            None => VizError::invalid("TOML", e.message()),
+        })?;
+        options.check_value(&values)?;
+        Ok(values)
+    }
+}
+
+/// Stack the parser of `toml` needs for every level of nesting, with room to spare.
+const STACK_PER_LEVEL: usize = 16 * 1024;
+
+/// Returns how deep arrays and inline tables are nested, like `2` for `a = [[1]]`.
+///
+/// Only the tokens are looked at, so tables of dotted keys and the values in tables are left
+/// to [`ParseOptions::check_value`].
+fn nesting(data: &str) -> usize {
+    let mut depth: usize = 0;
+    let mut deepest = 0;
+    for token in Source::new(data).lex() {
+        match token.kind() {
+            TokenKind::LeftSquareBracket | TokenKind::LeftCurlyBracket => {
+                depth += 1;
+                deepest = deepest.max(depth);
+            }
+            TokenKind::RightSquareBracket | TokenKind::RightCurlyBracket => {
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+    deepest
+}
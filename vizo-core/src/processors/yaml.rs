@@ -0,0 +1,517 @@
+use crate::values::VizValue;
+
+use super::{ParseError, ParseOptions, Processor, Warning, lenient};
+use crate::error::{Result, VizError};
+use crate::values::escape_pointer;
+use indexmap::IndexMap;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use yaml::parser::{Event, MarkedEventReceiver, Parser, Tag};
+use yaml::scanner::{Marker, ScanError, TScalarStyle};
+
+/// A version of the YAML specification, which decides how plain scalars are interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum YamlVersion {
+    /// YAML 1.1, where `yes`, `no`, `on` and `off` are booleans,
+    /// and numbers may be octal with a leading zero, binary or sexagesimal.
+    V1_1,
+    /// YAML 1.2 with the core schema.
+    #[default]
+    V1_2,
+}
+
+/// A processor that processes YAML structured data.
+/// Implements [`crate::processors::Processor`] trait.
+pub struct YAMLProcessor;
+impl Processor for YAMLProcessor {
+    fn process_data<'a>(
+        &self,
+        data: &'a str,
+        options: &ParseOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<VizValue<'a>> {
+        if options.lenient
+            && let Some(fixed) = lenient::fix_yaml(data, warnings)
+        {
+            let options = ParseOptions {
+                lenient: false,
+                ..options.clone()
+            };
+            return self
+                .process_data(&fixed, &options, warnings)
+                .map(VizValue::into_owned);
+        }
+
+        // Events are passed one by one, because `Parser::load` recurses for every level of
+        // nesting and would run out of stack before the loader reaches the depth limit.
+        let mut loader = Loader::new(options);
+        let mut parser = Parser::new_from_str(data);
+        while loader.error.is_none() {
+            let (event, mark) = parser.next_token().map_err(|e| scan_error(e, data))?;
+            if event == Event::StreamEnd {
+                break;
+            }
+            loader.on_event(event, mark);
+        }
+        if let Some((e, mark)) = loader.error {
+            let message = match e {
+                VizError::Invalid { message, .. } => message,
+                // Limits are about the whole document, not about where they were reached.
+                VizError::Limit(_) => return Err(e),
+                e => e.to_string(),
+            };
+            return Err(ParseError::new("YAML", message, mark.line(), mark.col() + 1, data).into());
+        }
+
+        let values = loader
+            .docs
+            .into_iter()
+            .next()
+            .ok_or_else(|| VizError::invalid("YAML", "no documents found"))?;
+
+        warnings.extend(loader.duplicates.into_iter().map(Warning::duplicate_key));
+        Ok(values)
+    }
+}
+
+/// A value together with the size of its subtree.
+#[derive(Clone)]
+struct Subtree {
+    value: VizValue<'static>,
+    /// Number of values in the subtree, including the value itself.
+    nodes: usize,
+    /// Nesting depth of the subtree, `0` for scalars.
+    height: usize,
+}
+
+/// A collection that is currently being built.
+struct Frame {
+    tree: Subtree,
+    anchor: usize,
+    /// A custom tag of the collection, applied once it is finished.
+    tag: Option<String>,
+    /// Keys of a mapping that were copied from a `<<` merge key.
+    merged: HashSet<Cow<'static, str>>,
+}
+
+/// A key of the mapping entry that is being built.
+enum EntryKey {
+    Name(Cow<'static, str>),
+    /// The `<<` merge key.
+    Merge,
+}
+
+/// Builds `VizValue` documents directly from YAML parser events.
+///
+/// Unlike [`yaml::YamlLoader`], it keeps track of the number of values and the nesting depth,
+/// including the values produced by aliases, so inputs like "billion laughs" are rejected
+/// before they exhaust the memory.
+struct Loader<'o> {
+    options: &'o ParseOptions,
+    docs: Vec<VizValue<'static>>,
+    root: Option<Subtree>,
+    stack: Vec<Frame>,
+    keys: Vec<Option<EntryKey>>,
+    anchors: HashMap<usize, Subtree>,
+    nodes: usize,
+    duplicates: Vec<String>,
+    /// Whether the scalar that is being inserted is a plain `<<`.
+    merge: bool,
+    error: Option<(VizError, Marker)>,
+}
+
+impl<'o> Loader<'o> {
+    fn new(options: &'o ParseOptions) -> Self {
+        Self {
+            options,
+            docs: Vec::new(),
+            root: None,
+            stack: Vec::new(),
+            keys: Vec::new(),
+            anchors: HashMap::new(),
+            nodes: 0,
+            duplicates: Vec::new(),
+            merge: false,
+            error: None,
+        }
+    }
+
+    fn on_event_impl(&mut self, ev: Event) -> Result<()> {
+        match ev {
+            Event::DocumentEnd => {
+                let doc = self.root.take().map(|t| t.value).unwrap_or(VizValue::Null);
+                self.docs.push(doc);
+            }
+            Event::SequenceStart(anchor, tag) => {
+                self.begin(VizValue::Array(Vec::new()), anchor, tag)?
+            }
+            Event::MappingStart(anchor, tag) => {
+                self.begin(VizValue::Object(IndexMap::new()), anchor, tag)?;
+                self.keys.push(None);
+            }
+            Event::SequenceEnd => self.end()?,
+            Event::MappingEnd => {
+                self.keys.pop();
+                self.end()?;
+            }
+            Event::Scalar(value, style, anchor, tag) => {
+                self.count(1, 0)?;
+                self.merge = style == TScalarStyle::Plain && tag.is_none() && value == "<<";
+                let tree = Subtree {
+                    value: scalar(value, style, tag, self.options.yaml_version),
+                    nodes: 1,
+                    height: 0,
+                };
+                self.insert(tree, anchor)?;
+            }
+            Event::Alias(id) => {
+                let Some((nodes, height)) = self.anchors.get(&id).map(|t| (t.nodes, t.height))
+                else {
+                    return Err(invalid("unknown anchor is referenced"));
+                };
+                // Checking the size before cloning keeps aliases from blowing up the memory.
+                self.count(nodes, height)?;
+                let tree = self.anchors[&id].clone();
+                self.insert(tree, 0)?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Accounts new values and checks them against the limits.
+    fn count(&mut self, nodes: usize, height: usize) -> Result<()> {
+        self.nodes += nodes;
+        self.options.check_nodes(self.nodes)?;
+        self.options.check_depth(self.stack.len() + height)
+    }
+
+    /// Starts a new collection.
+    fn begin(&mut self, value: VizValue<'static>, anchor: usize, tag: Option<Tag>) -> Result<()> {
+        self.count(1, 0)?;
+        self.stack.push(Frame {
+            tree: Subtree {
+                value,
+                nodes: 1,
+                height: 0,
+            },
+            anchor,
+            tag: tag.as_ref().and_then(tag_name),
+            merged: HashSet::new(),
+        });
+        Ok(())
+    }
+
+    /// Finishes the innermost collection.
+    fn end(&mut self) -> Result<()> {
+        let frame = self
+            .stack
+            .pop()
+            .ok_or_else(|| invalid("unexpected end of collection"))?;
+        let mut tree = frame.tree;
+        if let Some(tag) = frame.tag {
+            tree.value = VizValue::Tagged(Cow::Owned(tag), Box::new(tree.value));
+        }
+        self.insert(tree, frame.anchor)
+    }
+
+    /// Inserts a finished value into its parent collection.
+    fn insert(&mut self, tree: Subtree, anchor: usize) -> Result<()> {
+        let merge = mem::take(&mut self.merge);
+
+        // Valid anchor ids start from 1.
+        if anchor > 0 {
+            self.anchors.insert(anchor, tree.clone());
+        }
+
+        let Some(parent) = self.stack.last_mut() else {
+            self.root = Some(tree);
+            return Ok(());
+        };
+
+        parent.tree.nodes += tree.nodes;
+        parent.tree.height = parent.tree.height.max(tree.height + 1);
+
+        let (map, merged) = match &mut parent.tree.value {
+            VizValue::Array(vec) => {
+                vec.push(tree.value);
+                return Ok(());
+            }
+            VizValue::Object(map) => (map, &mut parent.merged),
+            _ => unreachable!("only collections are pushed to the stack"),
+        };
+
+        let key = self
+            .keys
+            .last_mut()
+            .ok_or_else(|| invalid("mapping without keys"))?;
+        let name = match key.take() {
+            None => {
+                *key = Some(if merge {
+                    EntryKey::Merge
+                } else {
+                    EntryKey::Name(key_name(tree.value))
+                });
+                return Ok(());
+            }
+            Some(EntryKey::Merge) => return merge_into(map, merged, tree.value),
+            Some(EntryKey::Name(name)) => name,
+        };
+
+        // Keys that come from a merge are overridden by explicit keys without a warning.
+        if !merged.remove(&name) && map.contains_key(&name) {
+            if self.options.strict {
+                return Err(invalid(format!("\"{name}\": duplicated key in mapping")));
+            }
+            let path = self.path_to(&name);
+            self.duplicates.push(path);
+        }
+
+        // The first occurrence keeps its position, but the last value wins.
+        if let Some(Frame {
+            tree:
+                Subtree {
+                    value: VizValue::Object(map),
+                    ..
+                },
+            ..
+        }) = self.stack.last_mut()
+        {
+            map.insert(name, tree.value);
+        }
+
+        Ok(())
+    }
+
+    /// Builds a JSON Pointer to the key of the innermost mapping.
+    fn path_to(&self, name: &str) -> String {
+        let mut path = String::new();
+        let mut keys = self.keys.iter();
+        let parents = &self.stack[..self.stack.len().saturating_sub(1)];
+
+        for frame in parents {
+            match &frame.tree.value {
+                VizValue::Array(vec) => path.push_str(&format!("/{}", vec.len())),
+                VizValue::Object(_) => {
+                    if let Some(Some(EntryKey::Name(key))) = keys.next() {
+                        path.push_str(&format!("/{}", escape_pointer(key)));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        path.push_str(&format!("/{}", escape_pointer(name)));
+        path
+    }
+}
+
+impl MarkedEventReceiver for Loader<'_> {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(e) = self.on_event_impl(ev) {
+            self.error = Some((e, mark));
+        }
+    }
+}
+
+/// Converts an error of the YAML parser into one that points to its location.
+fn scan_error(e: ScanError, data: &str) -> VizError {
+    // The scanner counts the levels of flow collections, like `[[1]]`, in a byte.
+    if e.info() == "recursion limit exceeded" {
+        return VizError::Limit(
+            "flow collections are nested deeper than the 255 levels the YAML parser supports"
+                .to_string(),
+        );
+    }
+    let mark = e.marker();
+    ParseError::new("YAML", e.info(), mark.line(), mark.col() + 1, data).into()
+}
+
+/// Creates an error of invalid YAML, which is located once it is returned from the loader.
+fn invalid(message: impl Into<String>) -> VizError {
+    VizError::invalid("YAML", message)
+}
+
+/// Copies entries of the mappings referenced by a `<<` merge key into the mapping.
+///
+/// Keys that are already present are kept, and earlier mappings of a sequence
+/// take precedence over later ones.
+fn merge_into(
+    map: &mut IndexMap<Cow<'static, str>, VizValue<'static>>,
+    merged: &mut HashSet<Cow<'static, str>>,
+    mut value: VizValue<'static>,
+) -> Result<()> {
+    let sources = match value {
+        VizValue::Object(ref mut source) => vec![mem::take(source)],
+        VizValue::Array(ref mut vec) => mem::take(vec)
+            .into_iter()
+            .map(|mut item| match &mut item {
+                VizValue::Object(source) => Ok(mem::take(source)),
+                _ => Err(invalid(
+                    "merge key expects a mapping or a sequence of mappings",
+                )),
+            })
+            .collect::<Result<_>>()?,
+        _ => {
+            return Err(invalid(
+                "merge key expects a mapping or a sequence of mappings",
+            ));
+        }
+    };
+
+    for (name, value) in sources.into_iter().flatten() {
+        if !map.contains_key(&name) {
+            merged.insert(name.clone());
+            map.insert(name, value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Interprets a scalar the same way as [`yaml::YamlLoader`] does.
+///
+/// Tags outside of the core schema are kept, so they can be displayed with the value.
+fn scalar(
+    value: String,
+    style: TScalarStyle,
+    tag: Option<Tag>,
+    version: YamlVersion,
+) -> VizValue<'static> {
+    let custom = tag.as_ref().and_then(tag_name);
+    let node = if style != TScalarStyle::Plain {
+        yaml::Yaml::String(value)
+    } else if let (YamlVersion::V1_1, None, Some(node)) = (version, &tag, yaml11_scalar(&value)) {
+        node
+    } else if let Some(Tag { handle, suffix }) = tag {
+        if handle == "tag:yaml.org,2002:" {
+            match suffix.as_str() {
+                "bool" => value
+                    .parse::<bool>()
+                    .map(yaml::Yaml::Boolean)
+                    .unwrap_or(yaml::Yaml::BadValue),
+                "int" => value
+                    .parse::<i64>()
+                    .map(yaml::Yaml::Integer)
+                    .unwrap_or(yaml::Yaml::BadValue),
+                "float" => yaml::Yaml::Real(value),
+                "null" => match value.as_str() {
+                    "~" | "null" => yaml::Yaml::Null,
+                    _ => yaml::Yaml::BadValue,
+                },
+                _ => yaml::Yaml::String(value),
+            }
+        } else {
+            yaml::Yaml::String(value)
+        }
+    } else {
+        yaml::Yaml::from_str(&value)
+    };
+
+    let value = VizValue::from_yaml(node);
+    match custom {
+        Some(tag) => VizValue::Tagged(Cow::Owned(tag), Box::new(value)),
+        None => value,
+    }
+}
+
+/// Interprets a plain scalar by the rules of YAML 1.1 where they differ from YAML 1.2.
+fn yaml11_scalar(value: &str) -> Option<yaml::Yaml> {
+    match value {
+        "y" | "Y" | "yes" | "Yes" | "YES" | "on" | "On" | "ON" => {
+            return Some(yaml::Yaml::Boolean(true));
+        }
+        "n" | "N" | "no" | "No" | "NO" | "off" | "Off" | "OFF" => {
+            return Some(yaml::Yaml::Boolean(false));
+        }
+        _ => {}
+    }
+
+    let (negative, digits) = match value.as_bytes().first()? {
+        b'-' => (true, &value[1..]),
+        b'+' => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let digits = digits.replace('_', "");
+    if digits.is_empty() {
+        return None;
+    }
+    let sign = if negative { -1 } else { 1 };
+
+    if let Some(binary) = digits.strip_prefix("0b") {
+        return i64::from_str_radix(binary, 2)
+            .ok()
+            .map(|n| yaml::Yaml::Integer(sign * n));
+    }
+    if digits.len() > 1 && digits.starts_with('0') && digits.bytes().all(|b| b.is_ascii_digit()) {
+        return i64::from_str_radix(&digits[1..], 8)
+            .ok()
+            .map(|n| yaml::Yaml::Integer(sign * n));
+    }
+
+    // Underscores are allowed as separators in all numbers.
+    if value.contains('_') {
+        if let Ok(n) = digits.parse::<i64>() {
+            return Some(yaml::Yaml::Integer(sign * n));
+        }
+        if digits.parse::<f64>().is_ok() {
+            let sign = if negative { "-" } else { "" };
+            return Some(yaml::Yaml::Real(format!("{sign}{digits}")));
+        }
+    }
+
+    // Sexagesimal numbers like `1:30:00` are in base 60.
+    if digits.contains(':') {
+        let (whole, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+        let mut total: i64 = 0;
+        for (id, part) in whole.split(':').enumerate() {
+            let valid = !part.is_empty()
+                && part.bytes().all(|b| b.is_ascii_digit())
+                && (id == 0 || (part.len() <= 2 && part.parse::<u8>().ok()? < 60));
+            if !valid {
+                return None;
+            }
+            total = total.checked_mul(60)?.checked_add(part.parse().ok()?)?;
+        }
+        if fraction.is_empty() && !digits.contains('.') {
+            return Some(yaml::Yaml::Integer(sign * total));
+        }
+        let fraction: f64 = format!("0.{fraction}").parse().ok()?;
+        let number = sign as f64 * (total as f64 + fraction);
+        return Some(yaml::Yaml::Real(number.to_string()));
+    }
+
+    None
+}
+
+/// Returns the display name of a tag, or `None` for tags of the core schema.
+fn tag_name(tag: &Tag) -> Option<String> {
+    const CORE: [&str; 7] = ["str", "int", "float", "bool", "null", "map", "seq"];
+
+    match (tag.handle.as_str(), tag.suffix.as_str()) {
+        ("tag:yaml.org,2002:", suffix) if CORE.contains(&suffix) => None,
+        ("tag:yaml.org,2002:", suffix) => Some(format!("!!{suffix}")),
+        // A non-specific `!` tag only forces the value to be a string.
+        ("!", "") => None,
+        ("!", suffix) => Some(format!("!{suffix}")),
+        (handle, suffix) => Some(format!("!<{handle}{suffix}>")),
+    }
+}
+
+/// Turns a value used as a mapping key into a key name.
+fn key_name(mut value: VizValue<'static>) -> Cow<'static, str> {
+    match &mut value {
+        VizValue::String(s) | VizValue::DateTime(s) | VizValue::RawNumber(s) => mem::take(s),
+        VizValue::Number(n) => Cow::Owned(n.to_string()),
+        VizValue::Float(f) => Cow::Owned(f.to_string()),
+        VizValue::Tagged(_, value) => key_name(mem::take(value)),
+        VizValue::Bool(b) => Cow::Owned(b.to_string()),
+        VizValue::Null => Cow::Borrowed("null"),
+        VizValue::Array(_) | VizValue::Object(_) => Cow::Borrowed(""),
+    }
+}
@@ -0,0 +1,240 @@
+use crate::error::{Result, VizError};
+use crate::path::{Path, PathSegment};
+use crate::values::VizValue;
+
+/// A value that matches a query, with the path to it.
+pub type Match<'v, 'a> = (Path<'v>, &'v VizValue<'a>);
+
+/// A step of a query.
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    /// Keys of objects, like `.name` or `['a', 'b']`.
+    Keys(Vec<String>),
+
+    /// Indices of arrays, where negative ones count from the end, like `[0]` or `[-1]`.
+    Indices(Vec<i64>),
+
+    /// A slice of arrays, like `[1:3]`.
+    Slice(Option<i64>, Option<i64>),
+
+    /// Every child, like `.*` or `[*]`.
+    Wildcard,
+
+    /// The step applied to the value and all of its descendants, like `..name`.
+    Descendants(Box<Step>),
+}
+
+/// Returns the values that match a query, in the order of the document.
+///
+/// The query is a JSON Pointer, like `/servers/0`, or a JSONPath expression, like
+/// `$.servers[*].host`, `$..name` or `$.items[-1]`, where the leading `$` may be left out.
+/// Filters and other expressions of JSONPath are not supported.
+pub fn query<'v, 'a>(value: &'v VizValue<'a>, expression: &str) -> Result<Vec<Match<'v, 'a>>> {
+    let expression = expression.trim();
+    if expression.starts_with('/') {
+        return Ok(pointer_match(value, expression).into_iter().collect());
+    }
+
+    let mut matches = vec![(Path::root(), value)];
+    for step in parse(expression)? {
+        matches = matches
+            .into_iter()
+            .flat_map(|(path, value)| apply(&step, path, value))
+            .collect();
+    }
+    Ok(matches)
+}
+
+/// Follows a JSON Pointer and keeps the path of the value.
+fn pointer_match<'v, 'a>(value: &'v VizValue<'a>, pointer: &str) -> Option<Match<'v, 'a>> {
+    let mut path = Path::root();
+    let mut current = value;
+    for segment in pointer.split('/').skip(1) {
+        let key = segment.replace("~1", "/").replace("~0", "~");
+        current = match current.untagged() {
+            VizValue::Object(map) => {
+                let (key, value) = map.get_key_value(key.as_str())?;
+                path.push(PathSegment::Key(key));
+                value
+            }
+            VizValue::Array(vec) => {
+                let index = key.parse::<usize>().ok()?;
+                path.push(PathSegment::Index(index));
+                vec.get(index)?
+            }
+            _ => return None,
+        };
+    }
+    Some((path, current))
+}
+
+/// Applies a step to a value and returns the values it selects.
+fn apply<'v, 'a>(step: &Step, path: Path<'v>, value: &'v VizValue<'a>) -> Vec<Match<'v, 'a>> {
+    let child = |segment| {
+        let mut path = path.clone();
+        path.push(segment);
+        path
+    };
+    let elements = |vec: &'v [VizValue<'a>], indices: &mut dyn Iterator<Item = usize>| {
+        indices
+            .filter_map(|index| Some((child(PathSegment::Index(index)), vec.get(index)?)))
+            .collect()
+    };
+
+    match (step, value.untagged()) {
+        (Step::Keys(keys), VizValue::Object(map)) => keys
+            .iter()
+            .filter_map(|key| map.get_key_value(key.as_str()))
+            .map(|(key, value)| (child(PathSegment::Key(key)), value))
+            .collect(),
+        (Step::Indices(indices), VizValue::Array(vec)) => {
+            let len = vec.len() as i64;
+            let mut indices = indices
+                .iter()
+                .map(|index| if *index < 0 { len + index } else { *index })
+                .filter(|index| (0..len).contains(index))
+                .map(|index| index as usize);
+            elements(vec, &mut indices)
+        }
+        (Step::Slice(start, end), VizValue::Array(vec)) => {
+            let len = vec.len() as i64;
+            let bound = |index: i64| (if index < 0 { len + index } else { index }).clamp(0, len);
+            let start = start.map_or(0, bound) as usize;
+            let end = end.map_or(len, bound) as usize;
+            elements(vec, &mut (start..end.max(start)))
+        }
+        (Step::Wildcard, VizValue::Array(vec)) => elements(vec, &mut (0..vec.len())),
+        (Step::Wildcard, VizValue::Object(map)) => map
+            .iter()
+            .map(|(key, value)| (child(PathSegment::Key(key)), value))
+            .collect(),
+        (Step::Descendants(step), value) => {
+            let mut matches = Vec::new();
+            value.walk(|relative, value| {
+                let mut full = path.clone();
+                for segment in relative.segments() {
+                    full.push(*segment);
+                }
+                matches.extend(apply(step, full, value));
+            });
+            matches
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Parses a JSONPath expression into its steps.
+fn parse(expression: &str) -> Result<Vec<Step>> {
+    let error = |message: &str| VizError::invalid("query", format!("{message} in `{expression}`"));
+    let rest = expression.strip_prefix('$').unwrap_or(expression);
+    let mut chars = rest.chars().peekable();
+    let mut steps = Vec::new();
+
+    // Without `$`, the expression may start with a key, like `servers[0]`.
+    let mut after_dot = !expression.starts_with('$') && !rest.starts_with(['.', '[']);
+    loop {
+        let descendants = if after_dot {
+            false
+        } else {
+            match chars.next() {
+                None => break,
+                Some('.') if chars.peek() == Some(&'.') => {
+                    chars.next();
+                    true
+                }
+                Some('.') => false,
+                Some('[') => {
+                    steps.push(parse_brackets(&mut chars).map_err(error)?);
+                    continue;
+                }
+                Some(_) => return Err(error("expected `.` or `[`")),
+            }
+        };
+        after_dot = false;
+
+        let step = match chars.peek() {
+            Some('[') if descendants => {
+                chars.next();
+                parse_brackets(&mut chars).map_err(error)?
+            }
+            Some('*') => {
+                chars.next();
+                Step::Wildcard
+            }
+            _ => {
+                let mut key = String::new();
+                while let Some(c) = chars.next_if(|c| !matches!(c, '.' | '[')) {
+                    key.push(c);
+                }
+                if key.is_empty() {
+                    return Err(error("expected a key"));
+                }
+                Step::Keys(vec![key])
+            }
+        };
+        steps.push(if descendants {
+            Step::Descendants(Box::new(step))
+        } else {
+            step
+        });
+    }
+
+    Ok(steps)
+}
+
+/// Parses the inside of brackets, after the opening one, like `0]` or `'name', 'id']`.
+fn parse_brackets(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+) -> std::result::Result<Step, &'static str> {
+    let mut inner = String::new();
+    let mut quote = None;
+    loop {
+        match chars.next() {
+            None => return Err("a bracket is not closed"),
+            Some(']') if quote.is_none() => break,
+            Some(c @ ('\'' | '"')) if quote.is_none() => {
+                quote = Some(c);
+                inner.push(c);
+            }
+            Some(c) if Some(c) == quote => {
+                quote = None;
+                inner.push(c);
+            }
+            Some(c) => inner.push(c),
+        }
+    }
+
+    let inner = inner.trim();
+    if inner == "*" {
+        return Ok(Step::Wildcard);
+    }
+    let parts: Vec<&str> = inner.split(',').map(str::trim).collect();
+    let quoted: Option<Vec<String>> = parts
+        .iter()
+        .map(|part| {
+            ['\'', '"']
+                .into_iter()
+                .find_map(|quote| Some(part.strip_prefix(quote)?.strip_suffix(quote)?.to_string()))
+        })
+        .collect();
+    if let Some(keys) = quoted {
+        return Ok(Step::Keys(keys));
+    }
+    if let Some((start, end)) = inner.split_once(':') {
+        let bound = |text: &str| match text.trim() {
+            "" => Ok(None),
+            text => text
+                .parse()
+                .map(Some)
+                .map_err(|_| "invalid bound of a slice"),
+        };
+        return Ok(Step::Slice(bound(start)?, bound(end)?));
+    }
+
+    parts
+        .iter()
+        .map(|part| part.parse())
+        .collect::<std::result::Result<_, _>>()
+        .map(Step::Indices)
+        .map_err(|_| "expected an index, a quoted key, a slice or `*`")
+}
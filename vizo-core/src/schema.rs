@@ -0,0 +1,463 @@
+use crate::diff::is_same;
+use crate::processors::Warning;
+use crate::values::{VizValue, escape_pointer};
+use indexmap::IndexMap;
+use std::borrow::Cow;
+
+/// How many `$ref` references may be followed without descending into the document.
+const MAX_REF_DEPTH: usize = 256;
+
+/// Keywords of JSON Schema that [`validate`] does not check.
+pub const UNSUPPORTED: [&str; 15] = [
+    "pattern",
+    "format",
+    "patternProperties",
+    "propertyNames",
+    "dependencies",
+    "dependentRequired",
+    "dependentSchemas",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "minContains",
+    "maxContains",
+    "contentEncoding",
+    "contentMediaType",
+    "$dynamicRef",
+    "$recursiveRef",
+];
+
+/// An object of the schema.
+type Object<'a> = IndexMap<Cow<'a, str>, VizValue<'a>>;
+
+/// A single place where the document does not match the schema.
+#[derive(Debug, Clone)]
+pub struct Violation {
+    /// JSON Pointer to the offending value.
+    pub path: String,
+
+    /// Description of the violation.
+    pub message: String,
+}
+
+/// Validates the value against a JSON Schema.
+///
+/// Supports the commonly used validation keywords of drafts 4 through 2020-12,
+/// including local `$ref` references. Keywords that are not supported,
+/// like `pattern` and `format`, are ignored, and [`unsupported_keywords`] lists them.
+pub fn validate(schema: &VizValue, value: &VizValue) -> Vec<Violation> {
+    let mut validator = Validator {
+        root: schema,
+        violations: Vec::new(),
+        ref_depth: 0,
+    };
+    validator.validate(schema, value, "");
+    validator.violations
+}
+
+/// Returns a warning for every keyword of the [`UNSUPPORTED`] ones that the schema uses,
+/// with the JSON Pointer to the subschema that has it, unless that is the root.
+pub fn unsupported_keywords(schema: &VizValue) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let mut stack = vec![(String::new(), schema)];
+    while let Some((path, schema)) = stack.pop() {
+        let VizValue::Object(schema) = schema.untagged() else {
+            continue;
+        };
+        for (key, value) in schema {
+            let key_path = format!("{path}/{}", escape_pointer(key));
+            if UNSUPPORTED.contains(&key.as_ref()) {
+                warnings.push(Warning {
+                    path: Some(path.clone()).filter(|path| !path.is_empty()),
+                    message: format!(
+                        "the schema keyword \"{key}\" is not supported and is ignored"
+                    ),
+                });
+                continue;
+            }
+            match (key.as_ref(), value.untagged()) {
+                ("properties" | "$defs" | "definitions", VizValue::Object(schemas)) => {
+                    stack.extend(schemas.iter().map(|(name, schema)| {
+                        (format!("{key_path}/{}", escape_pointer(name)), schema)
+                    }));
+                }
+                (
+                    "allOf" | "anyOf" | "oneOf" | "prefixItems" | "items",
+                    VizValue::Array(schemas),
+                ) => {
+                    stack.extend(
+                        schemas
+                            .iter()
+                            .enumerate()
+                            .map(|(id, schema)| (format!("{key_path}/{id}"), schema)),
+                    );
+                }
+                (
+                    "items"
+                    | "additionalItems"
+                    | "additionalProperties"
+                    | "contains"
+                    | "not"
+                    | "if"
+                    | "then"
+                    | "else",
+                    schema,
+                ) => stack.push((key_path, schema)),
+                _ => {}
+            }
+        }
+    }
+    warnings.sort_by(|a, b| a.path.cmp(&b.path));
+    warnings
+}
+
+/// Collects violations while walking through the schema and the document.
+struct Validator<'s, 'a> {
+    root: &'s VizValue<'a>,
+    violations: Vec<Violation>,
+    ref_depth: usize,
+}
+
+impl<'s, 'a> Validator<'s, 'a> {
+    /// Returns `true` if the value matches the schema, without recording violations.
+    fn matches(&self, schema: &'s VizValue<'a>, value: &VizValue, path: &str) -> bool {
+        let mut nested = Validator {
+            root: self.root,
+            violations: Vec::new(),
+            ref_depth: self.ref_depth,
+        };
+        nested.validate(schema, value, path);
+        nested.violations.is_empty()
+    }
+
+    fn report(&mut self, path: &str, message: String) {
+        self.violations.push(Violation {
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn validate(&mut self, schema: &'s VizValue<'a>, value: &VizValue, path: &str) {
+        let value = value.untagged();
+        let schema = match schema {
+            VizValue::Bool(true) => return,
+            VizValue::Bool(false) => {
+                self.report(path, "no value is allowed here".to_string());
+                return;
+            }
+            VizValue::Object(schema) => schema,
+            _ => return,
+        };
+
+        if let Some(VizValue::String(reference)) = schema.get("$ref") {
+            match self.resolve(reference) {
+                Some(_) if self.ref_depth >= MAX_REF_DEPTH => self.report(
+                    path,
+                    format!("reference \"{reference}\" is nested too deep"),
+                ),
+                Some(target) => {
+                    self.ref_depth += 1;
+                    self.validate(target, value, path);
+                    self.ref_depth -= 1;
+                }
+                None => self.report(path, format!("unresolvable reference \"{reference}\"")),
+            }
+        }
+
+        if let Some(types) = schema.get("type") {
+            let allowed: Vec<&str> = match types {
+                VizValue::String(t) => vec![t],
+                VizValue::Array(types) => types
+                    .iter()
+                    .filter_map(|t| match t {
+                        VizValue::String(t) => Some(t.as_ref()),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|t| is_type(value, t)) {
+                self.report(
+                    path,
+                    format!(
+                        "expected {}, found {}",
+                        allowed.join(" or "),
+                        type_name(value)
+                    ),
+                );
+            }
+        }
+
+        if let Some(VizValue::Array(options)) = schema.get("enum")
+            && !options.iter().any(|option| is_same(option, value))
+        {
+            self.report(path, "value is not one of the allowed options".to_string());
+        }
+
+        if let Some(expected) = schema.get("const")
+            && !is_same(expected, value)
+        {
+            self.report(
+                path,
+                "value does not match the expected constant".to_string(),
+            );
+        }
+
+        self.validate_combinators(schema, value, path);
+
+        match value {
+            VizValue::Object(map) => self.validate_object(schema, map, path),
+            VizValue::Array(vec) => self.validate_array(schema, vec, path),
+            VizValue::String(s) | VizValue::DateTime(s) => self.validate_string(schema, s, path),
+            VizValue::Number(n) => self.validate_number(schema, *n as f64, path),
+            VizValue::Float(f) => self.validate_number(schema, *f, path),
+            VizValue::RawNumber(_) => {
+                if let Some(n) = value.as_f64() {
+                    self.validate_number(schema, n, path)
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn validate_combinators(&mut self, schema: &'s Object<'a>, value: &VizValue, path: &str) {
+        if let Some(VizValue::Array(schemas)) = schema.get("allOf") {
+            for sub in schemas {
+                self.validate(sub, value, path);
+            }
+        }
+
+        if let Some(VizValue::Array(schemas)) = schema.get("anyOf")
+            && !schemas.iter().any(|sub| self.matches(sub, value, path))
+        {
+            self.report(path, "value does not match any of the schemas".to_string());
+        }
+
+        if let Some(VizValue::Array(schemas)) = schema.get("oneOf") {
+            let matched = schemas
+                .iter()
+                .filter(|sub| self.matches(sub, value, path))
+                .count();
+            if matched != 1 {
+                self.report(
+                    path,
+                    format!("value must match exactly one schema, but matches {matched}"),
+                );
+            }
+        }
+
+        if let Some(sub) = schema.get("not")
+            && self.matches(sub, value, path)
+        {
+            self.report(path, "value must not match the schema".to_string());
+        }
+
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.matches(condition, value, path) {
+                schema.get("then")
+            } else {
+                schema.get("else")
+            };
+            if let Some(branch) = branch {
+                self.validate(branch, value, path);
+            }
+        }
+    }
+
+    fn validate_object(&mut self, schema: &'s Object<'a>, map: &Object<'_>, path: &str) {
+        if let Some(VizValue::Array(required)) = schema.get("required") {
+            for key in required {
+                if let VizValue::String(key) = key
+                    && !map.contains_key(key.as_ref())
+                {
+                    self.report(path, format!("missing required property \"{key}\""));
+                }
+            }
+        }
+
+        let properties = match schema.get("properties") {
+            Some(VizValue::Object(properties)) => Some(properties),
+            _ => None,
+        };
+        let additional = schema.get("additionalProperties");
+
+        for (key, item) in map {
+            let item_path = format!("{path}/{}", escape_pointer(key));
+            match properties.and_then(|p| p.get(key.as_ref())) {
+                Some(sub) => self.validate(sub, item, &item_path),
+                None => match additional {
+                    Some(VizValue::Bool(false)) => {
+                        self.report(&item_path, format!("property \"{key}\" is not allowed"))
+                    }
+                    Some(sub) => self.validate(sub, item, &item_path),
+                    None => {}
+                },
+            }
+        }
+
+        if let Some(min) = get_usize(schema.get("minProperties"))
+            && map.len() < min
+        {
+            self.report(path, format!("expected at least {min} properties"));
+        }
+        if let Some(max) = get_usize(schema.get("maxProperties"))
+            && map.len() > max
+        {
+            self.report(path, format!("expected at most {max} properties"));
+        }
+    }
+
+    fn validate_array(&mut self, schema: &'s Object<'a>, vec: &[VizValue], path: &str) {
+        let prefix = match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(VizValue::Array(prefix)), _) => prefix.as_slice(),
+            // Before draft 2020-12, an array of schemas in `items` described a tuple.
+            (None, Some(VizValue::Array(prefix))) => prefix.as_slice(),
+            _ => &[],
+        };
+        let rest = match schema.get("items") {
+            Some(VizValue::Array(_)) => schema.get("additionalItems"),
+            items => items,
+        };
+
+        for (id, item) in vec.iter().enumerate() {
+            let item_path = format!("{path}/{id}");
+            if let Some(sub) = prefix.get(id).or(rest) {
+                self.validate(sub, item, &item_path)
+            }
+        }
+
+        if let Some(min) = get_usize(schema.get("minItems"))
+            && vec.len() < min
+        {
+            self.report(path, format!("expected at least {min} items"));
+        }
+        if let Some(max) = get_usize(schema.get("maxItems"))
+            && vec.len() > max
+        {
+            self.report(path, format!("expected at most {max} items"));
+        }
+
+        if let Some(VizValue::Bool(true)) = schema.get("uniqueItems") {
+            for (id, item) in vec.iter().enumerate() {
+                if vec[..id].contains(item) {
+                    self.report(&format!("{path}/{id}"), "duplicate item".to_string());
+                }
+            }
+        }
+
+        if let Some(sub) = schema.get("contains")
+            && !vec
+                .iter()
+                .enumerate()
+                .any(|(id, item)| self.matches(sub, item, &format!("{path}/{id}")))
+        {
+            self.report(path, "no item matches the \"contains\" schema".to_string());
+        }
+    }
+
+    fn validate_string(&mut self, schema: &'s Object<'a>, s: &str, path: &str) {
+        let length = s.chars().count();
+        if let Some(min) = get_usize(schema.get("minLength"))
+            && length < min
+        {
+            self.report(path, format!("expected at least {min} characters"));
+        }
+        if let Some(max) = get_usize(schema.get("maxLength"))
+            && length > max
+        {
+            self.report(path, format!("expected at most {max} characters"));
+        }
+    }
+
+    fn validate_number(&mut self, schema: &'s Object<'a>, n: f64, path: &str) {
+        let exclusive_flag = |key| matches!(schema.get(key), Some(VizValue::Bool(true)));
+
+        if let Some(min) = get_f64(schema.get("minimum")) {
+            // Draft 4 uses a boolean `exclusiveMinimum` to modify `minimum`.
+            if exclusive_flag("exclusiveMinimum") && n <= min {
+                self.report(path, format!("expected a value greater than {min}"));
+            } else if n < min {
+                self.report(path, format!("expected a value of at least {min}"));
+            }
+        }
+        if let Some(max) = get_f64(schema.get("maximum")) {
+            if exclusive_flag("exclusiveMaximum") && n >= max {
+                self.report(path, format!("expected a value less than {max}"));
+            } else if n > max {
+                self.report(path, format!("expected a value of at most {max}"));
+            }
+        }
+        if let Some(min) = get_f64(schema.get("exclusiveMinimum"))
+            && n <= min
+        {
+            self.report(path, format!("expected a value greater than {min}"));
+        }
+        if let Some(max) = get_f64(schema.get("exclusiveMaximum"))
+            && n >= max
+        {
+            self.report(path, format!("expected a value less than {max}"));
+        }
+        if let Some(divisor) = get_f64(schema.get("multipleOf"))
+            && divisor > 0.0
+            && !is_multiple(n, divisor)
+        {
+            self.report(path, format!("expected a multiple of {divisor}"));
+        }
+    }
+
+    /// Resolves a local reference like `#/$defs/name`.
+    fn resolve(&self, reference: &str) -> Option<&'s VizValue<'a>> {
+        self.root.pointer(reference.strip_prefix('#')?)
+    }
+}
+
+/// Returns `true` if `n` is a multiple of `divisor`, allowing for the rounding of floats,
+/// so that `0.3` is a multiple of `0.1`.
+fn is_multiple(n: f64, divisor: f64) -> bool {
+    let quotient = n / divisor;
+    if !quotient.is_finite() {
+        return false;
+    }
+    (quotient - quotient.round()).abs() <= quotient.abs().max(1.0) * 1e-9
+}
+
+/// Returns `true` if the value is of the given JSON Schema type.
+fn is_type(value: &VizValue, name: &str) -> bool {
+    match name {
+        "integer" => match value {
+            VizValue::Number(_) => true,
+            VizValue::Float(f) => f.fract() == 0.0,
+            VizValue::RawNumber(_) => value.as_f64().is_some_and(|f| f.fract() == 0.0),
+            _ => false,
+        },
+        "number" => matches!(
+            value,
+            VizValue::Number(_) | VizValue::Float(_) | VizValue::RawNumber(_)
+        ),
+        name => type_name(value) == name,
+    }
+}
+
+/// Returns the JSON Schema type name of the value.
+fn type_name(value: &VizValue) -> &'static str {
+    match value {
+        // JSON Schema has no type for dates, they are strings with a format.
+        VizValue::String(_) | VizValue::DateTime(_) => "string",
+        VizValue::Number(_) => "integer",
+        VizValue::Float(_) => "number",
+        VizValue::RawNumber(s) if s.bytes().all(|b| b.is_ascii_digit() || b == b'-') => "integer",
+        VizValue::RawNumber(_) => "number",
+        VizValue::Null => "null",
+        VizValue::Bool(_) => "boolean",
+        VizValue::Array(_) => "array",
+        VizValue::Object(_) => "object",
+        VizValue::Tagged(_, value) => type_name(value),
+    }
+}
+
+fn get_f64(value: Option<&VizValue>) -> Option<f64> {
+    value?.as_f64()
+}
+
+fn get_usize(value: Option<&VizValue>) -> Option<usize> {
+    get_f64(value).map(|n| n as usize)
+}
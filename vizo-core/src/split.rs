@@ -0,0 +1,59 @@
+use serde::de::IgnoredAny;
+
+/// Splits a stream of several documents, like the output of `kubectl get -o yaml` or of `jq`.
+///
+/// The documents are separated by lines that are equal to `delimiter`, where an empty one
+/// means blank lines, or by NUL characters if it is `\0`. Without a delimiter, NUL characters
+/// are used if there are any, then `---` for YAML, consecutive values for JSON and blank lines
+/// for other languages. Documents that are only whitespace, or a `---`, are skipped.
+pub fn split_documents<'a>(text: &'a str, language: &str, delimiter: Option<&str>) -> Vec<&'a str> {
+    let documents = match delimiter {
+        Some("\0") => text.split('\0').collect(),
+        Some(delimiter) => split_at_lines(text, false, |line| line.trim() == delimiter.trim()),
+        None if text.contains('\0') => text.split('\0').collect(),
+        // The markers start the documents, and may be followed by their content, like `--- {}`.
+        None if language == "yaml" => split_at_lines(text, true, |line| {
+            line.trim_end() == "---" || line.starts_with("--- ")
+        }),
+        None if language == "json" => split_json(text),
+        None => split_at_lines(text, false, |line| line.trim().is_empty()),
+    };
+    documents
+        .into_iter()
+        // A YAML marker at the end of the stream does not start a document.
+        .filter(|document| !document.trim().trim_start_matches("---").trim().is_empty())
+        .collect()
+}
+
+/// Splits the text at the lines that match, which are kept at the start of the next document
+/// if `keep` is set.
+fn split_at_lines(text: &str, keep: bool, is_delimiter: impl Fn(&str) -> bool) -> Vec<&str> {
+    let mut documents = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if is_delimiter(line) {
+            documents.push(&text[start..offset]);
+            start = if keep { offset } else { offset + line.len() };
+        }
+        offset += line.len();
+    }
+    documents.push(&text[start..]);
+    documents
+}
+
+/// Splits concatenated JSON values, like `{"a": 1} {"a": 2}`, whatever their layout is.
+///
+/// Text that is not valid JSON is kept as the last document, so its error is reported.
+fn split_json(text: &str) -> Vec<&str> {
+    let mut documents = Vec::new();
+    let mut values = serde_json::Deserializer::from_str(text).into_iter::<IgnoredAny>();
+    let mut start = 0;
+    while let Some(Ok(_)) = values.next() {
+        let end = values.byte_offset();
+        documents.push(&text[start..end]);
+        start = end;
+    }
+    documents.push(&text[start..]);
+    documents
+}
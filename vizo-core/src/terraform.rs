@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+
+use indexmap::IndexMap;
+
+use crate::values::VizValue;
+
+/// Returns the resources of a Terraform state, like a `terraform.tfstate` file, or `None` if the
+/// value is not one.
+pub fn resources<'v, 'a>(state: &'v VizValue<'a>) -> Option<&'v [VizValue<'a>]> {
+    state.pointer("/terraform_version")?;
+    match state.pointer("/resources")?.untagged() {
+        VizValue::Array(resources) => Some(resources),
+        _ => None,
+    }
+}
+
+/// Returns the address of a resource, like `module.vpc.aws_subnet.private` or `data.aws_ami.ubuntu`.
+pub fn address(resource: &VizValue) -> String {
+    let mut address = String::new();
+    if let Some(module) = resource.pointer_str("/module") {
+        address.push_str(module);
+        address.push('.');
+    }
+    if resource.pointer_str("/mode") == Some("data") {
+        address.push_str("data.");
+    }
+    address.push_str(resource.pointer_str("/type").unwrap_or("unknown"));
+    address.push('.');
+    address.push_str(resource.pointer_str("/name").unwrap_or("unknown"));
+    address
+}
+
+/// Groups the resources of a state by their modules and types, with the number of instances of each.
+///
+/// Resources of the root module are under `root`.
+pub fn summarize(resources: &[VizValue]) -> VizValue<'static> {
+    let mut modules: IndexMap<String, IndexMap<String, IndexMap<String, i64>>> = IndexMap::new();
+    for resource in resources {
+        let module = resource.pointer_str("/module").unwrap_or("root");
+        let mut kind = resource
+            .pointer_str("/type")
+            .unwrap_or("unknown")
+            .to_string();
+        if resource.pointer_str("/mode") == Some("data") {
+            kind.insert_str(0, "data.");
+        }
+        let name = resource.pointer_str("/name").unwrap_or("unknown");
+        *modules
+            .entry(module.to_string())
+            .or_default()
+            .entry(kind)
+            .or_default()
+            .entry(name.to_string())
+            .or_default() += instances(resource).len() as i64;
+    }
+
+    let object = |map: IndexMap<String, VizValue<'static>>| {
+        VizValue::Object(map.into_iter().map(|(k, v)| (Cow::Owned(k), v)).collect())
+    };
+    object(
+        modules
+            .into_iter()
+            .map(|(module, kinds)| {
+                let kinds = kinds
+                    .into_iter()
+                    .map(|(kind, names)| {
+                        let names = names
+                            .into_iter()
+                            .map(|(name, count)| (name, VizValue::Number(count)))
+                            .collect();
+                        (kind, object(names))
+                    })
+                    .collect();
+                (module, object(kinds))
+            })
+            .collect(),
+    )
+}
+
+/// Returns the attributes of the instances of the resources at an address, like `module.vpc`,
+/// by the addresses of the instances, like `module.vpc.aws_subnet.private[0]`.
+///
+/// The address may be the one of a module, of a type of resources in it, or of a resource.
+pub fn select<'a>(resources: &[VizValue<'a>], prefix: &str) -> VizValue<'a> {
+    let mut selected = IndexMap::new();
+    for resource in resources {
+        let address = address(resource);
+        if address != prefix && !address.starts_with(&format!("{prefix}.")) {
+            continue;
+        }
+        for instance in instances(resource) {
+            let key = match instance.pointer("/index_key").map(VizValue::untagged) {
+                Some(VizValue::Number(index)) => format!("{address}[{index}]"),
+                Some(VizValue::String(key)) => format!("{address}[\"{key}\"]"),
+                _ => address.clone(),
+            };
+            let attributes = instance
+                .pointer("/attributes")
+                .cloned()
+                .unwrap_or(VizValue::Null);
+            selected.insert(Cow::Owned(key), attributes);
+        }
+    }
+    VizValue::Object(selected)
+}
+
+fn instances<'v, 'a>(resource: &'v VizValue<'a>) -> &'v [VizValue<'a>] {
+    match resource.pointer("/instances").map(VizValue::untagged) {
+        Some(VizValue::Array(instances)) => instances,
+        _ => &[],
+    }
+}
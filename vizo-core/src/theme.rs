@@ -0,0 +1,441 @@
+use crate::error::{Result, VizError};
+use colored::Color;
+use std::fs;
+use std::path::Path;
+use toml::{Table, Value};
+
+/// Names of the built-in themes.
+pub const PRESETS: [&str; 5] = ["default", "monokai", "solarized-light", "dracula", "plain"];
+
+/// A color and attributes that a token is printed with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Paint {
+    /// Foreground color, the color of the terminal if not set.
+    pub color: Option<Color>,
+
+    /// Prints the token in bold.
+    pub bold: bool,
+
+    /// Prints the token dimmed.
+    pub dimmed: bool,
+
+    /// Prints the token in italics.
+    pub italic: bool,
+}
+
+impl Paint {
+    /// A paint that leaves the text as is.
+    pub const PLAIN: Paint = Paint {
+        color: None,
+        bold: false,
+        dimmed: false,
+        italic: false,
+    };
+
+    /// Creates a paint with the color.
+    pub const fn new(color: Color) -> Self {
+        Paint {
+            color: Some(color),
+            ..Paint::PLAIN
+        }
+    }
+
+    /// Creates a paint with a truecolor color, like `0xf8f8f2`.
+    pub const fn rgb(rgb: u32) -> Self {
+        Paint::new(Color::TrueColor {
+            r: (rgb >> 16) as u8,
+            g: (rgb >> 8) as u8,
+            b: rgb as u8,
+        })
+    }
+
+    /// Makes the paint bold.
+    pub const fn bold(self) -> Self {
+        Paint { bold: true, ..self }
+    }
+
+    /// Makes the paint dimmed.
+    pub const fn dimmed(self) -> Self {
+        Paint {
+            dimmed: true,
+            ..self
+        }
+    }
+
+    /// Applies the paint to the text with ANSI escape sequences, even if colors are disabled
+    /// for the program. Like in terminals, truecolor colors are approximated with basic colors
+    /// unless `COLORTERM` is `truecolor` or `24bit`.
+    pub fn paint(&self, text: &str) -> String {
+        // Styles around empty text would still print escape sequences.
+        if text.is_empty() || *self == Paint::PLAIN {
+            return text.to_string();
+        }
+
+        let mut codes = Vec::new();
+        if self.bold {
+            codes.push("1".into());
+        }
+        if self.dimmed {
+            codes.push("2".into());
+        }
+        if self.italic {
+            codes.push("3".into());
+        }
+        if let Some(color) = self.color {
+            codes.push(color.to_fg_str());
+        }
+        let style = format!("\x1b[{}m", codes.join(";"));
+        // Resets in the text, like the ones of painted parts, would end the style early.
+        let text = text.replace(RESET, &format!("{RESET}{style}"));
+        format!("{style}{text}{RESET}")
+    }
+}
+
+/// The escape sequence that resets the style.
+const RESET: &str = "\x1b[0m";
+
+/// The background of a terminal, which the default theme picks its colors for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Background {
+    /// A dark background, which most terminals have.
+    #[default]
+    Dark,
+    /// A light background, like the one of `solarized-light`.
+    Light,
+}
+
+/// Paints of every kind of token in the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// Keys of objects.
+    pub key: Paint,
+
+    /// Contents of strings.
+    pub string: Paint,
+
+    /// Integers and floats.
+    pub number: Paint,
+
+    /// `NaN` and infinities.
+    pub special_number: Paint,
+
+    /// `true` and `false`.
+    pub boolean: Paint,
+
+    /// `null`.
+    pub null: Paint,
+
+    /// Dates and times.
+    pub datetime: Paint,
+
+    /// YAML tags, like `!!binary`.
+    pub tag: Paint,
+
+    /// Brackets, quotes, commas and `=`.
+    pub punctuation: Paint,
+
+    /// Keys with annotations and the annotations themselves, like schema violations.
+    pub annotation: Paint,
+
+    /// Markers of omitted content, like the rest of a truncated string.
+    pub muted: Paint,
+
+    /// Comments, which are only shown by `--highlight-only`.
+    pub comment: Paint,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            key: Paint::new(Color::Blue),
+            string: Paint::new(Color::Green),
+            number: Paint::new(Color::Red),
+            special_number: Paint::new(Color::Yellow),
+            boolean: Paint::new(Color::BrightMagenta),
+            null: Paint::new(Color::BrightBlack),
+            datetime: Paint::new(Color::Cyan),
+            tag: Paint::PLAIN.dimmed(),
+            punctuation: Paint::PLAIN,
+            annotation: Paint::new(Color::Red),
+            muted: Paint::PLAIN.dimmed(),
+            comment: Paint::new(Color::BrightBlack),
+        }
+    }
+}
+
+impl Theme {
+    /// A theme that leaves every token uncolored, which is used when colors are disabled.
+    pub const UNCOLORED: Theme = Theme {
+        key: Paint::PLAIN,
+        string: Paint::PLAIN,
+        number: Paint::PLAIN,
+        special_number: Paint::PLAIN,
+        boolean: Paint::PLAIN,
+        null: Paint::PLAIN,
+        datetime: Paint::PLAIN,
+        tag: Paint::PLAIN,
+        punctuation: Paint::PLAIN,
+        annotation: Paint::PLAIN,
+        muted: Paint::PLAIN,
+        comment: Paint::PLAIN,
+    };
+
+    /// Returns the default theme with colors that can be read on the background.
+    ///
+    /// Yellow and bright magenta are hard to read on light backgrounds, so they are replaced
+    /// with magenta.
+    pub fn for_background(background: Background) -> Self {
+        match background {
+            Background::Dark => Theme::default(),
+            Background::Light => Theme {
+                special_number: Paint::new(Color::Magenta),
+                boolean: Paint::new(Color::Magenta),
+                ..Theme::default()
+            },
+        }
+    }
+
+    /// Returns the built-in theme with the name, one of [`PRESETS`].
+    pub fn preset(name: &str) -> Option<Self> {
+        let theme = match name {
+            "default" => Theme::default(),
+            "monokai" => Theme {
+                key: Paint::rgb(0xf92672),
+                string: Paint::rgb(0xe6db74),
+                number: Paint::rgb(0xae81ff),
+                special_number: Paint::rgb(0xfd971f),
+                boolean: Paint::rgb(0xae81ff),
+                null: Paint::rgb(0x75715e),
+                datetime: Paint::rgb(0x66d9ef),
+                tag: Paint::rgb(0xa6e22e),
+                punctuation: Paint::rgb(0xf8f8f2),
+                annotation: Paint::rgb(0xf92672),
+                muted: Paint::rgb(0x75715e),
+                comment: Paint::rgb(0x75715e),
+            },
+            "solarized-light" => Theme {
+                key: Paint::rgb(0x268bd2),
+                string: Paint::rgb(0x2aa198),
+                number: Paint::rgb(0xd33682),
+                special_number: Paint::rgb(0xcb4b16),
+                boolean: Paint::rgb(0x6c71c4),
+                null: Paint::rgb(0x93a1a1),
+                datetime: Paint::rgb(0x859900),
+                tag: Paint::rgb(0xb58900),
+                punctuation: Paint::rgb(0x657b83),
+                annotation: Paint::rgb(0xdc322f),
+                muted: Paint::rgb(0x93a1a1),
+                comment: Paint::rgb(0x93a1a1),
+            },
+            "dracula" => Theme {
+                key: Paint::rgb(0x8be9fd),
+                string: Paint::rgb(0xf1fa8c),
+                number: Paint::rgb(0xbd93f9),
+                special_number: Paint::rgb(0xffb86c),
+                boolean: Paint::rgb(0xff79c6),
+                null: Paint::rgb(0x6272a4),
+                datetime: Paint::rgb(0x50fa7b),
+                tag: Paint::rgb(0x50fa7b),
+                punctuation: Paint::rgb(0xf8f8f2),
+                annotation: Paint::rgb(0xff5555),
+                muted: Paint::rgb(0x6272a4),
+                comment: Paint::rgb(0x6272a4),
+            },
+            "plain" => Theme {
+                key: Paint::PLAIN,
+                string: Paint::PLAIN,
+                number: Paint::PLAIN,
+                special_number: Paint::PLAIN,
+                boolean: Paint::PLAIN,
+                null: Paint::PLAIN,
+                datetime: Paint::PLAIN,
+                tag: Paint::PLAIN,
+                punctuation: Paint::PLAIN,
+                annotation: Paint::PLAIN.bold(),
+                muted: Paint::PLAIN,
+                comment: Paint::PLAIN,
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Loads a theme from a TOML file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            VizError::Theme(format!("failed to read theme {}: {e}", path.display()))
+        })?;
+        Theme::parse(&contents)
+            .map_err(|e| VizError::Theme(format!("invalid theme {}: {e}", path.display())))
+    }
+
+    /// Parses a theme, where every key is a kind of token, like `key = "cyan"`.
+    ///
+    /// A color is a name (`bright-blue`), an index in the 256-color palette (`208`)
+    /// or a hex code (`#98c379`). A table like `{ color = "red", bold = true }` sets
+    /// attributes as well. Tokens that are not listed are taken from the `base` theme,
+    /// which is `default` if not set.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let table: Table = contents
+            .parse()
+            .map_err(|e: toml::de::Error| VizError::Theme(e.to_string()))?;
+
+        let mut theme = match table.get("base") {
+            None => Theme::default(),
+            Some(Value::String(name)) => Theme::preset(name)
+                .ok_or_else(|| theme_error(format!("unknown base theme `{name}`")))?,
+            Some(_) => return Err(theme_error("`base` must be the name of a built-in theme")),
+        };
+
+        for (token, value) in table.iter().filter(|(token, _)| *token != "base") {
+            let paint = theme
+                .token_mut(token)
+                .ok_or_else(|| theme_error(format!("unknown token `{token}`")))?;
+            *paint = parse_paint(value).map_err(|e| theme_error(format!("`{token}`: {e}")))?;
+        }
+
+        Ok(theme)
+    }
+
+    /// Returns the paint of the token by its name in theme files, like `special-number`.
+    pub fn token_mut(&mut self, token: &str) -> Option<&mut Paint> {
+        let paint = match token {
+            "key" => &mut self.key,
+            "string" => &mut self.string,
+            "number" => &mut self.number,
+            "special-number" => &mut self.special_number,
+            "boolean" => &mut self.boolean,
+            "null" => &mut self.null,
+            "datetime" => &mut self.datetime,
+            "tag" => &mut self.tag,
+            "punctuation" => &mut self.punctuation,
+            "annotation" => &mut self.annotation,
+            "muted" => &mut self.muted,
+            "comment" => &mut self.comment,
+            _ => return None,
+        };
+        Some(paint)
+    }
+}
+
+fn theme_error(message: impl Into<String>) -> VizError {
+    VizError::Theme(message.into())
+}
+
+/// Parses a paint, either a color or a table with a color and attributes.
+fn parse_paint(value: &Value) -> Result<Paint> {
+    let Value::Table(table) = value else {
+        return Ok(Paint {
+            color: Some(parse_color_value(value)?),
+            ..Paint::PLAIN
+        });
+    };
+
+    let mut paint = Paint::PLAIN;
+    for (name, value) in table {
+        let flag = || match value {
+            Value::Boolean(flag) => Ok(*flag),
+            _ => Err(theme_error(format!("`{name}` must be true or false"))),
+        };
+        match name.as_str() {
+            "color" => paint.color = Some(parse_color_value(value)?),
+            "bold" => paint.bold = flag()?,
+            "dimmed" => paint.dimmed = flag()?,
+            "italic" => paint.italic = flag()?,
+            _ => return Err(theme_error(format!("unknown attribute `{name}`"))),
+        }
+    }
+    Ok(paint)
+}
+
+fn parse_color_value(value: &Value) -> Result<Color> {
+    match value {
+        Value::String(color) => parse_color(color),
+        Value::Integer(index) => u8::try_from(*index)
+            .map(indexed_color)
+            .map_err(|_| theme_error(format!("color index {index} is out of 0-255"))),
+        _ => Err(theme_error("expected a color name, an index or a hex code")),
+    }
+}
+
+/// Parses a color name, an index in the 256-color palette or a hex code.
+pub fn parse_color(color: &str) -> Result<Color> {
+    let color = color.trim().to_lowercase();
+
+    if let Some(hex) = color.strip_prefix('#') {
+        let rgb = u32::from_str_radix(hex, 16)
+            .ok()
+            .filter(|_| hex.len() == 6)
+            .ok_or_else(|| theme_error(format!("invalid hex color `#{hex}` (expected #rrggbb)")))?;
+        return Ok(Paint::rgb(rgb).color.unwrap_or(Color::White));
+    }
+    if let Ok(index) = color.parse::<u8>() {
+        return Ok(indexed_color(index));
+    }
+
+    let named = match color.replace(['_', ' '], "-").as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" | "purple" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "bright-black" | "gray" | "grey" => Color::BrightBlack,
+        "bright-red" => Color::BrightRed,
+        "bright-green" => Color::BrightGreen,
+        "bright-yellow" => Color::BrightYellow,
+        "bright-blue" => Color::BrightBlue,
+        "bright-magenta" | "bright-purple" => Color::BrightMagenta,
+        "bright-cyan" => Color::BrightCyan,
+        "bright-white" => Color::BrightWhite,
+        _ => return Err(theme_error(format!("unknown color `{color}`"))),
+    };
+    Ok(named)
+}
+
+/// Converts an index in the xterm 256-color palette to a color.
+fn indexed_color(index: u8) -> Color {
+    const BASIC: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+        Color::BrightBlack,
+        Color::BrightRed,
+        Color::BrightGreen,
+        Color::BrightYellow,
+        Color::BrightBlue,
+        Color::BrightMagenta,
+        Color::BrightCyan,
+        Color::BrightWhite,
+    ];
+
+    match index {
+        0..=15 => BASIC[index as usize],
+        // A 6x6x6 cube of colors.
+        16..=231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let n = index - 16;
+            Color::TrueColor {
+                r: level(n / 36),
+                g: level(n / 6 % 6),
+                b: level(n % 6),
+            }
+        }
+        // A ramp of grays.
+        232..=255 => {
+            let gray = 8 + (index - 232) * 10;
+            Color::TrueColor {
+                r: gray,
+                g: gray,
+                b: gray,
+            }
+        }
+    }
+}
@@ -0,0 +1,886 @@
+use crate::path::{Path, PathSegment};
+use indexmap::IndexMap;
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::fmt;
+use std::mem;
+use std::vec;
+
+/// A global values for Viz.
+///
+/// Strings and keys are stored as [`Cow`], so processors can borrow them
+/// directly from the input buffer instead of allocating a new `String` for each one.
+///
+/// Values are cloned, compared and dropped without recursion, so their nesting is bounded only
+/// by memory.
+#[derive(Debug)]
+pub enum VizValue<'a> {
+    /// Represents string.
+    String(Cow<'a, str>),
+    /// Represents integer.
+    Number(i64),
+    /// Represents float.
+    Float(f64),
+    /// Represents a number exactly as written in the source, used when
+    /// converting it to `i64` or `f64` would change its digits.
+    RawNumber(Cow<'a, str>),
+    /// Represents null field.
+    Null,
+    /// Represents boolean data.
+    Bool(bool),
+    /// Represents array.
+    Array(Vec<VizValue<'a>>),
+    /// Represents object.
+    Object(IndexMap<Cow<'a, str>, VizValue<'a>>),
+    /// Represents a date, time or date-time, like the ones in TOML, as written in the source.
+    DateTime(Cow<'a, str>),
+    /// Represents a value with a custom tag, like `!Ref` in YAML.
+    Tagged(Cow<'a, str>, Box<VizValue<'a>>),
+}
+
+impl<'a> VizValue<'a> {
+    /// Creates a number from its source text.
+    ///
+    /// The text is kept as is unless it survives the conversion to `i64` or `f64`
+    /// and back unchanged, so large integers and precise decimals are not mangled.
+    pub fn from_number(lexeme: Cow<'a, str>) -> Self {
+        if let Ok(n) = lexeme.parse::<i64>()
+            && n.to_string() == lexeme
+        {
+            return VizValue::Number(n);
+        }
+        if let Ok(f) = lexeme.parse::<f64>()
+            && f.to_string() == lexeme
+        {
+            return VizValue::Float(f);
+        }
+        VizValue::RawNumber(lexeme)
+    }
+
+    /// Returns the value as `f64` if it is a number.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            VizValue::Number(n) => Some(*n as f64),
+            VizValue::Float(f) => Some(*f),
+            VizValue::RawNumber(s) => s.parse().ok(),
+            VizValue::Tagged(_, value) => value.as_f64(),
+            _ => None,
+        }
+    }
+
+    /// Converts the value into one that owns all of its strings.
+    pub fn into_owned(self) -> VizValue<'static> {
+        let owned = |s: Cow<str>| Cow::Owned(s.into_owned());
+        build(self, |mut value| match &mut value {
+            VizValue::Array(vec) => Shallow::Array(mem::take(vec)),
+            VizValue::Object(map) => Shallow::Object(
+                mem::take(map)
+                    .into_iter()
+                    .map(|(key, value)| (owned(key), value))
+                    .collect(),
+            ),
+            VizValue::Tagged(tag, inner) => {
+                Shallow::Tagged(owned(mem::take(tag)), mem::take(&mut **inner))
+            }
+            VizValue::String(s) => Shallow::Done(VizValue::String(owned(mem::take(s)))),
+            VizValue::RawNumber(s) => Shallow::Done(VizValue::RawNumber(owned(mem::take(s)))),
+            VizValue::DateTime(s) => Shallow::Done(VizValue::DateTime(owned(mem::take(s)))),
+            VizValue::Number(n) => Shallow::Done(VizValue::Number(*n)),
+            VizValue::Float(f) => Shallow::Done(VizValue::Float(*f)),
+            VizValue::Null => Shallow::Done(VizValue::Null),
+            VizValue::Bool(b) => Shallow::Done(VizValue::Bool(*b)),
+        })
+    }
+
+    /// Returns the value without its tag.
+    pub fn untagged(&self) -> &Self {
+        match self {
+            VizValue::Tagged(_, value) => value.untagged(),
+            value => value,
+        }
+    }
+
+    /// Returns the value at a JSON Pointer, like `/servers/0/name`, where `""` is the value itself.
+    pub fn pointer(&self, pointer: &str) -> Option<&Self> {
+        let mut current = self;
+        for segment in pointer_segments(pointer)? {
+            current = match current.untagged() {
+                VizValue::Object(map) => map.get(segment.as_str())?,
+                VizValue::Array(vec) => vec.get(segment.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    /// Returns the string at a JSON Pointer, or `None` if the value there is not a string.
+    pub fn pointer_str(&self, pointer: &str) -> Option<&str> {
+        match self.pointer(pointer)?.untagged() {
+            VizValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the value at a JSON Pointer for changing it, like [`VizValue::pointer`].
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Self> {
+        let mut current = self;
+        for segment in pointer_segments(pointer)? {
+            current = match current.untagged_mut() {
+                VizValue::Object(map) => map.get_mut(segment.as_str())?,
+                VizValue::Array(vec) => vec.get_mut(segment.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    /// Returns the value without its tag for changing it.
+    pub fn untagged_mut(&mut self) -> &mut Self {
+        match self {
+            VizValue::Tagged(_, value) => value.untagged_mut(),
+            value => value,
+        }
+    }
+
+    /// Compares values for sorting.
+    ///
+    /// Values of different types are ordered as nulls, booleans, numbers, strings, arrays
+    /// and objects. Numbers are compared by their value, and arrays element by element.
+    pub fn compare(&self, other: &VizValue) -> Ordering {
+        let rank = |value: &VizValue| match value {
+            VizValue::Null => 0,
+            VizValue::Bool(_) => 1,
+            VizValue::Number(_) | VizValue::Float(_) | VizValue::RawNumber(_) => 2,
+            VizValue::String(_) | VizValue::DateTime(_) => 3,
+            VizValue::Array(_) => 4,
+            VizValue::Object(_) => 5,
+            VizValue::Tagged(..) => unreachable!("tags are removed before ranking"),
+        };
+        let (a, b) = (self.untagged(), other.untagged());
+
+        match (a, b) {
+            (VizValue::Bool(a), VizValue::Bool(b)) => a.cmp(b),
+            (
+                VizValue::String(a) | VizValue::DateTime(a),
+                VizValue::String(b) | VizValue::DateTime(b),
+            ) => a.cmp(b),
+            (VizValue::Array(a), VizValue::Array(b)) => a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| a.compare(b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (VizValue::Object(a), VizValue::Object(b)) => a.len().cmp(&b.len()),
+            _ if rank(a) == 2 && rank(b) == 2 => match (a, b) {
+                (VizValue::Number(a), VizValue::Number(b)) => a.cmp(b),
+                _ => {
+                    let (a, b) = (
+                        a.as_f64().unwrap_or(f64::NAN),
+                        b.as_f64().unwrap_or(f64::NAN),
+                    );
+                    a.total_cmp(&b)
+                }
+            },
+            _ => rank(a).cmp(&rank(b)),
+        }
+    }
+
+    /// Sorts keys of all objects in the value alphabetically.
+    ///
+    /// Processors keep keys in the source order, so sorting is only done on request.
+    pub fn sort_keys(&mut self) {
+        let mut stack = vec![self];
+
+        while let Some(value) = stack.pop() {
+            match value {
+                VizValue::Array(vec) => stack.extend(vec.iter_mut()),
+                VizValue::Object(map) => {
+                    map.sort_unstable_keys();
+                    stack.extend(map.values_mut());
+                }
+                VizValue::Tagged(_, value) => stack.push(value),
+                _ => {}
+            }
+        }
+    }
+
+    /// Sorts arrays of scalars in the value, numbers by their value and strings alphabetically.
+    ///
+    /// Arrays with arrays or objects in them are kept in their order, as it usually matters.
+    pub fn sort_arrays(&mut self) {
+        let mut stack = vec![self];
+
+        while let Some(value) = stack.pop() {
+            match value {
+                VizValue::Array(vec) => {
+                    let is_scalar = |value: &VizValue| {
+                        !matches!(value.untagged(), VizValue::Array(_) | VizValue::Object(_))
+                    };
+                    if vec.iter().all(is_scalar) {
+                        vec.sort_by(|a, b| a.compare(b));
+                    } else {
+                        stack.extend(vec.iter_mut());
+                    }
+                }
+                VizValue::Object(map) => stack.extend(map.values_mut()),
+                VizValue::Tagged(_, value) => stack.push(value),
+                _ => {}
+            }
+        }
+    }
+
+    /// Visits the value and all values inside of it, parents before their children,
+    /// in the order of the document.
+    ///
+    /// A tagged value is visited once, with its tag, and its children are visited as if
+    /// the tag was not there.
+    ///
+    /// ```
+    /// use vizo_core::{Format, parse};
+    ///
+    /// let value = parse(r#"{"tags": ["a", "b"]}"#, Format::Json).unwrap();
+    /// let mut paths = Vec::new();
+    /// value.walk(|path, _| paths.push(path.to_string()));
+    /// assert_eq!(paths, ["", "/tags", "/tags/0", "/tags/1"]);
+    /// ```
+    pub fn walk<'s>(&'s self, mut visit: impl FnMut(&Path<'s>, &'s VizValue<'a>)) {
+        let mut path = Path::root();
+        let mut stack = vec![(0, None, self)];
+
+        while let Some((depth, segment, value)) = stack.pop() {
+            path.truncate(depth);
+            if let Some(segment) = segment {
+                path.push(segment);
+            }
+            visit(&path, value);
+
+            let depth = path.len();
+            match value.untagged() {
+                VizValue::Array(vec) => stack.extend(
+                    vec.iter()
+                        .enumerate()
+                        .rev()
+                        .map(|(index, value)| (depth, Some(PathSegment::Index(index)), value)),
+                ),
+                VizValue::Object(map) => stack.extend(
+                    map.iter()
+                        .rev()
+                        .map(|(key, value)| (depth, Some(PathSegment::Key(key.as_ref())), value)),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    /// Converts a YAML node into a `VizValue`, taking ownership of its strings.
+    pub fn from_yaml(value: yaml::Yaml) -> Self {
+        match value {
+            yaml::Yaml::Null => VizValue::Null,
+            yaml::Yaml::Boolean(b) => VizValue::Bool(b),
+            yaml::Yaml::Integer(i) => VizValue::Number(i),
+            yaml::Yaml::Real(s) => match s.as_str() {
+                ".nan" | ".NaN" | ".NAN" => VizValue::Float(f64::NAN),
+                ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => {
+                    VizValue::Float(f64::INFINITY)
+                }
+                "-.inf" | "-.Inf" | "-.INF" => VizValue::Float(f64::NEG_INFINITY),
+                _ => VizValue::from_number(Cow::Owned(s)),
+            },
+            yaml::Yaml::String(s) => VizValue::String(Cow::Owned(s)),
+            yaml::Yaml::Array(seq) => {
+                VizValue::Array(seq.into_iter().map(VizValue::from_yaml).collect())
+            }
+            yaml::Yaml::Hash(map) => {
+                let mut object = IndexMap::new();
+                for (k, v) in map {
+                    let key = match k {
+                        yaml::Yaml::String(s) => s,
+                        other => other.as_str().unwrap_or("").to_string(),
+                    };
+                    object.insert(Cow::Owned(key), VizValue::from_yaml(v));
+                }
+                VizValue::Object(object)
+            }
+            _ => VizValue::Null,
+        }
+    }
+}
+
+/// The default value is [`VizValue::Null`], which is what taking a value out of a tree leaves.
+impl Default for VizValue<'_> {
+    fn default() -> Self {
+        VizValue::Null
+    }
+}
+
+impl<'a> Clone for VizValue<'a> {
+    fn clone(&self) -> Self {
+        build(self, |value| match value {
+            VizValue::Array(vec) => Shallow::Array(vec.iter().collect()),
+            VizValue::Object(map) => Shallow::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), value))
+                    .collect(),
+            ),
+            VizValue::Tagged(tag, inner) => Shallow::Tagged(tag.clone(), inner),
+            VizValue::String(s) => Shallow::Done(VizValue::String(s.clone())),
+            VizValue::RawNumber(s) => Shallow::Done(VizValue::RawNumber(s.clone())),
+            VizValue::DateTime(s) => Shallow::Done(VizValue::DateTime(s.clone())),
+            VizValue::Number(n) => Shallow::Done(VizValue::Number(*n)),
+            VizValue::Float(f) => Shallow::Done(VizValue::Float(*f)),
+            VizValue::Null => Shallow::Done(VizValue::Null),
+            VizValue::Bool(b) => Shallow::Done(VizValue::Bool(*b)),
+        })
+    }
+}
+
+/// Objects are equal if they have the same entries, in any order, like [`IndexMap`]s.
+impl PartialEq for VizValue<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let mut stack = vec![(self, other)];
+        while let Some(pair) = stack.pop() {
+            let equal = match pair {
+                (VizValue::Array(a), VizValue::Array(b)) => {
+                    stack.extend(a.iter().zip(b));
+                    a.len() == b.len()
+                }
+                (VizValue::Object(a), VizValue::Object(b)) => {
+                    a.len() == b.len()
+                        && a.iter()
+                            .all(|(key, a)| b.get(key).map(|b| stack.push((a, b))).is_some())
+                }
+                (VizValue::Tagged(tag_a, a), VizValue::Tagged(tag_b, b)) => {
+                    stack.push((a, b));
+                    tag_a == tag_b
+                }
+                (VizValue::String(a), VizValue::String(b))
+                | (VizValue::RawNumber(a), VizValue::RawNumber(b))
+                | (VizValue::DateTime(a), VizValue::DateTime(b)) => a == b,
+                (VizValue::Number(a), VizValue::Number(b)) => a == b,
+                (VizValue::Float(a), VizValue::Float(b)) => a == b,
+                (VizValue::Bool(a), VizValue::Bool(b)) => a == b,
+                (VizValue::Null, VizValue::Null) => true,
+                _ => false,
+            };
+            if !equal {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl Drop for VizValue<'_> {
+    fn drop(&mut self) {
+        // Nested collections are moved out to a stack, so each value is dropped with no children
+        // left to recurse into, while scalars are dropped in place.
+        let mut stack = Vec::new();
+        take_nested(self, &mut stack);
+        while let Some(mut value) = stack.pop() {
+            take_nested(&mut value, &mut stack);
+        }
+    }
+}
+
+/// Moves the children of a value that have children of their own to the stack.
+fn take_nested<'a>(value: &mut VizValue<'a>, stack: &mut Vec<VizValue<'a>>) {
+    let is_nested = |value: &VizValue| match value {
+        VizValue::Array(vec) => !vec.is_empty(),
+        VizValue::Object(map) => !map.is_empty(),
+        VizValue::Tagged(..) => true,
+        _ => false,
+    };
+    match value {
+        VizValue::Array(vec) if vec.iter().any(is_nested) => {
+            stack.extend(vec.drain(..).filter(is_nested))
+        }
+        VizValue::Object(map) if map.values().any(is_nested) => {
+            stack.extend(map.drain(..).map(|(_, value)| value).filter(is_nested))
+        }
+        VizValue::Tagged(_, inner) if is_nested(inner) => stack.push(mem::take(&mut **inner)),
+        _ => {}
+    }
+}
+
+/// A value whose children are still to be built by [`build`].
+enum Shallow<'b, S> {
+    /// A value with no children.
+    Done(VizValue<'b>),
+    Array(Vec<S>),
+    Object(Vec<(Cow<'b, str>, S)>),
+    Tagged(Cow<'b, str>, S),
+}
+
+/// A collection of [`build`] whose children are being built.
+enum Frame<'b, S> {
+    Array(Vec<VizValue<'b>>, vec::IntoIter<S>),
+    Object(
+        IndexMap<Cow<'b, str>, VizValue<'b>>,
+        vec::IntoIter<(Cow<'b, str>, S)>,
+        Cow<'b, str>,
+    ),
+    Tagged(Cow<'b, str>),
+}
+
+/// Builds a value from a tree of sources, like the values it is copied from, without recursion.
+///
+/// Every source is expanded into a finished value or into the sources of its children.
+fn build<'b, S>(root: S, mut expand: impl FnMut(S) -> Shallow<'b, S>) -> VizValue<'b> {
+    let mut stack = Vec::new();
+    let mut source = root;
+    loop {
+        // Goes down to the first value that has no children to build.
+        let value = loop {
+            source = match expand(source) {
+                Shallow::Done(value) => break value,
+                Shallow::Array(items) => {
+                    let mut items = items.into_iter();
+                    let Some(first) = items.next() else {
+                        break VizValue::Array(Vec::new());
+                    };
+                    stack.push(Frame::Array(Vec::with_capacity(items.len() + 1), items));
+                    first
+                }
+                Shallow::Object(entries) => {
+                    let mut entries = entries.into_iter();
+                    let Some((key, first)) = entries.next() else {
+                        break VizValue::Object(IndexMap::new());
+                    };
+                    let map = IndexMap::with_capacity(entries.len() + 1);
+                    stack.push(Frame::Object(map, entries, key));
+                    first
+                }
+                Shallow::Tagged(tag, inner) => {
+                    stack.push(Frame::Tagged(tag));
+                    inner
+                }
+            };
+        };
+
+        // Goes up, putting values into their parents, until a parent has more children to build.
+        let mut value = Some(value);
+        loop {
+            let next = match stack.last_mut() {
+                None => return value.unwrap_or(VizValue::Null),
+                Some(Frame::Array(vec, items)) => {
+                    vec.extend(value.take());
+                    items.next()
+                }
+                Some(Frame::Object(map, entries, key)) => {
+                    if let Some(value) = value.take() {
+                        map.insert(mem::take(key), value);
+                    }
+                    entries.next().map(|(next_key, next)| {
+                        *key = next_key;
+                        next
+                    })
+                }
+                Some(Frame::Tagged(_)) => None,
+            };
+            if let Some(next) = next {
+                source = next;
+                break;
+            }
+            value = Some(match stack.pop() {
+                Some(Frame::Array(vec, _)) => VizValue::Array(vec),
+                Some(Frame::Object(map, ..)) => VizValue::Object(map),
+                Some(Frame::Tagged(tag)) => {
+                    VizValue::Tagged(tag, Box::new(value.take().unwrap_or(VizValue::Null)))
+                }
+                None => unreachable!("the stack was checked to have a frame"),
+            });
+        }
+    }
+}
+
+/// Values are serialized as the data they hold: date-times become strings and tags are dropped.
+/// Numbers that do not fit into `i64` or `f64` keep their digits with serializers that support
+/// them, like the one of `serde_json`.
+impl Serialize for VizValue<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            VizValue::String(s) | VizValue::DateTime(s) => serializer.serialize_str(s),
+            VizValue::Number(n) => serializer.serialize_i64(*n),
+            VizValue::Float(f) => serializer.serialize_f64(*f),
+            VizValue::RawNumber(s) => {
+                if let Ok(n) = s.parse::<i128>() {
+                    serializer.serialize_i128(n)
+                } else if let Ok(n) = s.parse::<u128>() {
+                    serializer.serialize_u128(n)
+                } else if let Ok(n) = s.parse::<serde_json::Number>() {
+                    n.serialize(serializer)
+                } else if let Ok(f) = s.parse::<f64>()
+                    && f.is_finite()
+                {
+                    serializer.serialize_f64(f)
+                } else {
+                    serializer.serialize_str(s)
+                }
+            }
+            VizValue::Null => serializer.serialize_unit(),
+            VizValue::Bool(b) => serializer.serialize_bool(*b),
+            VizValue::Array(vec) => {
+                let mut seq = serializer.serialize_seq(Some(vec.len()))?;
+                for item in vec {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            VizValue::Object(map) => {
+                let mut object = serializer.serialize_map(Some(map.len()))?;
+                for (key, value) in map {
+                    object.serialize_entry(key.as_ref(), value)?;
+                }
+                object.end()
+            }
+            VizValue::Tagged(_, value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl From<serde_json::Value> for VizValue<'static> {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => VizValue::Null,
+            serde_json::Value::Bool(b) => VizValue::Bool(b),
+            serde_json::Value::Number(n) => VizValue::from_number(Cow::Owned(n.to_string())),
+            serde_json::Value::String(s) => VizValue::String(Cow::Owned(s)),
+            serde_json::Value::Array(vec) => {
+                VizValue::Array(vec.into_iter().map(VizValue::from).collect())
+            }
+            serde_json::Value::Object(map) => VizValue::Object(
+                map.into_iter()
+                    .map(|(k, v)| (Cow::Owned(k), VizValue::from(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// NaN and infinities become nulls, like in `serde_json`.
+impl TryFrom<VizValue<'_>> for serde_json::Value {
+    type Error = serde_json::Error;
+
+    fn try_from(value: VizValue<'_>) -> Result<Self, Self::Error> {
+        serde_json::to_value(value)
+    }
+}
+
+/// Splits a JSON Pointer into its unescaped segments, `None` if it is not a pointer.
+fn pointer_segments(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    let segments = pointer.strip_prefix('/')?.split('/');
+    Some(
+        segments
+            .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+/// Escapes a key to be used as a JSON Pointer segment.
+pub fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// The key serde_json uses to pass numbers with arbitrary precision.
+const NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+/// The key toml uses to pass date-times.
+const DATETIME_TOKEN: &str = "$__toml_private_datetime";
+
+/// A segment of the path to the value that is currently deserialized.
+enum Segment<'de> {
+    Index(usize),
+    Key(Cow<'de, str>),
+}
+
+/// Keeps track of duplicate keys and the nesting depth while deserializing a `VizValue`.
+pub struct KeyTracker<'de> {
+    strict: bool,
+    max_depth: usize,
+    segments: RefCell<Vec<Segment<'de>>>,
+    duplicates: RefCell<Vec<String>>,
+    exceeded_depth: Cell<Option<usize>>,
+}
+
+impl<'de> KeyTracker<'de> {
+    /// Creates a new tracker. In strict mode, a duplicate key fails the deserialization.
+    pub fn new(strict: bool) -> Self {
+        Self {
+            strict,
+            max_depth: 0,
+            segments: RefCell::new(Vec::new()),
+            duplicates: RefCell::new(Vec::new()),
+            exceeded_depth: Cell::new(None),
+        }
+    }
+
+    /// Fails the deserialization once arrays and objects are nested deeper than `max_depth`,
+    /// before the rest of the document is read. `0` disables the check.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Returns the depth that exceeded the limit, if the deserialization failed because of it.
+    pub fn exceeded_depth(&self) -> Option<usize> {
+        self.exceeded_depth.get()
+    }
+
+    /// Returns JSON Pointer paths of the duplicate keys that were found.
+    pub fn into_duplicates(self) -> Vec<String> {
+        self.duplicates.into_inner()
+    }
+
+    /// Builds a JSON Pointer to the key in the object that is currently deserialized.
+    fn path_to(&self, key: &str) -> String {
+        let mut path = String::new();
+        for segment in self.segments.borrow().iter() {
+            match segment {
+                Segment::Index(id) => path.push_str(&format!("/{id}")),
+                Segment::Key(key) => path.push_str(&format!("/{}", escape_pointer(key))),
+            }
+        }
+        path.push_str(&format!("/{}", escape_pointer(key)));
+        path
+    }
+
+    /// Checks the depth of the array or object that is about to be read.
+    fn check_depth<E: de::Error>(&self) -> Result<(), E> {
+        let depth = self.segments.borrow().len();
+        if self.max_depth != 0 && depth > self.max_depth {
+            self.exceeded_depth.set(Some(depth));
+            return Err(E::custom("nesting limit exceeded"));
+        }
+        Ok(())
+    }
+}
+
+impl<'de> VizValue<'de> {
+    /// Deserializes a value, reporting duplicate keys to the tracker.
+    pub fn deserialize_tracked<D>(
+        deserializer: D,
+        tracker: &KeyTracker<'de>,
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(VizValueVisitor {
+            tracker: Some(tracker),
+        })
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for VizValue<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(VizValueVisitor { tracker: None })
+    }
+}
+
+/// A seed that passes the key tracker down to nested values.
+struct ValueSeed<'t, 'de> {
+    tracker: Option<&'t KeyTracker<'de>>,
+}
+
+impl<'de> DeserializeSeed<'de> for ValueSeed<'_, 'de> {
+    type Value = VizValue<'de>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(VizValueVisitor {
+            tracker: self.tracker,
+        })
+    }
+}
+
+/// A visitor that builds a `VizValue`, borrowing strings from the input when possible.
+struct VizValueVisitor<'t, 'de> {
+    tracker: Option<&'t KeyTracker<'de>>,
+}
+
+impl<'de> Visitor<'de> for VizValueVisitor<'_, 'de> {
+    type Value = VizValue<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid structured data value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(VizValue::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(VizValue::Number(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(i64::try_from(v)
+            .map(VizValue::Number)
+            .unwrap_or(VizValue::Float(v as f64)))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(VizValue::Float(v))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(VizValue::String(Cow::Borrowed(v)))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(VizValue::String(Cow::Owned(v.to_string())))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(VizValue::String(Cow::Owned(v)))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(VizValue::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(VizValue::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let seed = || ValueSeed {
+            tracker: self.tracker,
+        };
+        let mut vec = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+        let Some(tracker) = self.tracker else {
+            while let Some(item) = seq.next_element_seed(seed())? {
+                vec.push(item);
+            }
+            return Ok(VizValue::Array(vec));
+        };
+
+        tracker.check_depth()?;
+        loop {
+            tracker
+                .segments
+                .borrow_mut()
+                .push(Segment::Index(vec.len()));
+            let item = seq.next_element_seed(seed());
+            tracker.segments.borrow_mut().pop();
+            match item? {
+                Some(item) => vec.push(item),
+                None => break,
+            }
+        }
+        Ok(VizValue::Array(vec))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let seed = || ValueSeed {
+            tracker: self.tracker,
+        };
+        let mut object = IndexMap::with_capacity(map.size_hint().unwrap_or(0));
+        let mut next = map.next_key::<Key>()?;
+
+        // serde_json passes numbers as a map with a single private key to keep their digits,
+        // and toml does the same for date-times.
+        if let Some(Key(key)) = &next {
+            if key == NUMBER_TOKEN {
+                let Key(lexeme) = map.next_value::<Key>()?;
+                return Ok(VizValue::from_number(lexeme));
+            }
+            if key == DATETIME_TOKEN {
+                let Key(datetime) = map.next_value::<Key>()?;
+                return Ok(VizValue::DateTime(datetime));
+            }
+        }
+
+        let Some(tracker) = self.tracker else {
+            while let Some(Key(key)) = next {
+                object.insert(key, map.next_value_seed(seed())?);
+                next = map.next_key::<Key>()?;
+            }
+            return Ok(VizValue::Object(object));
+        };
+
+        tracker.check_depth()?;
+        while let Some(Key(key)) = next {
+            tracker
+                .segments
+                .borrow_mut()
+                .push(Segment::Key(key.clone()));
+            let value = map.next_value_seed(seed());
+            tracker.segments.borrow_mut().pop();
+            let value = value?;
+
+            if object.contains_key(&key) {
+                let path = tracker.path_to(&key);
+                if tracker.strict {
+                    return Err(de::Error::custom(format!(
+                        "duplicate key \"{key}\" at {path}"
+                    )));
+                }
+                tracker.duplicates.borrow_mut().push(path);
+            }
+            // The first occurrence keeps its position, but the last value wins.
+            object.insert(key, value);
+            next = map.next_key::<Key>()?;
+        }
+        Ok(VizValue::Object(object))
+    }
+}
+
+/// An object key that borrows from the input when possible.
+struct Key<'a>(Cow<'a, str>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for Key<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(KeyVisitor)
+    }
+}
+
+/// A visitor for [`Key`].
+struct KeyVisitor;
+
+impl<'de> Visitor<'de> for KeyVisitor {
+    type Value = Key<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string key")
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Key(Cow::Borrowed(v)))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Key(Cow::Owned(v.to_string())))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Key(Cow::Owned(v)))
+    }
+}
@@ -0,0 +1,24 @@
+use vizo_core::processors::detect::detect;
+
+#[test]
+fn reads_arrays_of_json_as_json() {
+    for array in [r#"["a", "b"]"#, "[1]", "[1.5]", "[true]", "[1, 2]"] {
+        assert_eq!(detect(array), Some("json"), "{array}");
+    }
+}
+
+#[test]
+fn reads_table_headers_as_toml() {
+    for header in [
+        "[package]",
+        "[[bin]]",
+        "[dependencies.serde]",
+        "[tool.poetry] # comment",
+    ] {
+        assert_eq!(
+            detect(&format!("{header}\na = 1\n")),
+            Some("toml"),
+            "{header}"
+        );
+    }
+}
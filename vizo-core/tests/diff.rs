@@ -0,0 +1,46 @@
+use vizo_core::diff::{diff, to_json_patch, to_merge_patch};
+use vizo_core::emit::to_json;
+use vizo_core::{Format, VizValue, parse};
+
+fn json(text: &str) -> VizValue<'_> {
+    parse(text, Format::Json).unwrap()
+}
+
+fn compact(value: &VizValue) -> String {
+    to_json(value).unwrap().split_whitespace().collect()
+}
+
+#[test]
+fn removes_elements_from_the_end_of_arrays_first() {
+    let old = json(r#"{"a": [1, 2, 3, 4], "b": [1, 2, 3]}"#);
+    let new = json(r#"{"a": [1, 2], "b": [0]}"#);
+
+    let patch = to_json_patch(&diff(&old, &new));
+    assert_eq!(
+        compact(&patch),
+        concat!(
+            r#"[{"op":"remove","path":"/a/3"},{"op":"remove","path":"/a/2"},"#,
+            r#"{"op":"replace","path":"/b/0","value":0},"#,
+            r#"{"op":"remove","path":"/b/2"},{"op":"remove","path":"/b/1"}]"#
+        )
+    );
+}
+
+#[test]
+fn warns_about_keys_that_merge_patches_cannot_set_to_null() {
+    let old = json(r#"{"a": 1, "gone": 2, "c": {"x": 1}}"#);
+    let new = json(r#"{"a": null, "b": null, "c": {"x": 1, "y": null}, "d": [null]}"#);
+
+    let mut warnings = Vec::new();
+    let patch = to_merge_patch(&old, &new, &mut warnings);
+    assert_eq!(
+        compact(&patch),
+        r#"{"gone":null,"a":null,"b":null,"c":{"y":null},"d":[null]}"#
+    );
+    let paths: Vec<_> = warnings.iter().filter_map(|w| w.path.as_deref()).collect();
+    assert_eq!(paths, ["/a", "/b", "/c/y"]);
+
+    let mut warnings = Vec::new();
+    to_merge_patch(&old, &json("null"), &mut warnings);
+    assert!(warnings.is_empty());
+}
@@ -0,0 +1,87 @@
+use vizo_core::flatten::{flatten, parse_properties, unflatten};
+use vizo_core::{VizError, VizValue};
+
+fn unflatten_paths(paths: &[&str]) -> vizo_core::Result<VizValue<'static>> {
+    unflatten(
+        paths
+            .iter()
+            .map(|path| (path.to_string(), VizValue::Number(1))),
+    )
+}
+
+#[test]
+fn rejects_indices_far_past_the_end_of_arrays() {
+    for path in ["a[18446744073709551615]", "a[4000000000]"] {
+        assert!(
+            matches!(unflatten_paths(&[path]), Err(VizError::Invalid { .. })),
+            "{path}"
+        );
+    }
+}
+
+#[test]
+fn fills_skipped_elements_with_nulls() {
+    let value = unflatten_paths(&["a[0]", "a[10]", "a[2]"]).unwrap();
+    let Some(VizValue::Array(items)) = value.pointer("/a") else {
+        panic!("expected an array");
+    };
+    assert_eq!(items.len(), 11);
+    assert_eq!(items[1], VizValue::Null);
+}
+
+#[test]
+fn round_trips_keys_with_brackets_and_quotes() {
+    let document = r#"{"k]x": 1, "a": {"[0]": 2, "\"q\\": 3, "b.c": 4, "": 5}}"#;
+    let value = vizo_core::parse(document, vizo_core::Format::Json).unwrap();
+    let flat = flatten(&value);
+    assert!(flat.contains_key(r#"["k]x"]"#), "{:?}", flat.keys());
+    assert!(flat.contains_key(r#"a["[0]"]"#), "{:?}", flat.keys());
+    assert_eq!(unflatten(flat).unwrap(), value);
+}
+
+#[test]
+fn reads_continuation_lines_of_properties() {
+    let text = "a = one, \\\n    two, \\\r\n\tthree\nb = ends with a backslash \\\\\nc = 1\n# d = \\\ne = \\\n";
+    let properties = parse_properties(text).unwrap();
+    assert_eq!(
+        properties.get("a"),
+        Some(&VizValue::String("one, two, three".into()))
+    );
+    assert_eq!(
+        properties.get("b"),
+        Some(&VizValue::String("ends with a backslash \\".into()))
+    );
+    assert_eq!(properties.get("c"), Some(&VizValue::Number(1)));
+    assert_eq!(
+        properties.get("e"),
+        Some(&VizValue::String(String::new().into()))
+    );
+    assert_eq!(properties.len(), 4);
+}
+
+#[test]
+fn reads_unicode_escapes_of_properties() {
+    let properties =
+        parse_properties("caf\\u00e9 = \\u00fcber\nsmile = \\uD83D\\uDE00\\u0021\n").unwrap();
+    assert_eq!(
+        properties.get("café"),
+        Some(&VizValue::String("über".into()))
+    );
+    assert_eq!(
+        properties.get("smile"),
+        Some(&VizValue::String("😀!".into()))
+    );
+
+    for text in [
+        "a = \\u00",
+        "a = \\u00zz",
+        "a = \\u+0ab",
+        "a = \\uD83D\\u0021",
+        "a = \\uDE00",
+    ] {
+        assert!(
+            matches!(parse_properties(text), Err(VizError::Invalid { .. })),
+            "{text}"
+        );
+    }
+}
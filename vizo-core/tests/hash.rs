@@ -0,0 +1,73 @@
+use vizo_core::hash::{Algorithm, canonical, hash};
+use vizo_core::{Format, parse};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Messages of FIPS 180-4 and ones at the edges of the padding: a message of 55 bytes
+/// still fits its length into its last block of SHA-256 and one of 56 bytes does not,
+/// and the same goes for 111 and 112 bytes with SHA-512.
+fn messages() -> [String; 6] {
+    [
+        String::new(),
+        "abc".to_string(),
+        "a".repeat(55),
+        "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".to_string(),
+        "a".repeat(111),
+        "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu".to_string(),
+    ]
+}
+
+#[test]
+fn computes_known_sha256_digests() {
+    let digests = [
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        "9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318",
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        "6374f73208854473827f6f6a3f43b1f53eaa3b82c21c1a6d69a2110b2a79baad",
+        "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1",
+    ];
+    for (message, digest) in messages().iter().zip(digests) {
+        let actual = hex(&Algorithm::Sha256.digest(message.as_bytes()));
+        assert_eq!(actual, digest, "{} bytes", message.len());
+    }
+}
+
+#[test]
+fn computes_known_sha512_digests() {
+    let digests = [
+        "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        "b0220c772cbf6c1822e2cb38a437d0e1d58772417a4bbb21c961364f8b6143e05aa6316dca8d1d7b19e16448419076395f6086cb55101fbd6d5497b148e1745f",
+        "204a8fc6dda82f0a0ced7beb8e08a41657c16ef468b228a8279be331a703c33596fd15c13b1b07f9aa1d3bea57789ca031ad85c7a71dd70354ec631238ca3445",
+        "fa9121c7b32b9e01733d034cfc78cbf67f926c7ed83e82200ef86818196921760b4beff48404df811b953828274461673c68d04e297b0eb7b2b4d60fc6b566a2",
+        "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+    ];
+    for (message, digest) in messages().iter().zip(digests) {
+        let actual = hex(&Algorithm::Sha512.digest(message.as_bytes()));
+        assert_eq!(actual, digest, "{} bytes", message.len());
+    }
+}
+
+#[test]
+fn hashes_the_same_numbers_alike_in_every_format() {
+    let documents = [
+        (r#"{"a": 1, "b": 1.0, "c": 1e0}"#, Format::Json),
+        ("a: 1\nb: 1.0\nc: 1e0\n", Format::Yaml),
+        ("a = 1\nb = 1.0\nc = 1e0\n", Format::Toml),
+        ("c = 1.0\nb = 1e0\na = 1\n", Format::Toml),
+    ];
+    for (document, format) in documents {
+        let value = parse(document, format).unwrap();
+        assert_eq!(canonical(&value), r#"{"a":1,"b":1,"c":1}"#, "{document}");
+        assert_eq!(
+            hash(&value, Algorithm::Sha256),
+            hash(
+                &parse(r#"{"c":1,"b":1,"a":1}"#, Format::Json).unwrap(),
+                Algorithm::Sha256
+            )
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use vizo_core::processors::Warning;
+use vizo_core::processors::lenient::{fix_json, fix_yaml};
+
+fn fix(
+    fixer: fn(&str, &mut Vec<Warning>) -> Option<String>,
+    data: &str,
+) -> (Option<String>, Vec<String>) {
+    let mut warnings = Vec::new();
+    let fixed = fixer(data, &mut warnings);
+    (fixed, warnings.into_iter().map(|w| w.message).collect())
+}
+
+#[test]
+fn leaves_valid_json_as_is() {
+    let (fixed, warnings) = fix(fix_json, r#"{"url": "http://a/*b*/", "c": "// x,]"}"#);
+    assert_eq!(fixed, None);
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn strips_comments_and_trailing_commas_in_place() {
+    let data = "{\n  // a comment\n  \"a\": [1, 2,], /* b */\n  \"c\": \"/* kept */\",\n}";
+    let (fixed, warnings) = fix(fix_json, data);
+    let fixed = fixed.unwrap();
+
+    // Every line keeps its length, so errors still point to the source.
+    for (fixed, line) in fixed.lines().zip(data.lines()) {
+        assert_eq!(fixed.len(), line.len(), "{line}");
+    }
+    let value: serde_json::Value = serde_json::from_str(&fixed).unwrap();
+    assert_eq!(value["c"], "/* kept */");
+    assert_eq!(value["a"], serde_json::json!([1, 2]));
+    assert_eq!(warnings.len(), 4, "{warnings:?}");
+    assert!(warnings[0].starts_with("line 2: "), "{warnings:?}");
+}
+
+#[test]
+fn quotes_special_floats() {
+    let (fixed, _) = fix(fix_json, r#"[NaN, -Infinity, "NaN"]"#);
+    let value: serde_json::Value = serde_json::from_str(&fixed.unwrap()).unwrap();
+    assert_eq!(value[0], "\u{0}NaN");
+    assert_eq!(value[1], "\u{0}-Infinity");
+    assert_eq!(value[2], "NaN");
+}
+
+#[test]
+fn replaces_tabs_only_if_every_indentation_uses_them() {
+    let (fixed, warnings) = fix(fix_yaml, "a:\n\tb: 1\n\tc:\n\t\t- 2\n");
+    assert_eq!(fixed.unwrap(), "a:\n  b: 1\n  c:\n    - 2\n");
+    assert_eq!(warnings.len(), 1);
+
+    let (fixed, _) = fix(fix_yaml, "a:\n\tb: 1\n  c: 2\n");
+    assert_eq!(fixed, None);
+}
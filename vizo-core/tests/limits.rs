@@ -0,0 +1,45 @@
+use vizo_core::processors::ParseOptions;
+use vizo_core::{Format, VizError, parse_with};
+
+/// Arrays nested `depth` levels deep in the format, so the innermost one is at `depth`.
+fn nested(format: Format, depth: usize) -> String {
+    match format {
+        Format::Json => format!("{}{}", "[".repeat(depth + 1), "]".repeat(depth + 1)),
+        Format::Toml => format!("a = {}{}\n", "[".repeat(depth), "]".repeat(depth)),
+        // Flow collections are limited by the YAML parser, so blocks are used instead.
+        Format::Yaml => {
+            let mut yaml: String = (0..depth)
+                .map(|id| format!("{}a:\n", " ".repeat(id)))
+                .collect();
+            yaml.push_str(&format!("{}[]\n", " ".repeat(depth)));
+            yaml
+        }
+    }
+}
+
+fn parse_nested(format: Format, depth: usize, max_depth: usize) -> vizo_core::Result<()> {
+    let options = ParseOptions {
+        max_depth,
+        ..ParseOptions::default()
+    };
+    parse_with(&nested(format, depth), format, &options, &mut Vec::new()).map(|_| ())
+}
+
+#[test]
+fn parses_documents_deeper_than_the_recursion_limits_of_parsers() {
+    for format in [Format::Json, Format::Toml, Format::Yaml] {
+        assert!(parse_nested(format, 300, 512).is_ok(), "{format:?}");
+        assert!(parse_nested(format, 512, 512).is_ok(), "{format:?}");
+    }
+}
+
+#[test]
+fn fails_with_a_limit_error_just_above_the_max_depth() {
+    for format in [Format::Json, Format::Toml, Format::Yaml] {
+        assert!(parse_nested(format, 400, 400).is_ok(), "{format:?}");
+        assert!(
+            matches!(parse_nested(format, 401, 400), Err(VizError::Limit(_))),
+            "{format:?}"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use vizo_core::query::query;
+use vizo_core::{Format, parse};
+
+fn paths(expression: &str) -> Vec<String> {
+    let value = parse(r#"{"a": [0, 1, 2, 3, 4]}"#, Format::Json).unwrap();
+    query(&value, expression)
+        .unwrap()
+        .into_iter()
+        .map(|(path, _)| path.to_string())
+        .collect()
+}
+
+#[test]
+fn selects_slices_of_arrays() {
+    assert_eq!(paths("$.a[1:3]"), ["/a/1", "/a/2"]);
+    assert_eq!(paths("$.a[3:]"), ["/a/3", "/a/4"]);
+    assert_eq!(paths("$.a[:2]"), ["/a/0", "/a/1"]);
+    assert_eq!(paths("$.a[-2:]"), ["/a/3", "/a/4"]);
+    assert_eq!(paths("$.a[:-3]"), ["/a/0", "/a/1"]);
+    assert_eq!(paths("$.a[3:9]"), ["/a/3", "/a/4"]);
+    assert!(paths("$.a[3:1]").is_empty());
+    assert!(paths("$.a[-9:-7]").is_empty());
+}
+
+#[test]
+fn counts_negative_indices_from_the_end() {
+    assert_eq!(paths("$.a[-1]"), ["/a/4"]);
+    assert_eq!(paths("$.a[-5]"), ["/a/0"]);
+    assert_eq!(paths("a[0, -1]"), ["/a/0", "/a/4"]);
+    assert!(paths("$.a[-6]").is_empty());
+    assert!(paths("$.a[5]").is_empty());
+}
@@ -0,0 +1,30 @@
+use vizo_core::schema::validate;
+use vizo_core::{Format, parse};
+
+fn is_valid(schema: &str, value: &str) -> bool {
+    let schema = parse(schema, Format::Json).unwrap();
+    let value = parse(value, Format::Json).unwrap();
+    validate(&schema, &value).is_empty()
+}
+
+#[test]
+fn accepts_multiples_of_fractions() {
+    for value in ["0.3", "0.7", "1.1", "3", "-0.9", "1e3"] {
+        assert!(is_valid(r#"{"multipleOf": 0.1}"#, value), "{value}");
+    }
+    for value in ["0.35", "0.01"] {
+        assert!(!is_valid(r#"{"multipleOf": 0.1}"#, value), "{value}");
+    }
+    assert!(!is_valid(r#"{"multipleOf": 3}"#, "10"));
+}
+
+#[test]
+fn compares_numbers_of_enums_and_constants_by_value() {
+    assert!(is_valid(r#"{"enum": [1.0, "a"]}"#, "1"));
+    assert!(is_valid(
+        r#"{"const": {"a": [1, 2.0]}}"#,
+        r#"{"a": [1.0, 2]}"#
+    ));
+    assert!(!is_valid(r#"{"enum": [1.5]}"#, "1"));
+    assert!(!is_valid(r#"{"const": [1, 2]}"#, "[1, 2, 3]"));
+}
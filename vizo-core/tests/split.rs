@@ -0,0 +1,40 @@
+use vizo_core::split::split_documents;
+
+#[test]
+fn splits_yaml_streams_at_markers() {
+    let text = "---\na: 1\n--- {b: 2}\n---\n\n---\nc: 3\n---\n";
+    assert_eq!(
+        split_documents(text, "yaml", None),
+        ["---\na: 1\n", "--- {b: 2}\n", "---\nc: 3\n"]
+    );
+}
+
+#[test]
+fn splits_concatenated_json_values() {
+    let text = "{\"a\": 1}{\"a\": 2}\n[3]\n\"x\" 4";
+    assert_eq!(
+        split_documents(text, "json", None),
+        ["{\"a\": 1}", "{\"a\": 2}", "\n[3]", "\n\"x\"", " 4"]
+    );
+    // The invalid rest is kept, so its error is reported.
+    assert_eq!(
+        split_documents("{\"a\": 1} {\"a\":", "json", None),
+        ["{\"a\": 1}", " {\"a\":"]
+    );
+}
+
+#[test]
+fn splits_at_nul_characters_and_delimiters() {
+    assert_eq!(
+        split_documents("a = 1\0\0b = 2", "toml", None),
+        ["a = 1", "b = 2"]
+    );
+    assert_eq!(
+        split_documents("a = 1\n%%\nb = 2\n  %%  \n", "toml", Some("%%")),
+        ["a = 1\n", "b = 2\n"]
+    );
+    assert_eq!(
+        split_documents("a = 1\n\n\nb = 2\n", "toml", None),
+        ["a = 1\n", "b = 2\n"]
+    );
+}
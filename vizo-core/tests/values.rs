@@ -0,0 +1,50 @@
+use vizo_core::processors::ParseOptions;
+use vizo_core::{Format, RenderOptions, VizValue, parse_with, render};
+
+#[test]
+fn parses_renders_and_drops_documents_nested_100k_levels_deep() {
+    let depth = 100_000;
+    let json = format!("{}{}", r#"{"a":["#.repeat(depth), "]}".repeat(depth));
+    let options = ParseOptions {
+        max_depth: 0,
+        max_nodes: 0,
+        ..ParseOptions::default()
+    };
+    let value = parse_with(&json, Format::Json, &options, &mut Vec::new()).unwrap();
+
+    let copy = value.clone();
+    assert!(copy == value);
+    drop(copy);
+
+    // Without indentation, the output grows with the depth instead of its square.
+    let render_options = RenderOptions::builder().indent(0).build();
+    let output = render(&value.into_owned(), &render_options);
+    assert!(output.lines().count() > 2 * depth);
+}
+
+#[test]
+fn serializes_raw_numbers_that_json_does_not_accept_as_numbers() {
+    for (raw, json) in [(".5", "0.5"), ("+1.5", "1.5"), ("1_000.5", "\"1_000.5\"")] {
+        let value = VizValue::RawNumber(raw.into());
+        assert_eq!(serde_json::to_string(&value).unwrap(), json, "{raw}");
+    }
+}
+
+#[test]
+fn escapes_quotes_and_backslashes_of_quoted_strings() {
+    let value =
+        vizo_core::parse(r#"{"q": "say \"hi\"", "k\\x": "C:\\dir"}"#, Format::Json).unwrap();
+
+    let options = RenderOptions::builder().color(Some(false));
+    let output = render(&value, &options.clone().build());
+    assert_eq!(
+        output.trim_end(),
+        "q = \"say \\\"hi\\\"\"\n\"k\\\\x\" = \"C:\\\\dir\""
+    );
+
+    let output = render(&value, &options.raw_strings(true).build());
+    assert_eq!(
+        output.trim_end(),
+        "q = \"say \"hi\"\"\n\"k\\x\" = \"C:\\dir\""
+    );
+}